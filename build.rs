@@ -5,7 +5,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     tonic_build::configure()
         .file_descriptor_set_path(out_dir.join("message_descriptor.bin"))
-        .compile(&["proto/message.proto"], &["proto"])?;
+        .compile(
+            &["proto/message.proto", "proto/message/v1/message.proto"],
+            &["proto"],
+        )?;
 
     Ok(())
 }
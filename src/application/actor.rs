@@ -0,0 +1,10 @@
+use charybdis::types::{Text, Timeuuid};
+use serde::{Deserialize, Serialize};
+
+/// The user a call is made on behalf of. Permissions are granted by username,
+/// the way topics name their owners, admins and members.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Actor {
+    pub user_id: Timeuuid,
+    pub username: Text,
+}
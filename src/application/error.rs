@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use thiserror::Error;
+use validator::ValidationErrors;
+
+/// Failures a caller can act on, independent of the transport. Interfaces map
+/// each kind onto their own status codes; anything else is an internal error.
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum ApplicationError {
+    #[error("{msg}")]
+    InvalidArgument {
+        msg: String,
+        violations: Vec<FieldViolation>,
+    },
+    #[error("{resource} not found")]
+    NotFound { resource: &'static str },
+    #[error("{msg}")]
+    Unauthenticated { msg: String },
+    #[error("{msg}")]
+    PermissionDenied { msg: String },
+    #[error("{resource} already exists")]
+    AlreadyExists { resource: &'static str },
+    #[error("{msg}")]
+    FailedPrecondition { msg: String },
+    #[error("storage is temporarily unavailable")]
+    Unavailable,
+}
+
+/// One rejected request field, named as in the request type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldViolation {
+    pub field: String,
+    pub description: String,
+}
+
+impl ApplicationError {
+    pub fn invalid_field(field: &str, description: impl Into<String>) -> Self {
+        let description = description.into();
+        Self::InvalidArgument {
+            msg: format!("{field}: {description}"),
+            violations: vec![FieldViolation {
+                field: field.to_owned(),
+                description,
+            }],
+        }
+    }
+
+    /// A conditional write to a topic lost to someone else's change.
+    pub fn topic_changed() -> Self {
+        Self::FailedPrecondition {
+            msg: "the topic was changed by someone else, reload it and try again".to_string(),
+        }
+    }
+
+    /// Stable identifier for clients to branch on, sent alongside the status.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::InvalidArgument { .. } => "INVALID_ARGUMENT",
+            Self::NotFound { .. } => "NOT_FOUND",
+            Self::Unauthenticated { .. } => "UNAUTHENTICATED",
+            Self::PermissionDenied { .. } => "PERMISSION_DENIED",
+            Self::AlreadyExists { .. } => "ALREADY_EXISTS",
+            Self::FailedPrecondition { .. } => "FAILED_PRECONDITION",
+            Self::Unavailable => "UNAVAILABLE",
+        }
+    }
+}
+
+impl From<ValidationErrors> for ApplicationError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut violations: Vec<FieldViolation> = errors
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                errors.iter().map(move |error| FieldViolation {
+                    field: field.to_string(),
+                    description: error
+                        .message
+                        .clone()
+                        .unwrap_or_else(|| Cow::Owned(format!("failed {} validation", error.code)))
+                        .into_owned(),
+                })
+            })
+            .collect();
+        violations.sort_by(|a, b| a.field.cmp(&b.field));
+
+        Self::InvalidArgument {
+            msg: errors.to_string(),
+            violations,
+        }
+    }
+}
@@ -1,67 +1,233 @@
 use super::{
-    response::PublicLatestMessage,
+    response::{PublicLatestMessage, PublicTopicUnread, PublicUnreadSummary},
 };
-use crate::application::latest_message::request::{RequestGetLatestMessagesByUserId, RequestUpdateLatestMessage};
+use crate::application::latest_message::request::{RequestGetLatestMessagesByUserId, RequestGetUnreadCounters, RequestGetUnreadSummary, RequestUpdateLatestMessage};
+use crate::application::topic_message::request::{RequestGetMessagesByTopicId, RequestGetReadCursor};
 use crate::domain::latest_message::{repository::LatestMessageRepository};
-use std::{future::Future, sync::Arc};
+use crate::domain::read_cursor::entity::ReadCursor;
+use crate::domain::read_cursor::repository::ReadCursorRepository;
+use crate::domain::topic_message::repository::TopicMessageRepository;
+use crate::domain::unread_counter::entity::UnreadCounter;
+use crate::domain::unread_counter::repository::UnreadCounterRepository;
+use crate::application::pagination::{Page, MAX_PAGE_SIZE};
+use charybdis::types::Timeuuid;
+use std::{collections::HashMap, env, future::Future, sync::Arc, time::Duration};
+use tokio::time::MissedTickBehavior;
 use uptop_core::common::result::AppResult;
 use crate::domain::latest_message::entity::LatestMessage;
 
+/// Most messages past a read cursor looked at when recounting unread ones.
+pub const UNREAD_RECOUNT_LIMIT: usize = 1000;
+
+const DEFAULT_UNREAD_RECONCILE_INTERVAL_SECS: u64 = 60 * 60;
+
+/// How often unread counters are reconciled, read from
+/// `UNREAD_RECONCILE_INTERVAL_SECS`. Zero turns reconciliation off.
+pub fn unread_reconcile_interval() -> Option<Duration> {
+    let secs = env::var("UNREAD_RECONCILE_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_UNREAD_RECONCILE_INTERVAL_SECS);
+    match secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// Reconciles the unread counters every `interval` until the process exits.
+/// A failed round is logged and retried at the next tick.
+pub async fn reconcile_unread_counters_every<L: LatestMessageAppInterface>(latest_message_app: Arc<L>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // The first tick completes immediately; wait a full interval after startup.
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        match latest_message_app.reconcile_unread_counters().await {
+            Ok(repaired) => tracing::info!(repaired, "reconciled unread counters"),
+            Err(err) => tracing::error!("{err:?}"),
+        }
+    }
+}
+
+/// Counts the timeline messages from others after `read_cursor`, or in the
+/// whole topic without one, looking at no more than [`UNREAD_RECOUNT_LIMIT`]
+/// messages. The flag tells whether it stopped before the end.
+pub(crate) async fn count_unread<TM: TopicMessageRepository>(
+    topic_message_repo: &TM,
+    topic_id: Timeuuid,
+    user_id: Timeuuid,
+    read_cursor: Option<&ReadCursor>,
+) -> AppResult<(i64, bool)> {
+    let mut query = RequestGetMessagesByTopicId {
+        topic_id,
+        page_size: Some(MAX_PAGE_SIZE),
+        page_token: None,
+        before: None,
+        after: read_cursor.map(|read_cursor| read_cursor.last_read_message_id),
+    };
+    let mut scanned: usize = 0;
+    let mut unread: i64 = 0;
+    loop {
+        let page = topic_message_repo.find_topic_message_by_partition_key(&query).await?;
+        scanned += page.items.len();
+        unread += page
+            .items
+            .iter()
+            .filter(|item| !item.is_deleted() && item.from_user_id != user_id)
+            .count() as i64;
+        match page.next_page_token {
+            Some(page_token) if scanned < UNREAD_RECOUNT_LIMIT => query.page_token = Some(page_token),
+            Some(_) => return Ok((unread, true)),
+            None => return Ok((unread, false)),
+        }
+    }
+}
+
 pub trait LatestMessageAppInterface: Clone + Send + Sync + 'static {
+    /// The latest message of each of the user's topics, with how many
+    /// messages they have not read there yet.
     fn find_list_latest_messages_by_user_id(
         &self,
         query: &RequestGetLatestMessagesByUserId,
-    ) -> impl Future<Output=AppResult<Vec<PublicLatestMessage>>> + Send;
+    ) -> impl Future<Output=AppResult<Page<PublicLatestMessage>>> + Send;
 
     fn update_latest_message(
         &self,
         latest_message: &RequestUpdateLatestMessage,
     ) -> impl Future<Output=AppResult<PublicLatestMessage>> + Send;
+
+    fn find_unread_summary(
+        &self,
+        query: &RequestGetUnreadSummary,
+    ) -> impl Future<Output=AppResult<PublicUnreadSummary>> + Send;
+
+    /// Recounts every unread counter from its user's read cursor and repairs
+    /// the ones that drifted, returning how many were. Counters past
+    /// [`UNREAD_RECOUNT_LIMIT`] cannot be recounted and are only raised.
+    fn reconcile_unread_counters(&self) -> impl Future<Output=AppResult<usize>> + Send;
 }
 
 #[derive(Clone, Debug)]
-pub struct LatestMessageApp<TP>
+pub struct LatestMessageApp<TP, UC, RC, TM>
 where
     TP: LatestMessageRepository,
+    UC: UnreadCounterRepository,
+    RC: ReadCursorRepository,
+    TM: TopicMessageRepository,
 {
     latest_message_repo: Arc<TP>,
+    unread_counter_repo: Arc<UC>,
+    read_cursor_repo: Arc<RC>,
+    topic_message_repo: Arc<TM>,
 }
 
-impl<TP> LatestMessageApp<TP>
+impl<TP, UC, RC, TM> LatestMessageApp<TP, UC, RC, TM>
 where
     TP: LatestMessageRepository,
+    UC: UnreadCounterRepository,
+    RC: ReadCursorRepository,
+    TM: TopicMessageRepository,
 {
-    pub fn new(latest_message_repo: Arc<TP>) -> Self {
-        Self { latest_message_repo }
+    pub fn new(
+        latest_message_repo: Arc<TP>,
+        unread_counter_repo: Arc<UC>,
+        read_cursor_repo: Arc<RC>,
+        topic_message_repo: Arc<TM>,
+    ) -> Self {
+        Self {
+            latest_message_repo,
+            unread_counter_repo,
+            read_cursor_repo,
+            topic_message_repo,
+        }
     }
 }
 
-impl<TP> LatestMessageAppInterface for LatestMessageApp<TP>
+impl<TP, UC, RC, TM> LatestMessageAppInterface for LatestMessageApp<TP, UC, RC, TM>
 where
     TP: LatestMessageRepository,
+    UC: UnreadCounterRepository,
+    RC: ReadCursorRepository,
+    TM: TopicMessageRepository,
 {
     async fn find_list_latest_messages_by_user_id(
         &self,
         query: &RequestGetLatestMessagesByUserId,
-    ) -> AppResult<Vec<PublicLatestMessage>> {
-        let mut result: Vec<PublicLatestMessage> = vec![];
-        self.latest_message_repo
+    ) -> AppResult<Page<PublicLatestMessage>> {
+        let page = self.latest_message_repo
             .find_latest_message_by_partition_key(query)
-            .await
-            .map(|ref latest_message: Vec<LatestMessage>| {
-                for item in latest_message.iter() {
-                    result.push(item.try_into().expect("Can not parse data!"));
-                }
+            .await?;
+
+        let summary_query = RequestGetUnreadSummary { user_id: query.user_id };
+        let unread: HashMap<Timeuuid, i64> = self.unread_counter_repo
+            .find_unread_counters_by_user_id(&summary_query)
+            .await?
+            .into_iter()
+            .map(|counter| (counter.topic_id, counter.unread))
+            .collect();
+
+        page.try_map(|item: &LatestMessage| {
+            let unread_count = unread.get(&item.topic_id).copied().unwrap_or_default();
+            Ok(PublicLatestMessage {
+                unread_count: unread_count.clamp(0, i32::MAX as i64) as i32,
+                ..PublicLatestMessage::try_from(item)?
             })
-            .expect("TODO: panic message");
-        Ok(result)
+        })
     }
 
     async fn update_latest_message(&self, latest_message: &RequestUpdateLatestMessage) -> AppResult<PublicLatestMessage> {
-        self.latest_message_repo
-            .update_latest_message(latest_message)
-            .await
-            .map(|latest_message| PublicLatestMessage::try_from(&latest_message).unwrap())
+        let latest_message = self.latest_message_repo.update_latest_message(latest_message).await?;
+        PublicLatestMessage::try_from(&latest_message)
+    }
+
+    async fn find_unread_summary(&self, query: &RequestGetUnreadSummary) -> AppResult<PublicUnreadSummary> {
+        let counters = self.unread_counter_repo.find_unread_counters_by_user_id(query).await?;
+        let mut topics: Vec<PublicTopicUnread> = vec![];
+        for counter in counters.iter().filter(|counter| counter.unread > 0) {
+            topics.push(PublicTopicUnread::try_from(counter)?);
+        }
+        let total_unread = topics.iter().map(|topic| topic.unread_count).sum();
+        Ok(PublicUnreadSummary { topics, total_unread })
+    }
+
+    async fn reconcile_unread_counters(&self) -> AppResult<usize> {
+        let mut repaired: usize = 0;
+        let mut query = RequestGetUnreadCounters {
+            page_size: Some(MAX_PAGE_SIZE),
+            page_token: None,
+        };
+        loop {
+            let page = self.unread_counter_repo.find_unread_counters(&query).await?;
+            for counter in page.items.iter() {
+                let cursor_query = RequestGetReadCursor {
+                    topic_id: counter.topic_id,
+                    user_id: counter.user_id,
+                };
+                let read_cursor = self.read_cursor_repo.find_read_cursor(&cursor_query).await?;
+                let (unread, capped) = count_unread(
+                    &*self.topic_message_repo,
+                    counter.topic_id,
+                    counter.user_id,
+                    read_cursor.as_ref(),
+                )
+                .await?;
+                if counter.unread == unread || (capped && counter.unread > unread) {
+                    continue;
+                }
+
+                let reconciled = UnreadCounter {
+                    unread,
+                    ..counter.clone()
+                };
+                self.unread_counter_repo.reset_unread_counter(&reconciled).await?;
+                repaired += 1;
+            }
+            match page.next_page_token {
+                Some(page_token) => query.page_token = Some(page_token),
+                None => return Ok(repaired),
+            }
+        }
     }
 
     // async fn get_full_field_latest_message(&self, query: &RequestGetLatestMessageByLatestMessageName) -> AppResult<LatestMessage> {
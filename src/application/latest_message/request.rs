@@ -1,9 +1,10 @@
 use anyhow::bail;
 use charybdis::types::{Timeuuid};
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
-use uptop_core::common::result::{AppError, AppResult};
+use uptop_core::common::result::AppResult;
 use validator::Validate;
+use crate::application::pagination::MAX_PAGE_SIZE;
+use crate::application::error::ApplicationError;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestUpdateLatestMessage {
@@ -11,6 +12,7 @@ pub struct RequestUpdateLatestMessage {
     #[validate(length(min = 3))]
     pub latest_message_content: String,
     pub topic_id: Timeuuid,
+    #[serde(default)]
     pub user_id: Timeuuid,
 }
 
@@ -18,9 +20,7 @@ impl RequestUpdateLatestMessage {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
@@ -34,26 +34,43 @@ impl RequestUpdateLatestMessage {
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestGetLatestMessagesByUserId {
+    #[serde(default)]
     pub user_id: Timeuuid,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
 }
 
 impl RequestGetLatestMessagesByUserId {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
             user_id: self.user_id,
+            page_size: self.page_size,
+            page_token: self.page_token,
         })
     }
 }
 
-#[derive(Debug, Error)]
-pub enum RequestFindLatestMessageError {
-    #[error("LatestMessage not found")]
-    LatestMessageNotFound,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetLatestMessage {
+    pub user_id: Timeuuid,
+    pub topic_id: Timeuuid,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetUnreadSummary {
+    #[serde(default)]
+    pub user_id: Timeuuid,
+}
+
+/// A page of the counters of every user, for reconciliation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetUnreadCounters {
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
 }
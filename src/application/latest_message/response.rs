@@ -2,6 +2,7 @@ use charybdis::types::{Text, Timeuuid};
 use serde::{Deserialize, Serialize};
 use uptop_core::common::result::AppResult;
 use crate::domain::latest_message::entity::LatestMessage;
+use crate::domain::unread_counter::entity::UnreadCounter;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PublicLatestMessage {
@@ -9,6 +10,8 @@ pub struct PublicLatestMessage {
     pub latest_message_content: Text,
     pub topic_id: Timeuuid,
     pub user_id: Timeuuid,
+    /// Messages from others the user has not read yet; only filled when listing.
+    pub unread_count: i32,
 }
 
 impl TryFrom<&LatestMessage> for PublicLatestMessage {
@@ -20,6 +23,32 @@ impl TryFrom<&LatestMessage> for PublicLatestMessage {
             topic_id: latest_message.topic_id,
             user_id: latest_message.user_id,
             latest_message_content: (*latest_message.latest_message_content).parse()?,
+            unread_count: 0,
+        })
+    }
+}
+
+/// Unread counts of every topic of a user that has any, and their sum.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicUnreadSummary {
+    pub topics: Vec<PublicTopicUnread>,
+    pub total_unread: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicTopicUnread {
+    pub topic_id: Timeuuid,
+    pub unread_count: i64,
+}
+
+impl TryFrom<&UnreadCounter> for PublicTopicUnread {
+    type Error = anyhow::Error;
+
+    fn try_from(counter: &UnreadCounter) -> AppResult<Self> {
+        Ok(Self {
+            topic_id: counter.topic_id,
+            // Racing resets can briefly push a counter below zero until reconciled.
+            unread_count: counter.unread.max(0),
         })
     }
 }
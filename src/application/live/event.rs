@@ -0,0 +1,80 @@
+use crate::application::notification::response::PublicNotification;
+use crate::application::topic_message::response::PublicTopicMessage;
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use serde::{Deserialize, Serialize};
+
+/// A change pushed to live subscribers of a topic.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TopicEvent {
+    MessageCreated(PublicTopicMessage),
+    MessageUpdated(PublicTopicMessage),
+    MessageDeleted(PublicTopicMessage),
+    Typing(TypingIndicator),
+    ReactionChanged(ReactionChange),
+    Presence(PresenceUpdate),
+    PinChanged(PinChange),
+    /// Only delivered to the subscriptions of the notified user.
+    Notification(PublicNotification),
+}
+
+/// Someone is typing, until `expires_at` unless repeated. Subscribers get a
+/// `stopped` indicator when they stop explicitly or the signal lapses.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TypingIndicator {
+    pub topic_id: Timeuuid,
+    pub username: Text,
+    pub stopped: bool,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// A member of the topic went online, away or offline.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PresenceUpdate {
+    pub topic_id: Timeuuid,
+    pub username: Text,
+    pub status: Text,
+    pub last_seen_at: Timestamp,
+}
+
+/// A user added or removed a reaction; `count` is the emoji's total on the message afterwards.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReactionChange {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub user_id: Timeuuid,
+    pub emoji: Text,
+    pub added: bool,
+    pub count: i32,
+}
+
+/// A message was pinned or unpinned by `user_id`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PinChange {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub user_id: Timeuuid,
+    pub pinned: bool,
+}
+
+impl TopicEvent {
+    pub fn topic_id(&self) -> Timeuuid {
+        match self {
+            TopicEvent::MessageCreated(message)
+            | TopicEvent::MessageUpdated(message)
+            | TopicEvent::MessageDeleted(message) => message.topic_id,
+            TopicEvent::Typing(typing) => typing.topic_id,
+            TopicEvent::ReactionChanged(change) => change.topic_id,
+            TopicEvent::Presence(presence) => presence.topic_id,
+            TopicEvent::PinChanged(change) => change.topic_id,
+            TopicEvent::Notification(notification) => notification.topic_id,
+        }
+    }
+
+    /// The only user the event is for, when it is not for every reader of the topic.
+    pub fn recipient(&self) -> Option<&str> {
+        match self {
+            TopicEvent::Notification(notification) => Some(&notification.username),
+            _ => None,
+        }
+    }
+}
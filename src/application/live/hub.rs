@@ -0,0 +1,280 @@
+use super::event::TopicEvent;
+use crate::application::error::ApplicationError;
+use anyhow::bail;
+use charybdis::types::{Text, Timeuuid};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::Notify;
+use uptop_core::common::result::AppResult;
+
+/// Events queued for each subscriber before it counts as lagging.
+const DEFAULT_HUB_CAPACITY: usize = 1024;
+
+/// What the hub and a subscription share besides the queue itself.
+#[derive(Debug, Default)]
+struct SubscriberState {
+    /// Events dropped because the queue was full.
+    skipped: AtomicU64,
+    /// The topic the subscriber can no longer read, once revoked.
+    revoked: Mutex<Option<Timeuuid>>,
+    /// Wakes the subscription for either of the above.
+    changed: Notify,
+}
+
+#[derive(Clone, Debug)]
+struct Subscriber {
+    id: u64,
+    username: Text,
+    events: mpsc::Sender<TopicEvent>,
+    state: Arc<SubscriberState>,
+}
+
+#[derive(Debug, Default)]
+struct Subscribers {
+    next_id: u64,
+    by_topic: HashMap<Timeuuid, Vec<Subscriber>>,
+}
+
+/// In-process fan-out of topic events to the live subscribers of each topic.
+/// Every subscriber has its own bounded queue, so one that falls behind only
+/// ends its own subscription, whatever the traffic of the other topics.
+#[derive(Clone, Debug)]
+pub struct TopicEventHub {
+    capacity: usize,
+    subscribers: Arc<Mutex<Subscribers>>,
+}
+
+impl TopicEventHub {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            subscribers: Arc::new(Mutex::new(Subscribers::default())),
+        }
+    }
+
+    pub fn publish(&self, event: TopicEvent) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let Some(topic_subscribers) = subscribers.by_topic.get_mut(&event.topic_id()) else {
+            return;
+        };
+        let recipient = event.recipient();
+        topic_subscribers.retain(|subscriber| {
+            if recipient.is_some_and(|recipient| recipient != subscriber.username) {
+                return true;
+            }
+            match subscriber.events.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    subscriber.state.skipped.fetch_add(1, Ordering::SeqCst);
+                    subscriber.state.changed.notify_one();
+                    true
+                }
+                Err(TrySendError::Closed(_)) => false,
+            }
+        });
+    }
+
+    /// Ends the subscriptions of `username` to the topic, or of everyone when
+    /// `None`. Called whenever someone may have lost `Read` there, since a
+    /// subscription is only authorized when it starts.
+    pub fn revoke_access(&self, topic_id: Timeuuid, username: Option<&str>) {
+        let subscribers = self.subscribers.lock().unwrap();
+        let Some(topic_subscribers) = subscribers.by_topic.get(&topic_id) else {
+            return;
+        };
+        for subscriber in topic_subscribers {
+            if username.is_none_or(|username| username == subscriber.username) {
+                *subscriber.state.revoked.lock().unwrap() = Some(topic_id);
+                subscriber.state.changed.notify_one();
+            }
+        }
+    }
+
+    /// Subscribe before authorizing `username`, so that access revoked in
+    /// between still ends the subscription.
+    pub fn subscribe(&self, username: &str, topic_ids: Vec<Timeuuid>) -> TopicSubscription {
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        let state = Arc::new(SubscriberState::default());
+        let topic_ids: HashSet<Timeuuid> = topic_ids.into_iter().collect();
+
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let id = subscribers.next_id;
+        subscribers.next_id += 1;
+        for topic_id in topic_ids.iter() {
+            subscribers.by_topic.entry(*topic_id).or_default().push(Subscriber {
+                id,
+                username: username.to_owned(),
+                events: sender.clone(),
+                state: Arc::clone(&state),
+            });
+        }
+
+        TopicSubscription {
+            id,
+            hub: self.clone(),
+            receiver,
+            state,
+            username: username.to_owned(),
+            topic_ids,
+            backlog: VecDeque::new(),
+            replayed: HashSet::new(),
+        }
+    }
+
+    fn unsubscribe(&self, id: u64, topic_ids: &HashSet<Timeuuid>) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        for topic_id in topic_ids {
+            if let Some(topic_subscribers) = subscribers.by_topic.get_mut(topic_id) {
+                topic_subscribers.retain(|subscriber| subscriber.id != id);
+                if topic_subscribers.is_empty() {
+                    subscribers.by_topic.remove(topic_id);
+                }
+            }
+        }
+    }
+}
+
+impl Default for TopicEventHub {
+    fn default() -> Self {
+        Self::new(DEFAULT_HUB_CAPACITY)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TopicSubscriptionError {
+    #[error("Subscriber lagged behind and skipped {skipped} events, resubscribe from the last seen message")]
+    Lagged { skipped: u64 },
+}
+
+/// A subscription to one or more topics: replays the backlog first, then follows the hub.
+/// It fails with `PermissionDenied` once its user loses access to any of the topics.
+#[derive(Debug)]
+pub struct TopicSubscription {
+    id: u64,
+    hub: TopicEventHub,
+    receiver: mpsc::Receiver<TopicEvent>,
+    state: Arc<SubscriberState>,
+    username: Text,
+    topic_ids: HashSet<Timeuuid>,
+    backlog: VecDeque<TopicEvent>,
+    /// Ids of replayed messages not yet seen again live.
+    replayed: HashSet<Timeuuid>,
+}
+
+impl TopicSubscription {
+    /// Queues an already persisted event to be delivered before any live event.
+    pub fn push_backlog(&mut self, event: TopicEvent) {
+        if let TopicEvent::MessageCreated(message) = &event {
+            self.replayed.insert(message.message_id);
+        }
+        self.backlog.push_back(event);
+    }
+
+    pub async fn next(&mut self) -> AppResult<Option<TopicEvent>> {
+        self.ensure_live()?;
+        if let Some(event) = self.backlog.pop_front() {
+            return Ok(Some(event));
+        }
+
+        loop {
+            // Revocations first: an event published after one must not slip through.
+            let event = tokio::select! {
+                biased;
+                _ = self.state.changed.notified() => {
+                    self.ensure_live()?;
+                    continue;
+                }
+                event = self.receiver.recv() => match event {
+                    Some(event) => event,
+                    None => return Ok(None),
+                },
+            };
+
+            // Messages written between subscribing and reading the backlog arrive twice.
+            if let TopicEvent::MessageCreated(message) = &event {
+                if self.replayed.remove(&message.message_id) {
+                    continue;
+                }
+            }
+
+            return Ok(Some(event));
+        }
+    }
+
+    fn ensure_live(&self) -> AppResult<()> {
+        if let Some(topic_id) = *self.state.revoked.lock().unwrap() {
+            bail!(ApplicationError::PermissionDenied {
+                msg: format!("{} can no longer read topic {}", self.username, topic_id)
+            });
+        }
+        match self.state.skipped.load(Ordering::SeqCst) {
+            0 => Ok(()),
+            skipped => bail!(TopicSubscriptionError::Lagged { skipped }),
+        }
+    }
+}
+
+impl Drop for TopicSubscription {
+    fn drop(&mut self) {
+        self.hub.unsubscribe(self.id, &self.topic_ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::live::event::TypingIndicator;
+    use uptop_core::common::utils::now_timeuuid;
+
+    fn typing(topic_id: Timeuuid, username: &str) -> TopicEvent {
+        TopicEvent::Typing(TypingIndicator {
+            topic_id,
+            username: username.to_string(),
+            stopped: true,
+            expires_at: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn a_busy_topic_lags_only_its_own_subscribers() {
+        let hub = TopicEventHub::new(4);
+        let (busy, quiet) = (now_timeuuid(), now_timeuuid());
+        let mut slow = hub.subscribe("alice", vec![busy]);
+        let mut other = hub.subscribe("bob", vec![quiet]);
+
+        for _ in 0..10 {
+            hub.publish(typing(busy, "carol"));
+        }
+        hub.publish(typing(quiet, "carol"));
+
+        assert_eq!(other.next().await.unwrap(), Some(typing(quiet, "carol")));
+        let err = slow.next().await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(TopicSubscriptionError::Lagged { skipped: 6 })));
+    }
+
+    #[tokio::test]
+    async fn a_revocation_ends_only_that_users_subscription() {
+        let hub = TopicEventHub::default();
+        let topic_id = now_timeuuid();
+        let mut alice = hub.subscribe("alice", vec![topic_id]);
+        let mut bob = hub.subscribe("bob", vec![topic_id]);
+
+        hub.revoke_access(topic_id, Some("alice"));
+        hub.publish(typing(topic_id, "carol"));
+
+        let err = alice.next().await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(ApplicationError::PermissionDenied { .. })));
+        assert_eq!(bob.next().await.unwrap(), Some(typing(topic_id, "carol")));
+    }
+
+    #[tokio::test]
+    async fn dropping_a_subscription_unregisters_it() {
+        let hub = TopicEventHub::default();
+        let topic_id = now_timeuuid();
+        drop(hub.subscribe("alice", vec![topic_id, topic_id]));
+        assert!(hub.subscribers.lock().unwrap().by_topic.is_empty());
+    }
+}
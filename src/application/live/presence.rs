@@ -0,0 +1,228 @@
+use super::event::{PresenceUpdate, TopicEvent, TypingIndicator};
+use super::hub::TopicEventHub;
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::time::MissedTickBehavior;
+
+/// A typing signal lapses unless the client repeats it within this long.
+pub const TYPING_TTL_SECS: i64 = 6;
+
+/// Online and away lapse to offline unless the client repeats them within this long.
+pub const PRESENCE_TTL_SECS: i64 = 90;
+
+const EXPIRY_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresenceStatus {
+    Online,
+    Away,
+    Offline,
+}
+
+impl PresenceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PresenceStatus::Online => "online",
+            PresenceStatus::Away => "away",
+            PresenceStatus::Offline => "offline",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<PresenceStatus> {
+        match value {
+            "online" => Some(PresenceStatus::Online),
+            "away" => Some(PresenceStatus::Away),
+            "offline" => Some(PresenceStatus::Offline),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Presence {
+    status: PresenceStatus,
+    /// Topics told about changes, resolved when the status was last set.
+    topic_ids: Vec<Timeuuid>,
+    last_seen_at: Timestamp,
+    expires_at: Timestamp,
+}
+
+/// Typing and presence signals, held in process only and never persisted.
+/// Each one expires on its own unless refreshed; [`PresenceRegistry::expire`]
+/// drops the lapsed ones and tells subscribers.
+#[derive(Debug, Default)]
+pub struct PresenceRegistry {
+    typing: Mutex<HashMap<(Timeuuid, Text), Timestamp>>,
+    presence: Mutex<HashMap<Text, Presence>>,
+}
+
+impl PresenceRegistry {
+    /// Records that `username` is typing in the topic, returning when that lapses.
+    pub fn start_typing(&self, topic_id: Timeuuid, username: &str) -> Timestamp {
+        let expires_at = Utc::now() + Duration::seconds(TYPING_TTL_SECS);
+        let mut typing = self.typing.lock().unwrap_or_else(PoisonError::into_inner);
+        typing.insert((topic_id, username.to_owned()), expires_at);
+        expires_at
+    }
+
+    /// Whether `username` was typing in the topic before this call.
+    pub fn stop_typing(&self, topic_id: Timeuuid, username: &str) -> bool {
+        let mut typing = self.typing.lock().unwrap_or_else(PoisonError::into_inner);
+        typing.remove(&(topic_id, username.to_owned())).is_some()
+    }
+
+    /// Sets the status of `username`, returning the previous one.
+    pub fn set_presence(
+        &self,
+        username: &str,
+        status: PresenceStatus,
+        topic_ids: Vec<Timeuuid>,
+    ) -> PresenceStatus {
+        let now = Utc::now();
+        let mut presence = self.presence.lock().unwrap_or_else(PoisonError::into_inner);
+        let previous = presence
+            .get(username)
+            .map(|previous| previous.status)
+            .unwrap_or(PresenceStatus::Offline);
+        presence.insert(
+            username.to_owned(),
+            Presence {
+                status,
+                topic_ids,
+                last_seen_at: now,
+                expires_at: now + Duration::seconds(PRESENCE_TTL_SECS),
+            },
+        );
+        previous
+    }
+
+    /// The status of `username` and when they were last seen, if ever since
+    /// the process started.
+    pub fn presence(&self, username: &str) -> (PresenceStatus, Option<Timestamp>) {
+        let presence = self.presence.lock().unwrap_or_else(PoisonError::into_inner);
+        match presence.get(username) {
+            Some(presence) => (presence.status, Some(presence.last_seen_at)),
+            None => (PresenceStatus::Offline, None),
+        }
+    }
+
+    /// Drops the typing signals that lapsed and turns lapsed presence
+    /// offline, returning the events announcing it.
+    pub fn expire(&self) -> Vec<TopicEvent> {
+        let now = Utc::now();
+        let mut events: Vec<TopicEvent> = vec![];
+
+        let mut typing = self.typing.lock().unwrap_or_else(PoisonError::into_inner);
+        typing.retain(|(topic_id, username), expires_at| {
+            if *expires_at > now {
+                return true;
+            }
+            events.push(TopicEvent::Typing(TypingIndicator {
+                topic_id: *topic_id,
+                username: username.to_owned(),
+                stopped: true,
+                expires_at: None,
+            }));
+            false
+        });
+        drop(typing);
+
+        let mut presence = self.presence.lock().unwrap_or_else(PoisonError::into_inner);
+        for (username, presence) in presence.iter_mut() {
+            if presence.status == PresenceStatus::Offline || presence.expires_at > now {
+                continue;
+            }
+            // Last seen stays at the final refresh, not the moment it lapsed.
+            presence.status = PresenceStatus::Offline;
+            for topic_id in presence.topic_ids.iter() {
+                events.push(TopicEvent::Presence(PresenceUpdate {
+                    topic_id: *topic_id,
+                    username: username.to_owned(),
+                    status: PresenceStatus::Offline.as_str().to_string(),
+                    last_seen_at: presence.last_seen_at,
+                }));
+            }
+        }
+        events
+    }
+}
+
+/// Expires typing and presence signals every second until the process exits.
+pub async fn expire_presence_every_second(registry: Arc<PresenceRegistry>, hub: Arc<TopicEventHub>) {
+    let mut ticker = tokio::time::interval(EXPIRY_SWEEP_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        for event in registry.expire() {
+            hub.publish(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uptop_core::common::utils::now_timeuuid;
+
+    /// Backdates a signal so the next sweep finds it lapsed.
+    fn lapse_typing(registry: &PresenceRegistry, topic_id: Timeuuid, username: &str) {
+        let mut typing = registry.typing.lock().unwrap();
+        typing.insert((topic_id, username.to_owned()), Utc::now() - Duration::seconds(1));
+    }
+
+    #[test]
+    fn a_lapsed_typing_signal_expires_as_stopped() {
+        let registry = PresenceRegistry::default();
+        let topic_id = now_timeuuid();
+        registry.start_typing(topic_id, "alice");
+        lapse_typing(&registry, topic_id, "bob");
+
+        let events = registry.expire();
+        assert_eq!(
+            events,
+            vec![TopicEvent::Typing(TypingIndicator {
+                topic_id,
+                username: "bob".to_string(),
+                stopped: true,
+                expires_at: None,
+            })]
+        );
+        assert!(!registry.stop_typing(topic_id, "bob"));
+        assert!(registry.stop_typing(topic_id, "alice"));
+    }
+
+    #[test]
+    fn typing_again_pushes_the_expiry_back() {
+        let registry = PresenceRegistry::default();
+        let topic_id = now_timeuuid();
+        lapse_typing(&registry, topic_id, "alice");
+        let expires_at = registry.start_typing(topic_id, "alice");
+
+        assert!(expires_at > Utc::now() + Duration::seconds(TYPING_TTL_SECS - 1));
+        assert!(registry.expire().is_empty());
+    }
+
+    #[test]
+    fn lapsed_presence_turns_offline_once() {
+        let registry = PresenceRegistry::default();
+        let topic_id = now_timeuuid();
+        registry.set_presence("alice", PresenceStatus::Online, vec![topic_id]);
+        let last_seen_at = registry.presence("alice").1.unwrap();
+        registry.presence.lock().unwrap().get_mut("alice").unwrap().expires_at = Utc::now();
+
+        let events = registry.expire();
+        assert_eq!(
+            events,
+            vec![TopicEvent::Presence(PresenceUpdate {
+                topic_id,
+                username: "alice".to_string(),
+                status: "offline".to_string(),
+                last_seen_at,
+            })]
+        );
+        assert_eq!(registry.presence("alice"), (PresenceStatus::Offline, Some(last_seen_at)));
+        assert!(registry.expire().is_empty());
+    }
+}
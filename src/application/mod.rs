@@ -4,3 +4,12 @@ pub mod topic_message;
 pub mod topic_user;
 pub mod user_topic;
 pub mod notification;
+pub mod topic_role;
+pub mod user_identity;
+pub mod topic_purge;
+pub mod live;
+pub mod actor;
+pub mod error;
+pub mod pagination;
+#[cfg(all(test, feature = "in-memory"))]
+pub(crate) mod testing;
@@ -1,8 +1,9 @@
 use super::{
     response::PublicNotification,
 };
-use crate::application::notification::request::{RequestGetNotificationByUsername, RequestUpdateNotification};
+use crate::application::notification::request::RequestGetNotificationByUsername;
 use crate::domain::notification::{repository::NotificationRepository};
+use crate::application::pagination::Page;
 use std::{future::Future, sync::Arc};
 use uptop_core::common::result::AppResult;
 use crate::domain::notification::entity::Notification;
@@ -11,12 +12,7 @@ pub trait NotificationAppInterface: Clone + Send + Sync + 'static {
     fn find_list_notification_by_username(
         &self,
         query: &RequestGetNotificationByUsername,
-    ) -> impl Future<Output=AppResult<Vec<PublicNotification>>> + Send;
-
-    fn update_notification(
-        &self,
-        notification: &RequestUpdateNotification,
-    ) -> impl Future<Output=AppResult<PublicNotification>> + Send;
+    ) -> impl Future<Output=AppResult<Page<PublicNotification>>> + Send;
 }
 
 #[derive(Clone, Debug)]
@@ -43,25 +39,11 @@ where
     async fn find_list_notification_by_username(
         &self,
         query: &RequestGetNotificationByUsername,
-    ) -> AppResult<Vec<PublicNotification>> {
-        let mut result: Vec<PublicNotification> = vec![];
+    ) -> AppResult<Page<PublicNotification>> {
         self.notification_repo
             .find_notifications_by_partition_key(query)
-            .await
-            .map(|ref notification: Vec<Notification>| {
-                for item in notification.iter() {
-                    result.push(item.try_into().expect("Can not parse data!"));
-                }
-            })
-            .expect("TODO: panic message");
-        Ok(result)
-    }
-
-    async fn update_notification(&self, notification: &RequestUpdateNotification) -> AppResult<PublicNotification> {
-        self.notification_repo
-            .update_notifications(notification)
-            .await
-            .map(|notification| PublicNotification::try_from(&notification).unwrap())
+            .await?
+            .try_map(|item: &Notification| item.try_into())
     }
 
     // async fn get_full_field_notification(&self, query: &RequestGetNotificationByNotificationName) -> AppResult<Notification> {
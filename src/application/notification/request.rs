@@ -1,58 +1,37 @@
 use anyhow::bail;
 use charybdis::types::{Text, Timeuuid};
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
-use uptop_core::common::result::{AppError, AppResult};
+use uptop_core::common::result::AppResult;
 use validator::Validate;
+use crate::application::pagination::MAX_PAGE_SIZE;
+use crate::application::error::ApplicationError;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
-pub struct RequestUpdateNotification {
-    pub topic_id: Timeuuid,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestRemoveTopicNotifications {
     pub username: Text,
-    pub from_user: Text,
-    pub message: Text,
-}
-
-impl RequestUpdateNotification {
-    pub fn try_into_domain(self) -> AppResult<Self> {
-        match self.validate() {
-            Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
-        };
-
-        Ok(Self {
-            topic_id: self.topic_id,
-            username: self.username,
-            from_user: self.from_user,
-            message: self.message,
-        })
-    }
+    pub topic_id: Timeuuid,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestGetNotificationByUsername {
+    #[serde(default)]
     pub username: Text,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
 }
 
 impl RequestGetNotificationByUsername {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
             username: self.username,
+            page_size: self.page_size,
+            page_token: self.page_token,
         })
     }
 }
-
-#[derive(Debug, Error)]
-pub enum RequestFindLatestMessageError {
-    #[error("LatestMessage not found")]
-    LatestMessageNotFound,
-}
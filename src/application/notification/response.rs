@@ -1,10 +1,11 @@
-use charybdis::types::{Text, Timestamp};
+use charybdis::types::{Text, Timestamp, Timeuuid};
 use serde::{Deserialize, Serialize};
 use uptop_core::common::result::AppResult;
 use crate::domain::notification::entity::Notification;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PublicNotification {
+    pub topic_id: Timeuuid,
     pub username: Text,
     pub from_user: Text,
     pub message: Text,
@@ -16,6 +17,7 @@ impl TryFrom<&Notification> for PublicNotification {
 
     fn try_from(notification: &Notification) -> AppResult<Self> {
         Ok(Self {
+            topic_id: notification.topic_id,
             username: (*notification.username).parse()?,
             from_user: (*notification.from_user).parse()?,
             message: (*notification.message).parse()?,
@@ -0,0 +1,46 @@
+use anyhow::bail;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use crate::application::error::ApplicationError;
+use uptop_core::common::result::AppResult;
+
+pub const DEFAULT_PAGE_SIZE: i32 = 50;
+pub const MAX_PAGE_SIZE: i32 = 500;
+
+/// One page of a list query. `next_page_token` is absent on the last page.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_page_token: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn try_map<U, F>(self, f: F) -> AppResult<Page<U>>
+    where
+        F: FnMut(&T) -> AppResult<U>,
+    {
+        Ok(Page {
+            items: self.items.iter().map(f).collect::<AppResult<Vec<U>>>()?,
+            next_page_token: self.next_page_token,
+        })
+    }
+}
+
+pub fn page_size_or_default(page_size: Option<i32>) -> i32 {
+    page_size.unwrap_or(DEFAULT_PAGE_SIZE)
+}
+
+/// Page tokens are opaque to clients: the driver paging state, base64url encoded.
+pub fn encode_page_token(paging_state: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(paging_state)
+}
+
+pub fn decode_page_token(page_token: &Option<String>) -> AppResult<Option<Vec<u8>>> {
+    match page_token.as_deref() {
+        None | Some("") => Ok(None),
+        Some(token) => match URL_SAFE_NO_PAD.decode(token) {
+            Ok(paging_state) => Ok(Some(paging_state)),
+            Err(_) => bail!(ApplicationError::invalid_field("page_token", "is not a valid page token")),
+        },
+    }
+}
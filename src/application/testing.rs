@@ -0,0 +1,202 @@
+//! The applications wired onto the in-memory repositories the way
+//! `server_message` wires them onto Scylla, for the application tests.
+
+use crate::application::actor::Actor;
+use crate::application::live::hub::TopicEventHub;
+use crate::application::live::presence::PresenceRegistry;
+use crate::application::topic::app::{TopicApp, TopicAppInterface};
+use crate::application::topic::request::RequestCreateTopic;
+use crate::application::topic_message::app::TopicMessageApp;
+use crate::application::topic_purge::app::TopicPurgeApp;
+use crate::application::topic_role::app::TopicRoleApp;
+use crate::application::topic_user::app::{TopicUserApp, TopicUserAppInterface};
+use crate::application::topic_user::request::RequestJoinTopic;
+use crate::infrastructure::memory::direct_conversation_repository::DirectConversationMemoryRepo;
+use crate::infrastructure::memory::group_conversation_repository::GroupConversationMemoryRepo;
+use crate::infrastructure::memory::hidden_message_repository::HiddenMessageMemoryRepo;
+use crate::infrastructure::memory::latest_message_repository::LatestMessageMemoryRepo;
+use crate::infrastructure::memory::message_reaction_repository::MessageReactionMemoryRepo;
+use crate::infrastructure::memory::notification_repository::NotificationMemoryRepo;
+use crate::infrastructure::memory::pinned_message_repository::PinnedMessageMemoryRepo;
+use crate::infrastructure::memory::read_cursor_repository::ReadCursorMemoryRepo;
+use crate::infrastructure::memory::thread_message_repository::ThreadMessageMemoryRepo;
+use crate::infrastructure::memory::topic_invite_repository::TopicInviteMemoryRepo;
+use crate::infrastructure::memory::topic_message_repository::TopicMessageMemoryRepo;
+use crate::infrastructure::memory::topic_message_revision_repository::TopicMessageRevisionMemoryRepo;
+use crate::infrastructure::memory::topic_purge_repository::TopicPurgeMemoryRepo;
+use crate::infrastructure::memory::topic_repository::TopicMemoryRepo;
+use crate::infrastructure::memory::topic_role_repository::TopicRoleMemoryRepo;
+use crate::infrastructure::memory::topic_user_repository::TopicUserMemoryRepo;
+use crate::infrastructure::memory::unread_counter_repository::UnreadCounterMemoryRepo;
+use crate::infrastructure::memory::user_identity_repository::UserIdentityMemoryRepo;
+use crate::infrastructure::memory::user_topic_repository::UserTopicMemoryRepo;
+use crate::infrastructure::memory::MemoryRepositories;
+use charybdis::types::Timeuuid;
+use std::sync::Arc;
+use uptop_core::common::utils::now_timeuuid;
+
+pub(crate) type TestTopicRoleApp = TopicRoleApp<TopicMemoryRepo, TopicRoleMemoryRepo, UserTopicMemoryRepo>;
+
+pub(crate) type TestTopicPurgeApp = TopicPurgeApp<
+    TopicMemoryRepo,
+    TopicPurgeMemoryRepo,
+    TopicUserMemoryRepo,
+    TopicMessageMemoryRepo,
+    ThreadMessageMemoryRepo,
+    MessageReactionMemoryRepo,
+    PinnedMessageMemoryRepo,
+    ReadCursorMemoryRepo,
+    TopicRoleMemoryRepo,
+    TopicInviteMemoryRepo,
+    HiddenMessageMemoryRepo,
+    LatestMessageMemoryRepo,
+    NotificationMemoryRepo,
+    UnreadCounterMemoryRepo,
+>;
+
+pub(crate) type TestTopicApp = TopicApp<
+    TopicMemoryRepo,
+    DirectConversationMemoryRepo,
+    GroupConversationMemoryRepo,
+    TopicUserMemoryRepo,
+    UserTopicMemoryRepo,
+    UserIdentityMemoryRepo,
+    TestTopicRoleApp,
+    TestTopicPurgeApp,
+>;
+
+pub(crate) type TestTopicUserApp = TopicUserApp<
+    TopicUserMemoryRepo,
+    UserTopicMemoryRepo,
+    TopicInviteMemoryRepo,
+    TestTopicRoleApp,
+    TopicMessageMemoryRepo,
+>;
+
+pub(crate) type TestTopicMessageApp = TopicMessageApp<
+    TopicMessageMemoryRepo,
+    TopicMessageRevisionMemoryRepo,
+    HiddenMessageMemoryRepo,
+    ThreadMessageMemoryRepo,
+    MessageReactionMemoryRepo,
+    PinnedMessageMemoryRepo,
+    ReadCursorMemoryRepo,
+    UnreadCounterMemoryRepo,
+    TopicUserMemoryRepo,
+    LatestMessageMemoryRepo,
+    NotificationMemoryRepo,
+    TestTopicRoleApp,
+>;
+
+pub(crate) struct TestApps {
+    /// Shared with the applications, to check what they stored.
+    pub repos: MemoryRepositories,
+    pub topic_role_app: Arc<TestTopicRoleApp>,
+    pub topic_purge_app: Arc<TestTopicPurgeApp>,
+    pub topic_app: TestTopicApp,
+    pub topic_user_app: TestTopicUserApp,
+    pub topic_message_app: TestTopicMessageApp,
+}
+
+impl TestApps {
+    pub fn new() -> Self {
+        let repos = MemoryRepositories::new();
+        let hub = Arc::new(TopicEventHub::default());
+        let presence = Arc::new(PresenceRegistry::default());
+        let topic_role_app = Arc::new(TopicRoleApp::new(
+            Arc::new(repos.topic.clone()),
+            Arc::new(repos.topic_role.clone()),
+            Arc::new(repos.user_topic.clone()),
+            Arc::clone(&hub),
+        ));
+        let topic_purge_app = Arc::new(TopicPurgeApp::new(
+            Arc::new(repos.topic.clone()),
+            Arc::new(repos.topic_purge.clone()),
+            Arc::new(repos.topic_user.clone()),
+            Arc::new(repos.topic_message.clone()),
+            Arc::new(repos.thread_message.clone()),
+            Arc::new(repos.message_reaction.clone()),
+            Arc::new(repos.pinned_message.clone()),
+            Arc::new(repos.read_cursor.clone()),
+            Arc::new(repos.topic_role.clone()),
+            Arc::new(repos.topic_invite.clone()),
+            Arc::new(repos.hidden_message.clone()),
+            Arc::new(repos.latest_message.clone()),
+            Arc::new(repos.notification.clone()),
+            Arc::new(repos.unread_counter.clone()),
+        ));
+        let topic_app = TopicApp::new(
+            Arc::new(repos.topic.clone()),
+            Arc::new(repos.direct_conversation.clone()),
+            Arc::new(repos.group_conversation.clone()),
+            Arc::new(repos.topic_user.clone()),
+            Arc::new(repos.user_topic.clone()),
+            Arc::new(repos.user_identity.clone()),
+            Arc::clone(&topic_role_app),
+            Arc::clone(&topic_purge_app),
+            Arc::clone(&hub),
+        );
+        let topic_user_app = TopicUserApp::new(
+            Arc::new(repos.topic_user.clone()),
+            Arc::new(repos.user_topic.clone()),
+            Arc::new(repos.topic_invite.clone()),
+            Arc::clone(&topic_role_app),
+            Arc::new(repos.topic_message.clone()),
+            Arc::clone(&hub),
+            Arc::clone(&presence),
+        );
+        let topic_message_app = TopicMessageApp::new(
+            Arc::new(repos.topic_message.clone()),
+            Arc::new(repos.topic_message_revision.clone()),
+            Arc::new(repos.hidden_message.clone()),
+            Arc::new(repos.thread_message.clone()),
+            Arc::new(repos.message_reaction.clone()),
+            Arc::new(repos.pinned_message.clone()),
+            Arc::new(repos.read_cursor.clone()),
+            Arc::new(repos.unread_counter.clone()),
+            Arc::new(repos.topic_user.clone()),
+            Arc::new(repos.latest_message.clone()),
+            Arc::new(repos.notification.clone()),
+            Arc::clone(&topic_role_app),
+            Arc::clone(&hub),
+            Arc::clone(&presence),
+        );
+        Self {
+            repos,
+            topic_role_app,
+            topic_purge_app,
+            topic_app,
+            topic_user_app,
+            topic_message_app,
+        }
+    }
+
+    /// A group topic owned by `owner`, who is also its first member.
+    pub async fn create_topic(&self, owner: &Actor) -> Timeuuid {
+        let req = RequestCreateTopic {
+            topic_name: "general".to_string(),
+            topic_description: None,
+            topic_owners: vec![],
+            topic_admins: vec![],
+        };
+        let topic = self.topic_app.create_topic(owner, req).await.unwrap();
+        self.join(topic.topic_id, owner).await;
+        topic.topic_id
+    }
+
+    pub async fn join(&self, topic_id: Timeuuid, actor: &Actor) {
+        let req = RequestJoinTopic {
+            topic_id,
+            username: actor.username.to_owned(),
+            user_id: actor.user_id,
+        };
+        self.topic_user_app.join_topic(&req).await.unwrap();
+    }
+}
+
+pub(crate) fn actor(username: &str) -> Actor {
+    Actor {
+        user_id: now_timeuuid(),
+        username: username.to_string(),
+    }
+}
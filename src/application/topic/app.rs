@@ -1,40 +1,111 @@
 use super::{
     request::{RequestCreateTopic, RequestGetTopicByPartitionKey},
-    response::PublicTopic,
+    response::{PublicDirectConversation, PublicGroupConversation, PublicTopic},
 };
+use crate::application::actor::Actor;
+use crate::application::error::ApplicationError;
 use crate::application::topic::request::{
-    RequestGetTopicByIndexKey, RequestGetTopicByPrimaryKey, RequestUpdateTopic,
+    RequestGetDirectConversation, RequestGetGroupConversation, RequestGetTopicByIndexKey,
+    RequestGetTopicByPrimaryKey, RequestOpenDirectConversation, RequestOpenGroupConversation,
+    RequestPromoteConversation, RequestSetTopicState, RequestUpdateTopic,
 };
+use crate::application::topic_purge::app::TopicPurgeAppInterface;
+use crate::application::topic_role::app::TopicRoleAppInterface;
+use crate::application::topic_role::request::{RequestGetRolesByTopicId, RequestSetTopicRole};
+use crate::application::topic_role::response::PublicTopicRole;
+use crate::application::topic_user::request::{RequestGetTopicMember, RequestGetUsersByTopicId};
+use crate::application::live::hub::TopicEventHub;
+use crate::domain::direct_conversation::{entity::DirectConversation, repository::DirectConversationRepository};
+use crate::domain::group_conversation::{entity::GroupConversation, repository::GroupConversationRepository};
+use crate::domain::topic::entity::{TopicState, TOPIC_KIND_DIRECT, TOPIC_KIND_GROUP, TOPIC_KIND_GROUP_DIRECT};
 use crate::domain::topic::{entity::Topic, repository::TopicRepository};
+use crate::domain::topic_role::entity::{Capability, Role};
+use crate::domain::topic_user::{entity::TopicUser, repository::TopicUserRepository};
+use crate::domain::user_topic::{entity::UserTopic, repository::UserTopicRepository};
+use crate::domain::user_identity::repository::UserIdentityRepository;
+use crate::application::user_identity::request::RequestGetUserIdentity;
+use crate::application::pagination::{Page, MAX_PAGE_SIZE};
+use anyhow::bail;
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use chrono::Utc;
 use std::{future::Future, sync::Arc};
-use uptop_core::common::result::AppResult;
+use uptop_core::common::{result::AppResult, utils::now_timeuuid};
 
+/// Topic metadata is readable by anyone not banned from the topic, so users
+/// can find topics before joining them; direct conversations only by their
+/// two members. Changing it needs `ManageSettings`.
 pub trait TopicAppInterface: Clone + Send + Sync + 'static {
     fn create_topic(
         &self,
+        actor: &Actor,
         req: RequestCreateTopic,
     ) -> impl Future<Output = AppResult<PublicTopic>> + Send;
 
     fn find_topic_by_partition_key(
         &self,
+        actor: &Actor,
         query: &RequestGetTopicByPartitionKey,
-    ) -> impl Future<Output = AppResult<Vec<PublicTopic>>> + Send;
+    ) -> impl Future<Output = AppResult<Page<PublicTopic>>> + Send;
 
     fn find_topic_by_primary_key(
         &self,
+        actor: &Actor,
         query: &RequestGetTopicByPrimaryKey,
     ) -> impl Future<Output = AppResult<PublicTopic>> + Send;
 
     fn find_topic_by_index_key(
         &self,
+        actor: &Actor,
         query: &RequestGetTopicByIndexKey,
-    ) -> impl Future<Output = AppResult<Vec<PublicTopic>>> + Send;
+    ) -> impl Future<Output = AppResult<Page<PublicTopic>>> + Send;
 
     fn update_topic(
         &self,
+        actor: &Actor,
         topic: &RequestUpdateTopic,
     ) -> impl Future<Output = AppResult<PublicTopic>> + Send;
 
+    fn set_topic_role(
+        &self,
+        actor: &Actor,
+        req: &RequestSetTopicRole,
+    ) -> impl Future<Output = AppResult<PublicTopicRole>> + Send;
+
+    /// Returns the one direct conversation of the pair, creating it and its
+    /// two memberships on first use. A member who left is added back.
+    fn open_direct_conversation(
+        &self,
+        req: &RequestOpenDirectConversation,
+    ) -> impl Future<Output = AppResult<PublicDirectConversation>> + Send;
+
+    /// Like [`open_direct_conversation`](Self::open_direct_conversation), for
+    /// a set of users. After the conversation is promoted the set gets a new one.
+    fn open_group_conversation(
+        &self,
+        req: &RequestOpenGroupConversation,
+    ) -> impl Future<Output = AppResult<PublicGroupConversation>> + Send;
+
+    /// Any member can name a group conversation, turning it into a group topic.
+    fn promote_conversation(
+        &self,
+        req: &RequestPromoteConversation,
+    ) -> impl Future<Output = AppResult<PublicTopic>> + Send;
+
+    /// Owners only. Nothing leaves `deleted`; deleting marks the topic at
+    /// once and purges everything hanging off it in the background, resuming
+    /// after a restart. Deleting again reruns the purge.
+    fn set_topic_state(
+        &self,
+        actor: &Actor,
+        req: &RequestSetTopicState,
+    ) -> impl Future<Output = AppResult<PublicTopic>> + Send;
+
+    fn find_list_roles_by_topic_id(
+        &self,
+        actor: &Actor,
+        query: &RequestGetRolesByTopicId,
+    ) -> impl Future<Output = AppResult<Page<PublicTopicRole>>> + Send;
+
     // fn get_full_field_topic(
     //     &self,
     //     query: &RequestGetTopicByTopicName,
@@ -42,82 +113,524 @@ pub trait TopicAppInterface: Clone + Send + Sync + 'static {
 }
 
 #[derive(Clone, Debug)]
-pub struct TopicApp<TP>
+pub struct TopicApp<TP, DC, GC, TU, UT, ID, RA, PA>
 where
     TP: TopicRepository,
+    DC: DirectConversationRepository,
+    GC: GroupConversationRepository,
+    TU: TopicUserRepository,
+    UT: UserTopicRepository,
+    ID: UserIdentityRepository,
+    RA: TopicRoleAppInterface,
+    PA: TopicPurgeAppInterface,
 {
     topic_repo: Arc<TP>,
+    direct_conversation_repo: Arc<DC>,
+    group_conversation_repo: Arc<GC>,
+    topic_user_repo: Arc<TU>,
+    user_topic_repo: Arc<UT>,
+    user_identity_repo: Arc<ID>,
+    topic_role_app: Arc<RA>,
+    topic_purge_app: Arc<PA>,
+    hub: Arc<TopicEventHub>,
 }
 
-impl<TP> TopicApp<TP>
+impl<TP, DC, GC, TU, UT, ID, RA, PA> TopicApp<TP, DC, GC, TU, UT, ID, RA, PA>
 where
     TP: TopicRepository,
+    DC: DirectConversationRepository,
+    GC: GroupConversationRepository,
+    TU: TopicUserRepository,
+    UT: UserTopicRepository,
+    ID: UserIdentityRepository,
+    RA: TopicRoleAppInterface,
+    PA: TopicPurgeAppInterface,
 {
-    pub fn new(topic_repo: Arc<TP>) -> Self {
-        Self { topic_repo }
+    // One repository per table the use cases touch; a builder would only hide that.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        topic_repo: Arc<TP>,
+        direct_conversation_repo: Arc<DC>,
+        group_conversation_repo: Arc<GC>,
+        topic_user_repo: Arc<TU>,
+        user_topic_repo: Arc<UT>,
+        user_identity_repo: Arc<ID>,
+        topic_role_app: Arc<RA>,
+        topic_purge_app: Arc<PA>,
+        hub: Arc<TopicEventHub>,
+    ) -> Self {
+        Self {
+            topic_repo,
+            direct_conversation_repo,
+            group_conversation_repo,
+            topic_user_repo,
+            user_topic_repo,
+            user_identity_repo,
+            topic_role_app,
+            topic_purge_app,
+            hub,
+        }
+    }
+
+    async fn is_visible(&self, actor: &Actor, topic: &Topic) -> AppResult<bool> {
+        if topic.is_deleted() {
+            return Ok(false);
+        }
+        let role = self.topic_role_app.find_role(topic, &actor.username).await?;
+        Ok(match topic.is_conversation() {
+            true => role.is_some_and(|role| role != Role::Banned),
+            false => role != Some(Role::Banned),
+        })
+    }
+
+    /// Deleted topics and conversations of other users are reported missing
+    /// rather than forbidden.
+    async fn ensure_visible(&self, actor: &Actor, topic: &Topic) -> AppResult<()> {
+        match self.is_visible(actor, topic).await? {
+            true => Ok(()),
+            false if topic.is_conversation() || topic.is_deleted() => bail!(ApplicationError::NotFound { resource: "topic" }),
+            false => bail!(ApplicationError::PermissionDenied {
+                msg: format!("{} is banned from this topic", actor.username)
+            }),
+        }
+    }
+
+    async fn find_topic(&self, topic_id: Timeuuid) -> AppResult<Option<Topic>> {
+        let query = RequestGetTopicByPartitionKey {
+            topic_id,
+            page_size: Some(1),
+            page_token: None,
+        };
+        Ok(self.topic_repo.find_topic_by_partition_key(&query).await?.items.into_iter().next())
+    }
+
+    /// Writes `topic` over `previous`, failing if the topic was changed in
+    /// between. Every change to an existing topic goes through here, since
+    /// conditional and plain writes to the same row do not mix.
+    async fn replace_topic(&self, previous: &Topic, topic: &Topic) -> AppResult<()> {
+        match self.topic_repo.update_topic(topic, previous.updated_at).await? {
+            true => Ok(()),
+            false => bail!(ApplicationError::topic_changed()),
+        }
+    }
+
+    /// Writes the topic of a conversation unless it is there already. Opening
+    /// claims the conversation row first and derives everything else from it,
+    /// so a retried or racing open finishes what another left undone.
+    async fn ensure_conversation_topic(
+        &self,
+        topic_id: Timeuuid,
+        created_at: Timestamp,
+        members: &[Text],
+        topic_kind: &str,
+    ) -> AppResult<()> {
+        if self.find_topic(topic_id).await?.is_some() {
+            return Ok(());
+        }
+
+        // No owners or admins: no member can rename it, invite or ban.
+        let topic = Topic {
+            topic_id,
+            topic_name: members.join(", "),
+            topic_description: None,
+            topic_owners: vec![],
+            topic_admins: vec![],
+            created_at,
+            updated_at: created_at,
+            topic_kind: Some(topic_kind.to_string()),
+            topic_state: Some(TopicState::Active.as_str().to_string()),
+        };
+        self.topic_repo.create_topic(&topic).await?;
+        Ok(())
+    }
+
+    /// The user id `username` signed in with. Only the user's own verified
+    /// calls record it, so a conversation cannot add someone under a made up id.
+    async fn user_id_of(&self, username: &Text) -> AppResult<Timeuuid> {
+        let query = RequestGetUserIdentity {
+            username: username.to_owned(),
+        };
+        match self.user_identity_repo.find_user_identity(&query).await? {
+            Some(identity) => Ok(identity.user_id),
+            None => bail!(ApplicationError::FailedPrecondition {
+                msg: format!("{username} has not signed in to messaging yet")
+            }),
+        }
+    }
+
+    /// Adds each member unless present, which also brings back one who left.
+    async fn ensure_conversation_members(
+        &self,
+        topic_id: Timeuuid,
+        created_at: Timestamp,
+        members: &[(Text, Timeuuid)],
+        topic_kind: &str,
+    ) -> AppResult<()> {
+        for (username, user_id) in members.iter() {
+            let query = RequestGetTopicMember {
+                topic_id,
+                username: username.to_owned(),
+            };
+            if self.user_topic_repo.find_user_topic(&query).await?.is_some() {
+                continue;
+            }
+
+            let topic_user = TopicUser {
+                topic_id,
+                username: username.to_owned(),
+                user_id: *user_id,
+                created_at,
+            };
+            let user_topic = UserTopic {
+                topic_id,
+                username: username.to_owned(),
+                created_at,
+                topic_kind: Some(topic_kind.to_string()),
+                topic_state: Some(TopicState::Active.as_str().to_string()),
+            };
+            self.topic_user_repo.add_topic_member(&topic_user, &user_topic, None).await?;
+        }
+        Ok(())
+    }
+
+    async fn find_all_members(&self, topic_id: Timeuuid) -> AppResult<Vec<TopicUser>> {
+        let mut members: Vec<TopicUser> = vec![];
+        let mut query = RequestGetUsersByTopicId {
+            topic_id,
+            page_size: Some(MAX_PAGE_SIZE),
+            page_token: None,
+        };
+        loop {
+            let page = self.topic_user_repo.find_topic_users_by_partition_key(&query).await?;
+            members.extend(page.items);
+            match page.next_page_token {
+                Some(page_token) => query.page_token = Some(page_token),
+                None => return Ok(members),
+            }
+        }
+    }
+
+    /// Copies the topic's state onto every membership, so members' topic
+    /// lists can leave archived and deleted topics out.
+    async fn relabel_memberships(&self, topic: &Topic) -> AppResult<()> {
+        for member in self.find_all_members(topic.topic_id).await? {
+            let user_topic = UserTopic {
+                topic_id: member.topic_id,
+                username: member.username.to_owned(),
+                created_at: member.created_at,
+                topic_kind: Some(topic.kind().to_string()),
+                topic_state: topic.topic_state.to_owned(),
+            };
+            self.topic_user_repo.add_topic_member(&member, &user_topic, None).await?;
+        }
+        Ok(())
     }
 }
 
-impl<TP> TopicAppInterface for TopicApp<TP>
+impl<TP, DC, GC, TU, UT, ID, RA, PA> TopicAppInterface for TopicApp<TP, DC, GC, TU, UT, ID, RA, PA>
 where
     TP: TopicRepository,
+    DC: DirectConversationRepository,
+    GC: GroupConversationRepository,
+    TU: TopicUserRepository,
+    UT: UserTopicRepository,
+    ID: UserIdentityRepository,
+    RA: TopicRoleAppInterface,
+    PA: TopicPurgeAppInterface,
 {
-    async fn create_topic(&self, req: RequestCreateTopic) -> AppResult<PublicTopic> {
+    async fn create_topic(&self, actor: &Actor, req: RequestCreateTopic) -> AppResult<PublicTopic> {
+        let mut topic = Topic::try_from(req)?;
+        // Whoever creates a topic owns it.
+        if !topic.topic_owners.contains(&actor.username) {
+            topic.topic_owners.push(actor.username.to_owned());
+        }
+
         self.topic_repo
-            .create_topic(&Topic::try_from(req)?)
+            .create_topic(&topic)
             .await
             .map(|topic| topic.try_into())?
     }
 
     async fn find_topic_by_partition_key(
         &self,
+        actor: &Actor,
         query: &RequestGetTopicByPartitionKey,
-    ) -> AppResult<Vec<PublicTopic>> {
-        let mut result: Vec<PublicTopic> = vec![];
-        self.topic_repo
-            .find_topic_by_partition_key(query)
-            .await
-            .map(|ref topic| {
-                for item in topic.iter() {
-                    result.push(item.try_into().expect("Can not parse data!"));
-                }
-            })
-            .expect("TODO: panic message");
-        Ok(result)
+    ) -> AppResult<Page<PublicTopic>> {
+        let topics = self.topic_repo.find_topic_by_partition_key(query).await?;
+        for topic in topics.items.iter() {
+            self.ensure_visible(actor, topic).await?;
+        }
+        topics.try_map(|item: &Topic| item.try_into())
     }
 
     async fn find_topic_by_primary_key(
         &self,
+        actor: &Actor,
         query: &RequestGetTopicByPrimaryKey,
     ) -> AppResult<PublicTopic> {
-        self.topic_repo
-            .find_topic_by_primary_key(query)
-            .await
-            .map(|ref topic| topic.try_into())?
+        let topic = self.topic_repo.find_topic_by_primary_key(query).await?;
+        self.ensure_visible(actor, &topic).await?;
+        PublicTopic::try_from(&topic)
     }
 
     async fn find_topic_by_index_key(
         &self,
+        actor: &Actor,
         query: &RequestGetTopicByIndexKey,
-    ) -> AppResult<Vec<PublicTopic>> {
-        let mut result: Vec<PublicTopic> = vec![];
-        self.topic_repo
-            .find_topic_by_index_key(query)
-            .await
-            .map(|ref topic| {
-                for item in topic.iter() {
-                    result.push(item.try_into().expect("Can not parse data!"));
+    ) -> AppResult<Page<PublicTopic>> {
+        let mut topics = self.topic_repo.find_topic_by_index_key(query).await?;
+        // Searches leave out topics the caller cannot see rather than failing.
+        let mut visible = Vec::with_capacity(topics.items.len());
+        for topic in topics.items {
+            if self.is_visible(actor, &topic).await? {
+                visible.push(topic);
+            }
+        }
+        topics.items = visible;
+        topics.try_map(|item: &Topic| item.try_into())
+    }
+
+    async fn update_topic(&self, actor: &Actor, topic: &RequestUpdateTopic) -> AppResult<PublicTopic> {
+        let access = self
+            .topic_role_app
+            .authorize(topic.topic_id, &actor.username, Capability::ManageSettings)
+            .await?;
+        let changes_owners = topic.push_to_owners.is_some() || topic.pop_to_owners.is_some();
+        if changes_owners && access.role != Role::Owner {
+            bail!(ApplicationError::PermissionDenied {
+                msg: "only topic owners can change the owners".to_string()
+            });
+        }
+        // Compared in milliseconds, the precision the cluster stores.
+        if let Some(expected_updated_at) = topic.expected_updated_at {
+            if expected_updated_at.timestamp_millis() != access.topic.updated_at.timestamp_millis() {
+                bail!(ApplicationError::topic_changed());
+            }
+        }
+        if topic.is_empty() {
+            return PublicTopic::try_from(&access.topic);
+        }
+
+        let updated = access.topic.with_update(topic);
+        if updated.topic_owners.is_empty() {
+            bail!(ApplicationError::invalid_field("pop_to_owners", "a topic needs at least one owner"));
+        }
+        self.replace_topic(&access.topic, &updated).await?;
+        PublicTopic::try_from(&updated)
+    }
+
+    async fn set_topic_role(&self, actor: &Actor, req: &RequestSetTopicRole) -> AppResult<PublicTopicRole> {
+        self.topic_role_app.set_topic_role(actor, req).await
+    }
+
+    async fn open_direct_conversation(
+        &self,
+        req: &RequestOpenDirectConversation,
+    ) -> AppResult<PublicDirectConversation> {
+        let peer_user_id = self.user_id_of(&req.peer_username).await?;
+        let (user_a, user_b) = DirectConversation::participants(&req.username, &req.peer_username);
+        let query = RequestGetDirectConversation { user_a, user_b };
+        let conversation = match self.direct_conversation_repo.find_direct_conversation(&query).await? {
+            Some(conversation) => conversation,
+            None => {
+                let conversation = DirectConversation {
+                    user_a: query.user_a,
+                    user_b: query.user_b,
+                    topic_id: now_timeuuid(),
+                    created_at: Utc::now(),
+                };
+                self.direct_conversation_repo
+                    .create_direct_conversation(&conversation)
+                    .await?
+            }
+        };
+
+        let usernames = [conversation.user_a.to_owned(), conversation.user_b.to_owned()];
+        let members = usernames.clone().map(|username| match username == req.username {
+            true => (username, req.user_id),
+            false => (username, peer_user_id),
+        });
+        self.ensure_conversation_topic(conversation.topic_id, conversation.created_at, &usernames, TOPIC_KIND_DIRECT)
+            .await?;
+        self.ensure_conversation_members(conversation.topic_id, conversation.created_at, &members, TOPIC_KIND_DIRECT)
+            .await?;
+
+        Ok(PublicDirectConversation {
+            topic_id: conversation.topic_id,
+            peer_username: req.peer_username.to_owned(),
+            created_at: conversation.created_at,
+        })
+    }
+
+    async fn open_group_conversation(
+        &self,
+        req: &RequestOpenGroupConversation,
+    ) -> AppResult<PublicGroupConversation> {
+        let mut members: Vec<(Text, Timeuuid)> = Vec::with_capacity(req.members.len() + 1);
+        for member in req.members.iter() {
+            members.push((member.username.to_owned(), self.user_id_of(&member.username).await?));
+        }
+        members.push((req.username.to_owned(), req.user_id));
+        members.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let usernames: Vec<Text> = members.iter().map(|(username, _)| username.to_owned()).collect();
+
+        let fresh = GroupConversation {
+            member_key: GroupConversation::member_key(&usernames),
+            members: usernames.clone(),
+            topic_id: now_timeuuid(),
+            created_at: Utc::now(),
+        };
+        let query = RequestGetGroupConversation {
+            member_key: fresh.member_key.to_owned(),
+        };
+        let conversation = match self.group_conversation_repo.find_group_conversation(&query).await? {
+            None => self.group_conversation_repo.create_group_conversation(&fresh).await?,
+            // Once promoted the topic is a named group, so the same set starts over.
+            Some(existing) => match self.find_topic(existing.topic_id).await? {
+                Some(topic) if !topic.is_conversation() => {
+                    self.group_conversation_repo
+                        .replace_group_conversation(&existing, &fresh)
+                        .await?
                 }
-            })
-            .expect("TODO: panic message");
-        Ok(result)
+                _ => existing,
+            },
+        };
+
+        self.ensure_conversation_topic(
+            conversation.topic_id,
+            conversation.created_at,
+            &usernames,
+            TOPIC_KIND_GROUP_DIRECT,
+        )
+        .await?;
+        self.ensure_conversation_members(
+            conversation.topic_id,
+            conversation.created_at,
+            &members,
+            TOPIC_KIND_GROUP_DIRECT,
+        )
+        .await?;
+
+        Ok(PublicGroupConversation {
+            topic_id: conversation.topic_id,
+            members: usernames,
+            created_at: conversation.created_at,
+        })
     }
 
-    async fn update_topic(&self, topic: &RequestUpdateTopic) -> AppResult<PublicTopic> {
-        self.topic_repo
-            .update_topic(topic)
-            .await
-            .map(|topic| PublicTopic::try_from(&topic).unwrap())
+    async fn promote_conversation(&self, req: &RequestPromoteConversation) -> AppResult<PublicTopic> {
+        let access = self
+            .topic_role_app
+            .authorize(req.topic_id, &req.username, Capability::Post)
+            .await?;
+        if access.topic.kind() != TOPIC_KIND_GROUP_DIRECT {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "only group conversations can be promoted".to_string()
+            });
+        }
+
+        let members = self.find_all_members(req.topic_id).await?;
+        let mut topic_owners = req.topic_owners.to_owned();
+        if !topic_owners.contains(&req.username) {
+            topic_owners.push(req.username.to_owned());
+        }
+        for owner in topic_owners.iter() {
+            if !members.iter().any(|member| &member.username == owner) {
+                bail!(ApplicationError::invalid_field(
+                    "topic_owners",
+                    format!("{owner} is not a member of the conversation")
+                ));
+            }
+        }
+
+        // Memberships are relabelled before the topic, so a retry after a
+        // partial failure still finds a conversation to promote.
+        for member in members.iter() {
+            let user_topic = UserTopic {
+                topic_id: member.topic_id,
+                username: member.username.to_owned(),
+                created_at: member.created_at,
+                topic_kind: Some(TOPIC_KIND_GROUP.to_string()),
+                topic_state: access.topic.topic_state.to_owned(),
+            };
+            self.topic_user_repo.add_topic_member(member, &user_topic, None).await?;
+        }
+
+        // Same key as before, so the row is overwritten in place and the
+        // messages, which hang off the topic id, are untouched.
+        let topic = Topic {
+            topic_name: req.topic_name.to_owned(),
+            topic_description: req.topic_description.to_owned(),
+            topic_owners,
+            updated_at: Utc::now(),
+            topic_kind: Some(TOPIC_KIND_GROUP.to_string()),
+            ..access.topic.clone()
+        };
+        self.replace_topic(&access.topic, &topic).await?;
+        PublicTopic::try_from(&topic)
+    }
+
+    async fn set_topic_state(&self, actor: &Actor, req: &RequestSetTopicState) -> AppResult<PublicTopic> {
+        // Read directly rather than through authorization, which hides deleted
+        // topics, so that deleting again can restart an interrupted purge.
+        let topic = match self.find_topic(req.topic_id).await? {
+            Some(topic) => topic,
+            None => bail!(ApplicationError::NotFound { resource: "topic" }),
+        };
+        if self.topic_role_app.find_role(&topic, &actor.username).await? != Some(Role::Owner) {
+            // Outsiders cannot tell a deleted topic from a missing one.
+            if topic.is_deleted() {
+                bail!(ApplicationError::NotFound { resource: "topic" });
+            }
+            bail!(ApplicationError::PermissionDenied {
+                msg: "only topic owners can change the topic state".to_string()
+            });
+        }
+        let state = match TopicState::parse(&req.topic_state) {
+            Some(state) => state,
+            None => bail!(ApplicationError::invalid_field("topic_state", "is not a known state")),
+        };
+
+        match (topic.state(), state) {
+            (TopicState::Deleted, TopicState::Deleted) => {
+                self.topic_purge_app.schedule_purge(topic.topic_id).await?;
+                self.topic_purge_app.spawn_purge(topic.topic_id);
+                return PublicTopic::try_from(&topic);
+            }
+            (TopicState::Deleted, _) => bail!(ApplicationError::FailedPrecondition {
+                msg: "deleted topics cannot be restored".to_string()
+            }),
+            (current, state) if current == state => return PublicTopic::try_from(&topic),
+            _ => (),
+        }
+
+        let updated = Topic {
+            topic_state: Some(state.as_str().to_string()),
+            updated_at: Utc::now(),
+            ..topic.clone()
+        };
+        // The purge is recorded before anything is marked deleted, so that a
+        // restart at any point after resumes it.
+        if state == TopicState::Deleted {
+            self.topic_purge_app.schedule_purge(updated.topic_id).await?;
+        }
+        // Memberships first, so a retry after a partial failure finds the
+        // topic still in its old state and relabels them again.
+        self.relabel_memberships(&updated).await?;
+        self.replace_topic(&topic, &updated).await?;
+        if state == TopicState::Deleted {
+            self.hub.revoke_access(updated.topic_id, None);
+            self.topic_purge_app.spawn_purge(updated.topic_id);
+        }
+        PublicTopic::try_from(&updated)
+    }
+
+    async fn find_list_roles_by_topic_id(
+        &self,
+        actor: &Actor,
+        query: &RequestGetRolesByTopicId,
+    ) -> AppResult<Page<PublicTopicRole>> {
+        self.topic_role_app.find_list_roles_by_topic_id(actor, query).await
     }
 
     // async fn get_full_field_topic(&self, query: &RequestGetTopicByTopicName) -> AppResult<Topic> {
@@ -1,11 +1,11 @@
 use anyhow::bail;
-use charybdis::types::{Timestamp, Timeuuid};
-use scylla::{SerializeRow, SerializeValue};
+use charybdis::types::{Text, Timestamp, Timeuuid};
 use serde::{Deserialize, Serialize};
-use std::cell::{Cell, RefCell};
-use thiserror::Error;
-use uptop_core::common::result::{AppError, AppResult};
+use uptop_core::common::result::AppResult;
 use validator::Validate;
+use crate::application::pagination::MAX_PAGE_SIZE;
+use crate::application::error::ApplicationError;
+use crate::domain::topic::entity::TopicState;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestCreateTopic {
@@ -20,9 +20,7 @@ impl RequestCreateTopic {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
@@ -37,26 +35,212 @@ impl RequestCreateTopic {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestGetTopicByPartitionKey {
     pub topic_id: Timeuuid,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
 }
 
 impl RequestGetTopicByPartitionKey {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
             topic_id: self.topic_id,
+            page_size: self.page_size,
+            page_token: self.page_token,
         })
     }
 }
 
-#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestGetTopicByIndexKey {
     pub topic_name: String,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
+}
+
+impl RequestGetTopicByIndexKey {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_name: self.topic_name,
+            page_size: self.page_size,
+            page_token: self.page_token,
+        })
+    }
+}
+
+/// Opens the direct conversation between the caller and `peer_username`,
+/// creating it on first use. The peer must have signed in before, which is
+/// where their user id comes from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestOpenDirectConversation {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
+    #[serde(default)]
+    pub user_id: Timeuuid,
+    #[validate(length(min = 1))]
+    pub peer_username: Text,
+}
+
+impl RequestOpenDirectConversation {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+        if self.peer_username == self.username {
+            bail!(ApplicationError::invalid_field(
+                "peer_username",
+                "cannot open a direct conversation with yourself"
+            ));
+        }
+
+        Ok(Self {
+            username: self.username,
+            user_id: self.user_id,
+            peer_username: self.peer_username,
+        })
+    }
+}
+
+/// Looks up the conversation of a pair; `user_a` sorts before `user_b`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetDirectConversation {
+    pub user_a: Text,
+    pub user_b: Text,
+}
+
+/// Group conversations hold this many users, the caller included. Two users
+/// talk in a direct conversation instead.
+pub const MIN_GROUP_CONVERSATION_MEMBERS: usize = 3;
+pub const MAX_GROUP_CONVERSATION_MEMBERS: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestConversationMember {
+    #[validate(length(min = 1))]
+    pub username: Text,
+}
+
+/// Opens the conversation of the caller and `members`, creating it on first
+/// use. As with direct conversations, every member must have signed in before.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestOpenGroupConversation {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
+    #[serde(default)]
+    pub user_id: Timeuuid,
+    /// Everyone else in the conversation.
+    #[validate(nested)]
+    pub members: Vec<RequestConversationMember>,
+}
+
+impl RequestOpenGroupConversation {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        let mut usernames: Vec<&str> = self.members.iter().map(|member| member.username.as_str()).collect();
+        usernames.push(&self.username);
+        usernames.sort_unstable();
+        if usernames.windows(2).any(|pair| pair[0] == pair[1]) {
+            bail!(ApplicationError::invalid_field(
+                "members",
+                "must name each user once and not include yourself"
+            ));
+        }
+        if !(MIN_GROUP_CONVERSATION_MEMBERS..=MAX_GROUP_CONVERSATION_MEMBERS).contains(&usernames.len()) {
+            bail!(ApplicationError::invalid_field(
+                "members",
+                format!(
+                    "a group conversation has {MIN_GROUP_CONVERSATION_MEMBERS} to \
+                     {MAX_GROUP_CONVERSATION_MEMBERS} members including you"
+                )
+            ));
+        }
+
+        Ok(Self {
+            username: self.username,
+            user_id: self.user_id,
+            members: self.members,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetGroupConversation {
+    pub member_key: Text,
+}
+
+/// Turns a group conversation into a named group topic. Its messages and
+/// members stay; `topic_owners` must be members and always include the caller.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestPromoteConversation {
+    pub topic_id: Timeuuid,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
+    #[validate(length(min = 1))]
+    pub topic_name: String,
+    #[validate(length(min = 3))]
+    pub topic_description: Option<String>,
+    #[serde(default)]
+    pub topic_owners: Vec<String>,
+}
+
+impl RequestPromoteConversation {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            username: self.username,
+            topic_name: self.topic_name,
+            topic_description: self.topic_description,
+            topic_owners: self.topic_owners,
+        })
+    }
+}
+
+/// Moves a topic to `active`, `locked`, `archived` or `deleted`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestSetTopicState {
+    pub topic_id: Timeuuid,
+    #[serde(default)]
+    pub username: Text,
+    pub topic_state: Text,
+}
+
+impl RequestSetTopicState {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        if TopicState::parse(&self.topic_state).is_none() {
+            bail!(ApplicationError::invalid_field(
+                "topic_state",
+                "must be one of active, locked, archived, deleted"
+            ));
+        }
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            username: self.username,
+            topic_state: self.topic_state,
+        })
+    }
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -65,31 +249,44 @@ pub struct RequestGetTopicByPrimaryKey {
     pub created_at: Timestamp,
 }
 
-#[derive(Debug, Error)]
-pub enum RequestFindTopicError {
-    #[error("Topic not found")]
-    TopicNotFound,
-}
 
+/// A partial update: fields left out stay as they are. Pushes are applied
+/// before pops.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestUpdateTopic {
     pub topic_id: Timeuuid,
+    #[validate(length(min = 1))]
     pub topic_name: Option<String>,
+    #[validate(length(min = 3))]
     pub topic_description: Option<String>,
     pub push_to_owners: Option<Vec<String>>,
     pub pop_to_owners: Option<Vec<String>>,
     pub push_to_admins: Option<Vec<String>>,
     pub pop_to_admins: Option<Vec<String>>,
+    /// The `updated_at` the caller last saw. When set, the update is refused
+    /// if the topic has changed since.
+    #[serde(default)]
+    pub expected_updated_at: Option<Timestamp>,
 }
 
 impl RequestUpdateTopic {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
+        for (field, pushed, popped) in [
+            ("pop_to_owners", &self.push_to_owners, &self.pop_to_owners),
+            ("pop_to_admins", &self.push_to_admins, &self.pop_to_admins),
+        ] {
+            let pushed = pushed.as_deref().unwrap_or_default();
+            if let Some(username) = popped.iter().flatten().find(|&username| pushed.contains(username)) {
+                bail!(ApplicationError::invalid_field(
+                    field,
+                    format!("{username} is both pushed and popped")
+                ));
+            }
+        }
 
         Ok(Self {
             topic_name: self.topic_name,
@@ -99,6 +296,16 @@ impl RequestUpdateTopic {
             push_to_admins: self.push_to_admins,
             pop_to_admins: self.pop_to_admins,
             topic_id: self.topic_id,
+            expected_updated_at: self.expected_updated_at,
         })
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.topic_name.is_none()
+            && self.topic_description.is_none()
+            && self.push_to_owners.is_none()
+            && self.pop_to_owners.is_none()
+            && self.push_to_admins.is_none()
+            && self.pop_to_admins.is_none()
+    }
 }
@@ -1,15 +1,21 @@
-use charybdis::types::{Text, Timestamp};
+use charybdis::types::{Text, Timestamp, Timeuuid};
 use serde::{Deserialize, Serialize};
 use uptop_core::common::result::AppResult;
 use crate::domain::topic::entity::Topic;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PublicTopic {
+    pub topic_id: Timeuuid,
     pub topic_name: Text,
     pub topic_description: Option<Text>,
     pub topic_owners: Vec<Text>,
     pub topic_admins: Vec<Text>,
     pub created_at: Timestamp,
+    /// Sent back as `expected_updated_at` to update the topic only if no one
+    /// else has in the meantime.
+    pub updated_at: Timestamp,
+    pub topic_kind: Text,
+    pub topic_state: Text,
 }
 
 impl TryFrom<&Topic> for PublicTopic {
@@ -17,11 +23,30 @@ impl TryFrom<&Topic> for PublicTopic {
 
     fn try_from(topic: &Topic) -> AppResult<Self> {
         Ok(Self {
+            topic_id: topic.topic_id,
             topic_name: topic.topic_name.to_owned(),
             topic_description: topic.topic_description.to_owned(),
             topic_owners: topic.topic_owners.to_owned(),
-            topic_admins: topic.topic_owners.to_owned(),
+            topic_admins: topic.topic_admins.to_owned(),
             created_at: topic.created_at,
+            updated_at: topic.updated_at,
+            topic_kind: topic.kind().to_string(),
+            topic_state: topic.state().as_str().to_string(),
         })
     }
 }
+
+/// A direct conversation as seen by one of its two members.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicDirectConversation {
+    pub topic_id: Timeuuid,
+    pub peer_username: Text,
+    pub created_at: Timestamp,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicGroupConversation {
+    pub topic_id: Timeuuid,
+    pub members: Vec<Text>,
+    pub created_at: Timestamp,
+}
@@ -1,70 +1,1446 @@
 use super::{
-    response::PublicTopicMessage,
+    response::{PublicMessageReader, PublicPinnedMessage, PublicReaction, PublicReadCursor, PublicTopicMessage, PublicTopicMessageRevision},
 };
-use crate::application::topic_message::request::{RequestGetMessagesByTopicId, RequestUpdateTopicMessage};
+use crate::application::actor::Actor;
+use crate::application::error::ApplicationError;
+use crate::application::live::event::{PinChange, ReactionChange, TopicEvent, TypingIndicator};
+use crate::application::live::presence::PresenceRegistry;
+use crate::application::live::hub::{TopicEventHub, TopicSubscription};
+use crate::application::pagination::{Page, MAX_PAGE_SIZE};
+use crate::application::topic_message::request::{RequestDeleteTopicMessage, RequestForwardTopicMessage, RequestGetHiddenMessages, RequestGetMessageReactions, RequestGetMessageRevisions, RequestGetThreadMessages, RequestGetMessagesByTopicId, RequestGetPinnedMessages, RequestGetReadCursor, RequestGetReadCursorsByTopicId, RequestGetTopicMessage, RequestMarkRead, RequestNotifyTyping, RequestPinTopicMessage, RequestPostTopicMessage, RequestReactTopicMessage, RequestSubscribeTopics, RequestUpdateTopicMessage, MAX_REPLAYED_MESSAGES};
+use crate::application::topic_role::app::TopicRoleAppInterface;
+use crate::application::topic_user::request::RequestGetUsersByTopicId;
+use crate::application::latest_message::app::count_unread;
+use crate::application::latest_message::request::RequestGetLatestMessage;
+use crate::application::notification::response::PublicNotification;
+use crate::domain::hidden_message::entity::HiddenMessage;
+use crate::domain::hidden_message::repository::HiddenMessageRepository;
+use crate::domain::latest_message::entity::LatestMessage;
+use crate::domain::latest_message::repository::LatestMessageRepository;
+use crate::domain::message_reaction::entity::MessageReaction;
+use crate::domain::message_reaction::repository::MessageReactionRepository;
+use crate::domain::notification::entity::Notification;
+use crate::domain::notification::repository::NotificationRepository;
+use crate::domain::pinned_message::entity::PinnedMessage;
+use crate::domain::pinned_message::repository::PinnedMessageRepository;
+use crate::domain::read_cursor::entity::ReadCursor;
+use crate::domain::read_cursor::repository::ReadCursorRepository;
+use crate::domain::thread_message::repository::ThreadMessageRepository;
 use crate::domain::topic_message::{repository::TopicMessageRepository};
-use std::{future::Future, sync::Arc};
+use crate::domain::topic_message_revision::entity::TopicMessageRevision;
+use crate::domain::topic_message_revision::repository::TopicMessageRevisionRepository;
+use crate::domain::topic_role::entity::Capability;
+use crate::domain::topic_user::entity::TopicUser;
+use crate::domain::topic_user::repository::TopicUserRepository;
+use crate::domain::unread_counter::entity::UnreadCounter;
+use crate::domain::unread_counter::repository::UnreadCounterRepository;
+use anyhow::bail;
+use charybdis::types::Timeuuid;
+use chrono::{Duration, Utc};
+use std::{collections::HashSet, env, future::Future, sync::Arc};
 use uptop_core::common::result::AppResult;
-use crate::domain::topic_message::entity::TopicMessage;
+use crate::domain::topic_message::entity::{TopicMessage, USER_MESSAGE};
 
+const DEFAULT_EDIT_WINDOW_SECS: i64 = 48 * 60 * 60;
+
+const DEFAULT_MAX_PINNED_MESSAGES: usize = 50;
+
+/// Largest topic that can list who read a message; beyond it receipts are
+/// too costly to gather and mostly noise.
+pub const READ_RECEIPTS_MAX_MEMBERS: i32 = 50;
+
+/// How long after posting a message can still be edited, read from
+/// `MESSAGE_EDIT_WINDOW_SECS`.
+pub fn edit_window() -> Duration {
+    let secs = env::var("MESSAGE_EDIT_WINDOW_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_EDIT_WINDOW_SECS);
+    Duration::seconds(secs)
+}
+
+/// How many messages a topic can have pinned at once, read from
+/// `MAX_PINNED_MESSAGES`.
+pub fn max_pinned_messages() -> usize {
+    env::var("MAX_PINNED_MESSAGES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_PINNED_MESSAGES)
+}
+
+/// Every call is checked against the caller's role in the topic first.
 pub trait TopicMessageAppInterface: Clone + Send + Sync + 'static {
+    fn post_message(
+        &self,
+        actor: &Actor,
+        req: RequestPostTopicMessage,
+    ) -> impl Future<Output=AppResult<PublicTopicMessage>> + Send;
+
+    /// Reposts a message the caller can read into another topic they can post
+    /// in, keeping the original author and topic as provenance.
+    fn forward_message(
+        &self,
+        actor: &Actor,
+        req: &RequestForwardTopicMessage,
+    ) -> impl Future<Output=AppResult<PublicTopicMessage>> + Send;
+
+    /// The topic timeline, with the reactions on each message. Thread replies
+    /// are left out, their parents carry the reply count and last reply time instead.
     fn find_list_messages_by_topic_id(
         &self,
+        actor: &Actor,
         query: &RequestGetMessagesByTopicId,
-    ) -> impl Future<Output=AppResult<Vec<PublicTopicMessage>>> + Send;
+    ) -> impl Future<Output=AppResult<Page<PublicTopicMessage>>> + Send;
 
+    /// Replies under one message, oldest first.
+    fn find_list_messages_by_thread(
+        &self,
+        actor: &Actor,
+        query: &RequestGetThreadMessages,
+    ) -> impl Future<Output=AppResult<Page<PublicTopicMessage>>> + Send;
+
+    /// Only the author, or someone allowed to edit any message, may edit, and
+    /// only within the edit window. The replaced body is kept as a revision.
     fn update_topic_message(
         &self,
+        actor: &Actor,
         topic_message: &RequestUpdateTopicMessage,
     ) -> impl Future<Output=AppResult<PublicTopicMessage>> + Send;
+
+    /// For everyone leaves a tombstone and needs the author, or the right to
+    /// delete any message; for the caller only it just hides the message.
+    fn delete_topic_message(
+        &self,
+        actor: &Actor,
+        req: &RequestDeleteTopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Removes the message without a trace, for moderators.
+    fn purge_topic_message(
+        &self,
+        actor: &Actor,
+        req: &RequestGetTopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn find_list_revisions_by_message_id(
+        &self,
+        actor: &Actor,
+        query: &RequestGetMessageRevisions,
+    ) -> impl Future<Output=AppResult<Page<PublicTopicMessageRevision>>> + Send;
+
+    /// Moves the caller's read cursor up to the message. Cursors never move
+    /// back, so marking an older message leaves the cursor where it is.
+    fn mark_read(
+        &self,
+        actor: &Actor,
+        req: &RequestMarkRead,
+    ) -> impl Future<Output=AppResult<PublicReadCursor>> + Send;
+
+    /// Members who have read up to the message, oldest read first. Only for
+    /// topics of at most [`READ_RECEIPTS_MAX_MEMBERS`] members.
+    fn find_list_message_readers(
+        &self,
+        actor: &Actor,
+        query: &RequestGetTopicMessage,
+    ) -> impl Future<Output=AppResult<Vec<PublicMessageReader>>> + Send;
+
+    /// Reacts to a message with an emoji, returning the message's reactions afterwards.
+    fn add_reaction(
+        &self,
+        actor: &Actor,
+        req: &RequestReactTopicMessage,
+    ) -> impl Future<Output=AppResult<Vec<PublicReaction>>> + Send;
+
+    fn remove_reaction(
+        &self,
+        actor: &Actor,
+        req: &RequestReactTopicMessage,
+    ) -> impl Future<Output=AppResult<Vec<PublicReaction>>> + Send;
+
+    /// Pins a timeline message, for those allowed to pin. The timeline gets a
+    /// system message quoting it. Pinning a pinned message changes nothing.
+    fn pin_message(
+        &self,
+        actor: &Actor,
+        req: &RequestPinTopicMessage,
+    ) -> impl Future<Output=AppResult<PublicPinnedMessage>> + Send;
+
+    fn unpin_message(
+        &self,
+        actor: &Actor,
+        req: &RequestPinTopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Pinned messages, most recently pinned first. Messages the caller hid are left out.
+    fn find_list_pinned_messages(
+        &self,
+        actor: &Actor,
+        query: &RequestGetPinnedMessages,
+    ) -> impl Future<Output=AppResult<Vec<PublicPinnedMessage>>> + Send;
+
+    fn subscribe_topics(
+        &self,
+        actor: &Actor,
+        req: &RequestSubscribeTopics,
+    ) -> impl Future<Output=AppResult<TopicSubscription>> + Send;
+
+    fn notify_typing(
+        &self,
+        actor: &Actor,
+        req: &RequestNotifyTyping,
+    ) -> impl Future<Output=AppResult<()>> + Send;
 }
 
 #[derive(Clone, Debug)]
-pub struct TopicMessageApp<TP>
+pub struct TopicMessageApp<TP, TR, HM, TH, MR, PM, RC, UC, TU, LM, NR, RA>
 where
     TP: TopicMessageRepository,
+    TR: TopicMessageRevisionRepository,
+    HM: HiddenMessageRepository,
+    TH: ThreadMessageRepository,
+    MR: MessageReactionRepository,
+    PM: PinnedMessageRepository,
+    RC: ReadCursorRepository,
+    UC: UnreadCounterRepository,
+    TU: TopicUserRepository,
+    LM: LatestMessageRepository,
+    NR: NotificationRepository,
+    RA: TopicRoleAppInterface,
 {
     topic_message_repo: Arc<TP>,
+    revision_repo: Arc<TR>,
+    hidden_message_repo: Arc<HM>,
+    thread_message_repo: Arc<TH>,
+    reaction_repo: Arc<MR>,
+    pinned_message_repo: Arc<PM>,
+    read_cursor_repo: Arc<RC>,
+    unread_counter_repo: Arc<UC>,
+    topic_user_repo: Arc<TU>,
+    latest_message_repo: Arc<LM>,
+    notification_repo: Arc<NR>,
+    topic_role_app: Arc<RA>,
+    hub: Arc<TopicEventHub>,
+    presence: Arc<PresenceRegistry>,
+    edit_window: Duration,
+    max_pinned_messages: usize,
 }
 
-impl<TP> TopicMessageApp<TP>
+impl<TP, TR, HM, TH, MR, PM, RC, UC, TU, LM, NR, RA> TopicMessageApp<TP, TR, HM, TH, MR, PM, RC, UC, TU, LM, NR, RA>
 where
     TP: TopicMessageRepository,
+    TR: TopicMessageRevisionRepository,
+    HM: HiddenMessageRepository,
+    TH: ThreadMessageRepository,
+    MR: MessageReactionRepository,
+    PM: PinnedMessageRepository,
+    RC: ReadCursorRepository,
+    UC: UnreadCounterRepository,
+    TU: TopicUserRepository,
+    LM: LatestMessageRepository,
+    NR: NotificationRepository,
+    RA: TopicRoleAppInterface,
 {
-    pub fn new(topic_message_repo: Arc<TP>) -> Self {
-        Self { topic_message_repo }
+    // One repository per table the use cases touch; a builder would only hide that.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        topic_message_repo: Arc<TP>,
+        revision_repo: Arc<TR>,
+        hidden_message_repo: Arc<HM>,
+        thread_message_repo: Arc<TH>,
+        reaction_repo: Arc<MR>,
+        pinned_message_repo: Arc<PM>,
+        read_cursor_repo: Arc<RC>,
+        unread_counter_repo: Arc<UC>,
+        topic_user_repo: Arc<TU>,
+        latest_message_repo: Arc<LM>,
+        notification_repo: Arc<NR>,
+        topic_role_app: Arc<RA>,
+        hub: Arc<TopicEventHub>,
+        presence: Arc<PresenceRegistry>,
+    ) -> Self {
+        Self {
+            topic_message_repo,
+            revision_repo,
+            hidden_message_repo,
+            thread_message_repo,
+            reaction_repo,
+            pinned_message_repo,
+            read_cursor_repo,
+            unread_counter_repo,
+            topic_user_repo,
+            latest_message_repo,
+            notification_repo,
+            topic_role_app,
+            hub,
+            presence,
+            edit_window: Duration::seconds(DEFAULT_EDIT_WINDOW_SECS),
+            max_pinned_messages: DEFAULT_MAX_PINNED_MESSAGES,
+        }
+    }
+
+    pub fn with_edit_window(mut self, edit_window: Duration) -> Self {
+        self.edit_window = edit_window;
+        self
+    }
+
+    pub fn with_max_pinned_messages(mut self, max_pinned_messages: usize) -> Self {
+        self.max_pinned_messages = max_pinned_messages;
+        self
     }
 }
 
-impl<TP> TopicMessageAppInterface for TopicMessageApp<TP>
+impl<TP, TR, HM, TH, MR, PM, RC, UC, TU, LM, NR, RA> TopicMessageApp<TP, TR, HM, TH, MR, PM, RC, UC, TU, LM, NR, RA>
 where
     TP: TopicMessageRepository,
+    TR: TopicMessageRevisionRepository,
+    HM: HiddenMessageRepository,
+    TH: ThreadMessageRepository,
+    MR: MessageReactionRepository,
+    PM: PinnedMessageRepository,
+    RC: ReadCursorRepository,
+    UC: UnreadCounterRepository,
+    TU: TopicUserRepository,
+    LM: LatestMessageRepository,
+    NR: NotificationRepository,
+    RA: TopicRoleAppInterface,
 {
+    /// Callers act as themselves; a sender field naming someone else is rejected.
+    fn ensure_sender(actor: &Actor, from_user_id: Timeuuid) -> AppResult<()> {
+        match actor.user_id == from_user_id {
+            true => Ok(()),
+            false => bail!(ApplicationError::PermissionDenied {
+                msg: "cannot act on behalf of another user".to_string()
+            }),
+        }
+    }
+
+    /// Fan-out needs every member, so walk the membership partition page by page.
+    async fn find_all_members(&self, topic_id: Timeuuid) -> AppResult<Vec<TopicUser>> {
+        let mut members: Vec<TopicUser> = vec![];
+        let mut query = RequestGetUsersByTopicId {
+            topic_id,
+            page_size: Some(MAX_PAGE_SIZE),
+            page_token: None,
+        };
+        loop {
+            let page = self.topic_user_repo.find_topic_users_by_partition_key(&query).await?;
+            members.extend(page.items);
+            match page.next_page_token {
+                Some(page_token) => query.page_token = Some(page_token),
+                None => return Ok(members),
+            }
+        }
+    }
+
+    /// Stores a new timeline message and fans it out to every member.
+    async fn publish_message(&self, actor: &Actor, topic_message: TopicMessage) -> AppResult<PublicTopicMessage> {
+        let members = self.find_all_members(topic_message.topic_id).await?;
+
+        // The message row is the source of truth, the fan-out below is derived from it
+        // and only upserts, so retrying a partially applied post converges.
+        self.topic_message_repo.create_topic_message(&topic_message).await?;
+
+        let now = Utc::now();
+        let latest_messages: Vec<LatestMessage> = members
+            .iter()
+            .map(|member| LatestMessage {
+                latest_message_id: topic_message.message_id,
+                latest_message_content: topic_message.message.to_owned(),
+                topic_id: topic_message.topic_id,
+                user_id: member.user_id,
+                created_at: topic_message.created_at,
+                updated_at: now,
+            })
+            .collect();
+        self.latest_message_repo.upsert_latest_messages(&latest_messages).await?;
+
+        let notifications: Vec<Notification> = members
+            .iter()
+            .filter(|member| member.user_id != actor.user_id)
+            .map(|member| Notification {
+                topic_id: topic_message.topic_id,
+                username: member.username.to_owned(),
+                from_user: actor.username.to_owned(),
+                message: topic_message.message.to_owned(),
+                created_at: topic_message.created_at,
+            })
+            .collect();
+        if !notifications.is_empty() {
+            self.notification_repo.create_notifications(&notifications).await?;
+        }
+
+        // Unlike the upserts above a retry counts twice; reconciliation repairs it.
+        let increments: Vec<UnreadCounter> = members
+            .iter()
+            .filter(|member| member.user_id != actor.user_id)
+            .map(|member| UnreadCounter {
+                user_id: member.user_id,
+                topic_id: topic_message.topic_id,
+                unread: 1,
+            })
+            .collect();
+        self.unread_counter_repo.increment_unread_counters(&increments).await?;
+
+        let posted = PublicTopicMessage::try_from(&topic_message)?;
+        // Subscribers treat the new message as the end of typing, so no stop is sent.
+        self.presence.stop_typing(topic_message.topic_id, &actor.username);
+        self.hub.publish(TopicEvent::MessageCreated(posted.clone()));
+        for notification in notifications.iter() {
+            self.hub.publish(TopicEvent::Notification(PublicNotification::try_from(notification)?));
+        }
+        Ok(posted)
+    }
+
+    async fn find_existing(&self, query: &RequestGetTopicMessage) -> AppResult<TopicMessage> {
+        match self.topic_message_repo.find_topic_message(query).await? {
+            Some(existing) => Ok(existing),
+            None => bail!(ApplicationError::NotFound { resource: "topic message" }),
+        }
+    }
+
+    /// Replies stay out of the timeline and the latest messages; only the
+    /// author of the parent is notified.
+    async fn post_reply(
+        &self,
+        actor: &Actor,
+        reply: TopicMessage,
+        parent_id: Timeuuid,
+    ) -> AppResult<PublicTopicMessage> {
+        let query = RequestGetTopicMessage {
+            topic_id: reply.topic_id,
+            message_id: parent_id,
+        };
+        let parent = self.find_existing(&query).await?;
+        if parent.is_reply() {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "replies cannot start a thread".to_string()
+            });
+        }
+        if parent.is_deleted() {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "cannot reply to a deleted message".to_string()
+            });
+        }
+
+        self.thread_message_repo.create_thread_reply(&reply).await?;
+        let parent = self.thread_message_repo.refresh_reply_stats(&parent).await?;
+
+        if parent.kind() == USER_MESSAGE && parent.from_user_id != actor.user_id {
+            let members = self.find_all_members(reply.topic_id).await?;
+            if let Some(author) = members.iter().find(|member| member.user_id == parent.from_user_id) {
+                let notification = Notification {
+                    topic_id: reply.topic_id,
+                    username: author.username.to_owned(),
+                    from_user: actor.username.to_owned(),
+                    message: reply.message.to_owned(),
+                    created_at: reply.created_at,
+                };
+                self.notification_repo.create_notifications(std::slice::from_ref(&notification)).await?;
+                self.hub.publish(TopicEvent::Notification(PublicNotification::try_from(&notification)?));
+            }
+        }
+
+        let posted = PublicTopicMessage::try_from(&reply)?;
+        self.hub.publish(TopicEvent::MessageCreated(posted.clone()));
+        self.hub.publish(TopicEvent::MessageUpdated(PublicTopicMessage::try_from(&parent)?));
+        Ok(posted)
+    }
+
+    /// Purges every reply under `parent`. The whole thread is read before
+    /// anything is removed so deleting cannot shift the pages being walked.
+    async fn purge_thread(&self, parent: &TopicMessage) -> AppResult<()> {
+        let mut replies: Vec<TopicMessage> = vec![];
+        let mut query = RequestGetThreadMessages {
+            topic_id: parent.topic_id,
+            parent_id: parent.message_id,
+            page_size: Some(MAX_PAGE_SIZE),
+            page_token: None,
+        };
+        loop {
+            let page = self.thread_message_repo.find_thread_messages_by_partition_key(&query).await?;
+            replies.extend(page.items);
+            match page.next_page_token {
+                Some(page_token) => query.page_token = Some(page_token),
+                None => break,
+            }
+        }
+
+        for reply in replies.iter() {
+            let reply_query = RequestGetTopicMessage {
+                topic_id: reply.topic_id,
+                message_id: reply.message_id,
+            };
+            self.topic_message_repo.purge_topic_message(&reply_query).await?;
+            self.reaction_repo.remove_message_reactions(&reply_query).await?;
+            self.thread_message_repo.remove_thread_reply(reply).await?;
+        }
+        Ok(())
+    }
+
+    /// Fills in the reactions of every message on the page, as seen by `user_id`.
+    async fn with_reactions(
+        &self,
+        user_id: Timeuuid,
+        topic_id: Timeuuid,
+        mut page: Page<PublicTopicMessage>,
+    ) -> AppResult<Page<PublicTopicMessage>> {
+        let query = RequestGetMessageReactions {
+            topic_id,
+            message_ids: page.items.iter().map(|item| item.message_id).collect(),
+        };
+        let reactions = self.reaction_repo.find_reactions_by_message_ids(&query).await?;
+        let mut tallies = PublicReaction::tally(&reactions, user_id);
+        for item in page.items.iter_mut() {
+            item.reactions = tallies.remove(&item.message_id).unwrap_or_default();
+        }
+        Ok(page)
+    }
+
+    /// Adds or removes the caller's reaction and tells subscribers the new count.
+    async fn react(&self, actor: &Actor, req: &RequestReactTopicMessage, added: bool) -> AppResult<Vec<PublicReaction>> {
+        Self::ensure_sender(actor, req.user_id)?;
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, Capability::Post)
+            .await?;
+        let query = RequestGetTopicMessage {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+        };
+        let existing = self.find_existing(&query).await?;
+        if added && existing.is_deleted() {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "cannot react to a deleted message".to_string()
+            });
+        }
+
+        let reaction = MessageReaction {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+            emoji: req.emoji.to_owned(),
+            user_id: actor.user_id,
+            reacted_at: Utc::now(),
+        };
+        match added {
+            true => self.reaction_repo.save_reaction(&reaction).await?,
+            false => self.reaction_repo.remove_reaction(&reaction).await?,
+        }
+
+        let query = RequestGetMessageReactions {
+            topic_id: req.topic_id,
+            message_ids: vec![req.message_id],
+        };
+        let reactions = self.reaction_repo.find_reactions_by_message_ids(&query).await?;
+        let reactions = PublicReaction::tally(&reactions, actor.user_id)
+            .remove(&req.message_id)
+            .unwrap_or_default();
+        let count = reactions
+            .iter()
+            .find(|public| public.emoji == req.emoji)
+            .map(|public| public.count)
+            .unwrap_or_default();
+
+        self.hub.publish(TopicEvent::ReactionChanged(ReactionChange {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+            user_id: actor.user_id,
+            emoji: req.emoji.to_owned(),
+            added,
+            count,
+        }));
+        Ok(reactions)
+    }
+
+    async fn find_pinned_message(&self, query: &RequestGetTopicMessage) -> AppResult<Option<PinnedMessage>> {
+        let pins_query = RequestGetPinnedMessages { topic_id: query.topic_id };
+        let pinned_messages = self.pinned_message_repo.find_pinned_messages(&pins_query).await?;
+        Ok(pinned_messages
+            .into_iter()
+            .find(|pinned_message| pinned_message.message_id == query.message_id))
+    }
+
+    /// Records the pin or unpin in the timeline, quoting the message, and
+    /// tells subscribers the pinned list changed.
+    async fn announce_pin(&self, actor: &Actor, message: &TopicMessage, pinned: bool) -> AppResult<()> {
+        let action = match pinned {
+            true => "pinned",
+            false => "unpinned",
+        };
+        let announcement = TopicMessage::system(message.topic_id, format!("{} {action} a message", actor.username))
+            .quoting(message);
+        self.topic_message_repo.create_topic_message(&announcement).await?;
+        self.hub.publish(TopicEvent::MessageCreated(PublicTopicMessage::try_from(&announcement)?));
+        self.hub.publish(TopicEvent::PinChanged(PinChange {
+            topic_id: message.topic_id,
+            message_id: message.message_id,
+            user_id: actor.user_id,
+            pinned,
+        }));
+        Ok(())
+    }
+
+    /// A message deleted for everyone or purged loses its pin without an
+    /// announcement; subscribers still learn the pinned list changed.
+    async fn drop_pin(&self, actor: &Actor, query: &RequestGetTopicMessage) -> AppResult<()> {
+        if self.find_pinned_message(query).await?.is_none() {
+            return Ok(());
+        }
+        self.pinned_message_repo.remove_pinned_message(query).await?;
+        self.hub.publish(TopicEvent::PinChanged(PinChange {
+            topic_id: query.topic_id,
+            message_id: query.message_id,
+            user_id: actor.user_id,
+            pinned: false,
+        }));
+        Ok(())
+    }
+
+    async fn find_hidden_ids(&self, user_id: Timeuuid, topic_id: Timeuuid) -> AppResult<HashSet<Timeuuid>> {
+        let query = RequestGetHiddenMessages { user_id, topic_id };
+        let hidden_messages = self.hidden_message_repo.find_hidden_messages(&query).await?;
+        Ok(hidden_messages.into_iter().map(|hidden| hidden.message_id).collect())
+    }
+
+    /// The newest message before `removed` that `user_id` can still see.
+    async fn find_previous_visible(
+        &self,
+        removed: &TopicMessage,
+        user_id: Timeuuid,
+    ) -> AppResult<Option<TopicMessage>> {
+        let hidden = self.find_hidden_ids(user_id, removed.topic_id).await?;
+        let mut query = RequestGetMessagesByTopicId {
+            topic_id: removed.topic_id,
+            page_size: Some(MAX_PAGE_SIZE),
+            page_token: None,
+            before: Some(removed.message_id),
+            after: None,
+        };
+        loop {
+            let page = self.topic_message_repo.find_topic_message_by_partition_key(&query).await?;
+            let visible = page
+                .items
+                .into_iter()
+                .find(|item| !item.is_deleted() && !hidden.contains(&item.message_id));
+            if visible.is_some() {
+                return Ok(visible);
+            }
+            match page.next_page_token {
+                Some(page_token) => query.page_token = Some(page_token),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Points the latest message of each user still showing `removed` at the
+    /// message before it, or clears it when nothing visible is left.
+    async fn fall_back_latest_messages(&self, removed: &TopicMessage, user_ids: &[Timeuuid]) -> AppResult<()> {
+        if removed.is_reply() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        let mut latest_messages: Vec<LatestMessage> = vec![];
+        for user_id in user_ids {
+            let query = RequestGetLatestMessage {
+                user_id: *user_id,
+                topic_id: removed.topic_id,
+            };
+            let latest = match self.latest_message_repo.find_latest_message(&query).await? {
+                Some(latest) if latest.latest_message_id == removed.message_id => latest,
+                _ => continue,
+            };
+            let latest = match self.find_previous_visible(removed, *user_id).await? {
+                Some(previous) => LatestMessage {
+                    latest_message_id: previous.message_id,
+                    latest_message_content: previous.message,
+                    created_at: previous.created_at,
+                    updated_at: now,
+                    ..latest
+                },
+                None => LatestMessage {
+                    latest_message_content: String::new(),
+                    updated_at: now,
+                    ..latest
+                },
+            };
+            latest_messages.push(latest);
+        }
+
+        if !latest_messages.is_empty() {
+            self.latest_message_repo.upsert_latest_messages(&latest_messages).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<TP, TR, HM, TH, MR, PM, RC, UC, TU, LM, NR, RA> TopicMessageAppInterface for TopicMessageApp<TP, TR, HM, TH, MR, PM, RC, UC, TU, LM, NR, RA>
+where
+    TP: TopicMessageRepository,
+    TR: TopicMessageRevisionRepository,
+    HM: HiddenMessageRepository,
+    TH: ThreadMessageRepository,
+    MR: MessageReactionRepository,
+    PM: PinnedMessageRepository,
+    RC: ReadCursorRepository,
+    UC: UnreadCounterRepository,
+    TU: TopicUserRepository,
+    LM: LatestMessageRepository,
+    NR: NotificationRepository,
+    RA: TopicRoleAppInterface,
+{
+    async fn post_message(&self, actor: &Actor, req: RequestPostTopicMessage) -> AppResult<PublicTopicMessage> {
+        Self::ensure_sender(actor, req.from_user_id)?;
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, Capability::Post)
+            .await?;
+
+        let mut topic_message = TopicMessage::try_from(req)?;
+        if let Some(quoted_message_id) = topic_message.quoted_message_id {
+            let query = RequestGetTopicMessage {
+                topic_id: topic_message.topic_id,
+                message_id: quoted_message_id,
+            };
+            let quoted = self.find_existing(&query).await?;
+            if quoted.is_deleted() {
+                bail!(ApplicationError::FailedPrecondition {
+                    msg: "cannot quote a deleted message".to_string()
+                });
+            }
+            topic_message = topic_message.quoting(&quoted);
+        }
+
+        match topic_message.parent_id {
+            Some(parent_id) => self.post_reply(actor, topic_message, parent_id).await,
+            None => self.publish_message(actor, topic_message).await,
+        }
+    }
+
+    async fn forward_message(
+        &self,
+        actor: &Actor,
+        req: &RequestForwardTopicMessage,
+    ) -> AppResult<PublicTopicMessage> {
+        Self::ensure_sender(actor, req.from_user_id)?;
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, Capability::Read)
+            .await?;
+        self.topic_role_app
+            .authorize(req.to_topic_id, &actor.username, Capability::Post)
+            .await?;
+
+        let query = RequestGetTopicMessage {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+        };
+        let source = self.find_existing(&query).await?;
+        if source.is_deleted() || source.kind() != USER_MESSAGE {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "only messages posted by users can be forwarded".to_string()
+            });
+        }
+
+        let forwarded = TopicMessage::forwarded(req.to_topic_id, actor.user_id, &source);
+        self.publish_message(actor, forwarded).await
+    }
+
     async fn find_list_messages_by_topic_id(
         &self,
+        actor: &Actor,
         query: &RequestGetMessagesByTopicId,
-    ) -> AppResult<Vec<PublicTopicMessage>> {
-        let mut result: Vec<PublicTopicMessage> = vec![];
-        self.topic_message_repo
+    ) -> AppResult<Page<PublicTopicMessage>> {
+        self.topic_role_app
+            .authorize(query.topic_id, &actor.username, Capability::Read)
+            .await?;
+        // Messages the caller hid are dropped after paging, so a page may
+        // come back short. Replies are never on the timeline.
+        let hidden = self.find_hidden_ids(actor.user_id, query.topic_id).await?;
+        let mut page = self.topic_message_repo
             .find_topic_message_by_partition_key(query)
-            .await
-            .map(|ref topic_message: Vec<TopicMessage>| {
-                for item in topic_message.iter() {
-                    result.push(item.try_into().expect("Can not parse data!"));
+            .await?;
+        page.items.retain(|item| !hidden.contains(&item.message_id));
+        let page = page.try_map(|item: &TopicMessage| item.try_into())?;
+        self.with_reactions(actor.user_id, query.topic_id, page).await
+    }
+
+    async fn find_list_messages_by_thread(
+        &self,
+        actor: &Actor,
+        query: &RequestGetThreadMessages,
+    ) -> AppResult<Page<PublicTopicMessage>> {
+        self.topic_role_app
+            .authorize(query.topic_id, &actor.username, Capability::Read)
+            .await?;
+        let hidden = self.find_hidden_ids(actor.user_id, query.topic_id).await?;
+        let mut page = self.thread_message_repo
+            .find_thread_messages_by_partition_key(query)
+            .await?;
+        page.items.retain(|item| !hidden.contains(&item.message_id));
+        let page = page.try_map(|item: &TopicMessage| item.try_into())?;
+        self.with_reactions(actor.user_id, query.topic_id, page).await
+    }
+
+    async fn update_topic_message(
+        &self,
+        actor: &Actor,
+        topic_message: &RequestUpdateTopicMessage,
+    ) -> AppResult<PublicTopicMessage> {
+        Self::ensure_sender(actor, topic_message.from_user_id)?;
+        let query = RequestGetTopicMessage {
+            topic_id: topic_message.topic_id,
+            message_id: topic_message.message_id,
+        };
+        let existing = self.find_existing(&query).await?;
+        if existing.is_deleted() {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "deleted messages cannot be edited".to_string()
+            });
+        }
+        if existing.kind() != USER_MESSAGE {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "system messages cannot be edited".to_string()
+            });
+        }
+        let capability = match existing.from_user_id == actor.user_id {
+            true => Capability::EditOwn,
+            false => Capability::EditAny,
+        };
+        self.topic_role_app
+            .authorize(topic_message.topic_id, &actor.username, capability)
+            .await?;
+
+        let now = Utc::now();
+        if now - existing.created_at > self.edit_window {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "the edit window for this message has passed".to_string()
+            });
+        }
+        // Resending the same body records nothing.
+        if existing.message == topic_message.message {
+            return PublicTopicMessage::try_from(&existing);
+        }
+
+        let revision = TopicMessageRevision::of(&existing, actor.user_id, now);
+        let updated = self.topic_message_repo
+            .update_topic_message(topic_message, &revision)
+            .await?;
+        let updated = PublicTopicMessage::try_from(&updated)?;
+
+        self.hub.publish(TopicEvent::MessageUpdated(updated.clone()));
+        Ok(updated)
+    }
+
+    async fn delete_topic_message(&self, actor: &Actor, req: &RequestDeleteTopicMessage) -> AppResult<()> {
+        let query = RequestGetTopicMessage {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+        };
+        let existing = self.find_existing(&query).await?;
+
+        if !req.for_everyone {
+            self.topic_role_app
+                .authorize(req.topic_id, &actor.username, Capability::Read)
+                .await?;
+            let hidden_message = HiddenMessage {
+                user_id: actor.user_id,
+                topic_id: req.topic_id,
+                message_id: req.message_id,
+                hidden_at: Utc::now(),
+            };
+            self.hidden_message_repo.save_hidden_message(&hidden_message).await?;
+            return self.fall_back_latest_messages(&existing, &[actor.user_id]).await;
+        }
+
+        let capability = match existing.kind() == USER_MESSAGE && existing.from_user_id == actor.user_id {
+            true => Capability::EditOwn,
+            false => Capability::Delete,
+        };
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, capability)
+            .await?;
+        if existing.is_deleted() {
+            return Ok(());
+        }
+
+        let tombstone = existing.tombstone(actor.user_id, Utc::now());
+        self.topic_message_repo.delete_topic_message(&tombstone).await?;
+        self.reaction_repo.remove_message_reactions(&query).await?;
+        self.drop_pin(actor, &query).await?;
+        self.hub.publish(TopicEvent::MessageDeleted(PublicTopicMessage::try_from(&tombstone)?));
+
+        let members = self.find_all_members(req.topic_id).await?;
+        let user_ids: Vec<Timeuuid> = members.iter().map(|member| member.user_id).collect();
+        self.fall_back_latest_messages(&existing, &user_ids).await
+    }
+
+    async fn purge_topic_message(&self, actor: &Actor, req: &RequestGetTopicMessage) -> AppResult<()> {
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, Capability::Delete)
+            .await?;
+        let existing = self.find_existing(req).await?;
+
+        match existing.parent_id {
+            Some(parent_id) => {
+                self.topic_message_repo.purge_topic_message(req).await?;
+                self.thread_message_repo.remove_thread_reply(&existing).await?;
+                let parent_query = RequestGetTopicMessage {
+                    topic_id: req.topic_id,
+                    message_id: parent_id,
+                };
+                if let Some(parent) = self.topic_message_repo.find_topic_message(&parent_query).await? {
+                    let parent = self.thread_message_repo.refresh_reply_stats(&parent).await?;
+                    self.hub.publish(TopicEvent::MessageUpdated(PublicTopicMessage::try_from(&parent)?));
                 }
+            }
+            None => {
+                self.purge_thread(&existing).await?;
+                self.topic_message_repo.purge_topic_message(req).await?;
+            }
+        }
+        self.reaction_repo.remove_message_reactions(req).await?;
+        self.drop_pin(actor, req).await?;
+        let purged = existing.tombstone(actor.user_id, Utc::now());
+        self.hub.publish(TopicEvent::MessageDeleted(PublicTopicMessage::try_from(&purged)?));
+
+        let members = self.find_all_members(req.topic_id).await?;
+        let user_ids: Vec<Timeuuid> = members.iter().map(|member| member.user_id).collect();
+        self.fall_back_latest_messages(&existing, &user_ids).await
+    }
+
+    async fn find_list_revisions_by_message_id(
+        &self,
+        actor: &Actor,
+        query: &RequestGetMessageRevisions,
+    ) -> AppResult<Page<PublicTopicMessageRevision>> {
+        self.topic_role_app
+            .authorize(query.topic_id, &actor.username, Capability::Read)
+            .await?;
+        self.revision_repo
+            .find_revisions_by_partition_key(query)
+            .await?
+            .try_map(|item: &TopicMessageRevision| item.try_into())
+    }
+
+    async fn mark_read(&self, actor: &Actor, req: &RequestMarkRead) -> AppResult<PublicReadCursor> {
+        Self::ensure_sender(actor, req.user_id)?;
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, Capability::Read)
+            .await?;
+        let query = RequestGetTopicMessage {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+        };
+        let message = self.find_existing(&query).await?;
+
+        let query = RequestGetReadCursor {
+            topic_id: req.topic_id,
+            user_id: actor.user_id,
+        };
+        if let Some(read_cursor) = self.read_cursor_repo.find_read_cursor(&query).await? {
+            if read_cursor.last_read_at >= message.created_at {
+                return PublicReadCursor::try_from(&read_cursor);
+            }
+        }
+
+        let read_cursor = ReadCursor {
+            topic_id: req.topic_id,
+            user_id: actor.user_id,
+            last_read_message_id: message.message_id,
+            last_read_at: message.created_at,
+            read_at: Utc::now(),
+        };
+        self.read_cursor_repo.save_read_cursor(&read_cursor).await?;
+
+        // Reading up to the latest message clears the counter without a recount.
+        let latest_query = RequestGetLatestMessage {
+            user_id: actor.user_id,
+            topic_id: req.topic_id,
+        };
+        let unread = match self.latest_message_repo.find_latest_message(&latest_query).await? {
+            Some(latest) if latest.created_at <= read_cursor.last_read_at => 0,
+            _ => {
+                let (unread, _) =
+                    count_unread(&*self.topic_message_repo, req.topic_id, actor.user_id, Some(&read_cursor)).await?;
+                unread
+            }
+        };
+        let counter = UnreadCounter {
+            user_id: actor.user_id,
+            topic_id: req.topic_id,
+            unread,
+        };
+        self.unread_counter_repo.reset_unread_counter(&counter).await?;
+        PublicReadCursor::try_from(&read_cursor)
+    }
+
+    async fn find_list_message_readers(
+        &self,
+        actor: &Actor,
+        query: &RequestGetTopicMessage,
+    ) -> AppResult<Vec<PublicMessageReader>> {
+        self.topic_role_app
+            .authorize(query.topic_id, &actor.username, Capability::Read)
+            .await?;
+        let message = self.find_existing(query).await?;
+
+        let members_query = RequestGetUsersByTopicId {
+            topic_id: query.topic_id,
+            page_size: Some(READ_RECEIPTS_MAX_MEMBERS + 1),
+            page_token: None,
+        };
+        let members = self.topic_user_repo.find_topic_users_by_partition_key(&members_query).await?;
+        if members.items.len() > READ_RECEIPTS_MAX_MEMBERS as usize {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: format!("read receipts are only kept for topics of at most {READ_RECEIPTS_MAX_MEMBERS} members")
+            });
+        }
+
+        let cursors_query = RequestGetReadCursorsByTopicId { topic_id: query.topic_id };
+        let read_cursors = self.read_cursor_repo.find_read_cursors_by_topic_id(&cursors_query).await?;
+        let mut readers: Vec<PublicMessageReader> = members
+            .items
+            .iter()
+            .filter(|member| member.user_id != message.from_user_id)
+            .filter_map(|member| {
+                read_cursors
+                    .iter()
+                    .find(|read_cursor| read_cursor.user_id == member.user_id)
+                    .filter(|read_cursor| read_cursor.last_read_at >= message.created_at)
+                    .map(|read_cursor| PublicMessageReader {
+                        user_id: member.user_id,
+                        username: member.username.to_owned(),
+                        read_at: read_cursor.read_at,
+                    })
             })
-            .expect("TODO: panic message");
-        Ok(result)
+            .collect();
+        readers.sort_by_key(|reader| reader.read_at);
+        Ok(readers)
     }
 
-    async fn update_topic_message(&self, topic_message: &RequestUpdateTopicMessage) -> AppResult<PublicTopicMessage> {
-        self.topic_message_repo
-            .update_topic_message(topic_message)
-            .await
-            .map(|topic_message| PublicTopicMessage::try_from(&topic_message).unwrap())
+    async fn add_reaction(&self, actor: &Actor, req: &RequestReactTopicMessage) -> AppResult<Vec<PublicReaction>> {
+        self.react(actor, req, true).await
+    }
+
+    async fn remove_reaction(&self, actor: &Actor, req: &RequestReactTopicMessage) -> AppResult<Vec<PublicReaction>> {
+        self.react(actor, req, false).await
+    }
+
+    async fn pin_message(&self, actor: &Actor, req: &RequestPinTopicMessage) -> AppResult<PublicPinnedMessage> {
+        Self::ensure_sender(actor, req.user_id)?;
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, Capability::Pin)
+            .await?;
+        let query = RequestGetTopicMessage {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+        };
+        let existing = self.find_existing(&query).await?;
+        if existing.is_deleted() {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "deleted messages cannot be pinned".to_string()
+            });
+        }
+        if existing.is_reply() {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "thread replies cannot be pinned".to_string()
+            });
+        }
+
+        let pins_query = RequestGetPinnedMessages { topic_id: req.topic_id };
+        let pinned_messages = self.pinned_message_repo.find_pinned_messages(&pins_query).await?;
+        if let Some(pinned_message) = pinned_messages.iter().find(|pinned| pinned.message_id == req.message_id) {
+            return Ok(PublicPinnedMessage {
+                message: PublicTopicMessage::try_from(&existing)?,
+                pinned_by: pinned_message.pinned_by,
+                pinned_at: pinned_message.pinned_at,
+            });
+        }
+        // Counted before writing, so admins pinning at the same moment can
+        // overshoot the cap by a pin or two.
+        if pinned_messages.len() >= self.max_pinned_messages {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: format!("a topic can have at most {} pinned messages", self.max_pinned_messages)
+            });
+        }
+
+        let pinned_message = PinnedMessage {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+            pinned_by: actor.user_id,
+            pinned_at: Utc::now(),
+        };
+        self.pinned_message_repo.save_pinned_message(&pinned_message).await?;
+        self.announce_pin(actor, &existing, true).await?;
+        Ok(PublicPinnedMessage {
+            message: PublicTopicMessage::try_from(&existing)?,
+            pinned_by: pinned_message.pinned_by,
+            pinned_at: pinned_message.pinned_at,
+        })
+    }
+
+    async fn unpin_message(&self, actor: &Actor, req: &RequestPinTopicMessage) -> AppResult<()> {
+        Self::ensure_sender(actor, req.user_id)?;
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, Capability::Pin)
+            .await?;
+        let query = RequestGetTopicMessage {
+            topic_id: req.topic_id,
+            message_id: req.message_id,
+        };
+        if self.find_pinned_message(&query).await?.is_none() {
+            return Ok(());
+        }
+        let existing = self.find_existing(&query).await?;
+
+        self.pinned_message_repo.remove_pinned_message(&query).await?;
+        self.announce_pin(actor, &existing, false).await
+    }
+
+    async fn find_list_pinned_messages(
+        &self,
+        actor: &Actor,
+        query: &RequestGetPinnedMessages,
+    ) -> AppResult<Vec<PublicPinnedMessage>> {
+        self.topic_role_app
+            .authorize(query.topic_id, &actor.username, Capability::Read)
+            .await?;
+        let hidden = self.find_hidden_ids(actor.user_id, query.topic_id).await?;
+        let mut pinned_messages = self.pinned_message_repo.find_pinned_messages(query).await?;
+        pinned_messages.sort_by_key(|pinned_message| std::cmp::Reverse(pinned_message.pinned_at));
+
+        let mut public: Vec<PublicPinnedMessage> = Vec::with_capacity(pinned_messages.len());
+        for pinned_message in pinned_messages {
+            if hidden.contains(&pinned_message.message_id) {
+                continue;
+            }
+            let message_query = RequestGetTopicMessage {
+                topic_id: pinned_message.topic_id,
+                message_id: pinned_message.message_id,
+            };
+            // A pin can outlive its message if deleting it failed halfway.
+            let message = match self.topic_message_repo.find_topic_message(&message_query).await? {
+                Some(message) if !message.is_deleted() => message,
+                _ => continue,
+            };
+            public.push(PublicPinnedMessage {
+                message: PublicTopicMessage::try_from(&message)?,
+                pinned_by: pinned_message.pinned_by,
+                pinned_at: pinned_message.pinned_at,
+            });
+        }
+        Ok(public)
+    }
+
+    async fn subscribe_topics(&self, actor: &Actor, req: &RequestSubscribeTopics) -> AppResult<TopicSubscription> {
+        // Subscribe before authorizing and reading the backlog, so that neither
+        // a revocation nor a message in between is missed.
+        let mut subscription = self.hub.subscribe(&actor.username, req.topic_ids.clone());
+        for topic_id in req.topic_ids.iter() {
+            self.topic_role_app
+                .authorize(*topic_id, &actor.username, Capability::Read)
+                .await?;
+        }
+
+        // One page past the limit tells whether the client is too far behind.
+        // The backlog shows what the timeline would, so hidden messages are
+        // left out.
+        let mut backlog: Vec<TopicMessage> = vec![];
+        for cursor in req.resume_from.iter() {
+            let query = RequestGetMessagesByTopicId {
+                topic_id: cursor.topic_id,
+                page_size: Some(MAX_REPLAYED_MESSAGES + 1),
+                page_token: None,
+                before: None,
+                after: Some(cursor.last_message_id),
+            };
+            let page = self.topic_message_repo.find_topic_message_by_partition_key(&query).await?;
+            if page.items.len() > MAX_REPLAYED_MESSAGES as usize || page.next_page_token.is_some() {
+                bail!(ApplicationError::FailedPrecondition {
+                    msg: format!(
+                        "more than {MAX_REPLAYED_MESSAGES} messages were posted in topic {} since {}, \
+                         list them with `after` and subscribe again from the newest",
+                        cursor.topic_id, cursor.last_message_id
+                    )
+                });
+            }
+            let hidden = self.find_hidden_ids(actor.user_id, cursor.topic_id).await?;
+            backlog.extend(
+                page.items
+                    .into_iter()
+                    .filter(|item| !hidden.contains(&item.message_id)),
+            );
+        }
+
+        backlog.sort_by_key(|item| item.created_at);
+        for item in backlog.iter() {
+            subscription.push_backlog(TopicEvent::MessageCreated(PublicTopicMessage::try_from(item)?));
+        }
+
+        Ok(subscription)
+    }
+
+    async fn notify_typing(&self, actor: &Actor, req: &RequestNotifyTyping) -> AppResult<()> {
+        if req.username != actor.username {
+            bail!(ApplicationError::PermissionDenied {
+                msg: "cannot act on behalf of another user".to_string()
+            });
+        }
+        self.topic_role_app
+            .authorize(req.topic_id, &actor.username, Capability::Post)
+            .await?;
+
+        let typing = match req.stopped {
+            true if !self.presence.stop_typing(req.topic_id, &req.username) => return Ok(()),
+            true => TypingIndicator {
+                topic_id: req.topic_id,
+                username: req.username.to_owned(),
+                stopped: true,
+                expires_at: None,
+            },
+            false => TypingIndicator {
+                topic_id: req.topic_id,
+                username: req.username.to_owned(),
+                stopped: false,
+                expires_at: Some(self.presence.start_typing(req.topic_id, &req.username)),
+            },
+        };
+        self.hub.publish(TopicEvent::Typing(typing));
+        Ok(())
     }
 
     // async fn get_full_field_topic_message(&self, query: &RequestGetTopicMessageByTopicMessageName) -> AppResult<TopicMessage> {
     //     self.latest_message_repo.find_latest_message(query).await
     // }
 }
+
+#[cfg(all(test, feature = "in-memory"))]
+mod tests {
+    use super::*;
+    use crate::application::latest_message::request::RequestGetUnreadSummary;
+    use crate::application::notification::request::RequestGetNotificationByUsername;
+    use crate::application::testing::{actor, TestApps};
+    use crate::infrastructure::persistence::FAN_OUT_CHUNK_SIZE;
+
+    fn post(topic_id: Timeuuid, author: &Actor, message: &str) -> RequestPostTopicMessage {
+        RequestPostTopicMessage {
+            topic_id,
+            from_user_id: author.user_id,
+            message: message.to_string(),
+            parent_id: None,
+            quoted_message_id: None,
+        }
+    }
+
+    fn react(topic_id: Timeuuid, message_id: Timeuuid, user: &Actor, emoji: &str) -> RequestReactTopicMessage {
+        RequestReactTopicMessage {
+            topic_id,
+            message_id,
+            user_id: user.user_id,
+            emoji: emoji.to_string(),
+        }
+    }
+
+    fn reaction(emoji: &str, count: i32, reacted_by_me: bool) -> PublicReaction {
+        PublicReaction {
+            emoji: emoji.to_string(),
+            count,
+            reacted_by_me,
+        }
+    }
+
+    fn is_permission_denied(err: &anyhow::Error) -> bool {
+        matches!(err.downcast_ref(), Some(ApplicationError::PermissionDenied { .. }))
+    }
+
+    #[tokio::test]
+    async fn acting_as_someone_else_or_outside_the_topic_is_refused() {
+        let apps = TestApps::new();
+        let (alice, bob, carol) = (actor("alice"), actor("bob"), actor("carol"));
+        let topic_id = apps.create_topic(&alice).await;
+        apps.join(topic_id, &bob).await;
+        let messages = &apps.topic_message_app;
+
+        let err = messages.post_message(&alice, post(topic_id, &bob, "hello")).await.unwrap_err();
+        assert!(is_permission_denied(&err));
+        let err = messages.post_message(&carol, post(topic_id, &carol, "hello")).await.unwrap_err();
+        assert!(is_permission_denied(&err));
+
+        let posted = messages.post_message(&alice, post(topic_id, &alice, "hello")).await.unwrap();
+        let err = messages.add_reaction(&bob, &react(topic_id, posted.message_id, &alice, "👍")).await.unwrap_err();
+        assert!(is_permission_denied(&err));
+        let typing = RequestNotifyTyping { topic_id, username: alice.username.to_owned(), stopped: false };
+        assert!(is_permission_denied(&messages.notify_typing(&bob, &typing).await.unwrap_err()));
+        let query = RequestGetMessagesByTopicId {
+            topic_id,
+            page_size: None,
+            page_token: None,
+            before: None,
+            after: None,
+        };
+        let err = messages.find_list_messages_by_topic_id(&carol, &query).await.unwrap_err();
+        assert!(is_permission_denied(&err));
+    }
+
+    #[tokio::test]
+    async fn only_moderators_delete_the_messages_of_others() {
+        let apps = TestApps::new();
+        let (alice, bob) = (actor("alice"), actor("bob"));
+        let topic_id = apps.create_topic(&alice).await;
+        apps.join(topic_id, &bob).await;
+        let messages = &apps.topic_message_app;
+        let from_alice = messages.post_message(&alice, post(topic_id, &alice, "hello")).await.unwrap();
+        let from_bob = messages.post_message(&bob, post(topic_id, &bob, "hi")).await.unwrap();
+
+        let req = RequestDeleteTopicMessage { topic_id, message_id: from_alice.message_id, for_everyone: true };
+        assert!(is_permission_denied(&messages.delete_topic_message(&bob, &req).await.unwrap_err()));
+        let edit = RequestUpdateTopicMessage {
+            topic_id,
+            message_id: from_alice.message_id,
+            from_user_id: bob.user_id,
+            message: "edited".to_string(),
+        };
+        assert!(is_permission_denied(&messages.update_topic_message(&bob, &edit).await.unwrap_err()));
+
+        let req = RequestDeleteTopicMessage { topic_id, message_id: from_bob.message_id, for_everyone: true };
+        messages.delete_topic_message(&alice, &req).await.unwrap();
+        let query = RequestGetTopicMessage { topic_id, message_id: from_bob.message_id };
+        let tombstone = apps.repos.topic_message.find_topic_message(&query).await.unwrap().unwrap();
+        assert_eq!((tombstone.is_deleted(), tombstone.deleted_by), (true, Some(alice.user_id)));
+    }
+
+    #[tokio::test]
+    async fn reactions_are_counted_per_emoji_and_user() {
+        let apps = TestApps::new();
+        let (alice, bob) = (actor("alice"), actor("bob"));
+        let topic_id = apps.create_topic(&alice).await;
+        apps.join(topic_id, &bob).await;
+        let messages = &apps.topic_message_app;
+        let posted = messages.post_message(&alice, post(topic_id, &alice, "hello")).await.unwrap();
+        let message_id = posted.message_id;
+
+        messages.add_reaction(&alice, &react(topic_id, message_id, &alice, "👍")).await.unwrap();
+        messages.add_reaction(&bob, &react(topic_id, message_id, &bob, "👍")).await.unwrap();
+        messages.add_reaction(&bob, &react(topic_id, message_id, &bob, "👍")).await.unwrap();
+        let reactions = messages.add_reaction(&bob, &react(topic_id, message_id, &bob, "🎉")).await.unwrap();
+        assert_eq!(reactions, vec![reaction("👍", 2, true), reaction("🎉", 1, true)]);
+
+        let reactions = messages.remove_reaction(&bob, &react(topic_id, message_id, &bob, "👍")).await.unwrap();
+        assert_eq!(reactions, vec![reaction("👍", 1, false), reaction("🎉", 1, true)]);
+
+        let query = RequestGetMessagesByTopicId {
+            topic_id,
+            page_size: Some(1),
+            page_token: None,
+            before: None,
+            after: None,
+        };
+        let page = messages.find_list_messages_by_topic_id(&alice, &query).await.unwrap();
+        assert_eq!(page.items[0].message_id, message_id);
+        assert_eq!(page.items[0].reactions, vec![reaction("👍", 1, true), reaction("🎉", 1, false)]);
+    }
+
+    #[tokio::test]
+    async fn the_timeline_pages_through_every_message_once() {
+        let apps = TestApps::new();
+        let alice = actor("alice");
+        let topic_id = apps.create_topic(&alice).await;
+        let messages = &apps.topic_message_app;
+        for i in 0..5 {
+            messages.post_message(&alice, post(topic_id, &alice, &format!("message {i}"))).await.unwrap();
+        }
+
+        let mut query = RequestGetMessagesByTopicId {
+            topic_id,
+            page_size: None,
+            page_token: None,
+            before: None,
+            after: None,
+        };
+        let everything: Vec<Timeuuid> = messages
+            .find_list_messages_by_topic_id(&alice, &query)
+            .await
+            .unwrap()
+            .items
+            .iter()
+            .map(|item| item.message_id)
+            .collect();
+        assert!(everything.len() > 5);
+
+        query.page_size = Some(2);
+        let mut paged: Vec<Timeuuid> = vec![];
+        loop {
+            let page = messages.find_list_messages_by_topic_id(&alice, &query).await.unwrap();
+            assert!(page.items.len() <= 2);
+            paged.extend(page.items.iter().map(|item| item.message_id));
+            match page.next_page_token {
+                Some(page_token) => query.page_token = Some(page_token),
+                None => break,
+            }
+        }
+        assert_eq!(paged, everything);
+    }
+
+    #[tokio::test]
+    async fn a_post_fans_out_to_members_beyond_one_batch() {
+        let apps = TestApps::new();
+        let alice = actor("alice");
+        let topic_id = apps.create_topic(&alice).await;
+        let members: Vec<Actor> = (0..FAN_OUT_CHUNK_SIZE * 2 + 1)
+            .map(|i| actor(&format!("member-{i}")))
+            .collect();
+        for member in &members {
+            apps.join(topic_id, member).await;
+        }
+
+        let posted = apps.topic_message_app.post_message(&alice, post(topic_id, &alice, "hello")).await.unwrap();
+
+        for member in &members {
+            let query = RequestGetLatestMessage { user_id: member.user_id, topic_id };
+            let latest = apps.repos.latest_message.find_latest_message(&query).await.unwrap();
+            assert_eq!(latest.map(|latest| latest.latest_message_id), Some(posted.message_id));
+
+            let query = RequestGetUnreadSummary { user_id: member.user_id };
+            let counters = apps.repos.unread_counter.find_unread_counters_by_user_id(&query).await.unwrap();
+            assert_eq!(counters.iter().map(|counter| counter.unread).sum::<i64>(), 1);
+
+            let query = RequestGetNotificationByUsername {
+                username: member.username.to_owned(),
+                page_size: None,
+                page_token: None,
+            };
+            let notifications = apps.repos.notification.find_notifications_by_partition_key(&query).await.unwrap();
+            assert_eq!(notifications.items.len(), 1);
+        }
+
+        // The author gets the latest message, but no notification of their own post.
+        let query = RequestGetNotificationByUsername {
+            username: alice.username.to_owned(),
+            page_size: None,
+            page_token: None,
+        };
+        let notifications = apps.repos.notification.find_notifications_by_partition_key(&query).await.unwrap();
+        assert!(notifications.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_post_notifies_only_the_other_members_subscriptions() {
+        let apps = TestApps::new();
+        let (alice, bob) = (actor("alice"), actor("bob"));
+        let topic_id = apps.create_topic(&alice).await;
+        apps.join(topic_id, &bob).await;
+        let req = RequestSubscribeTopics { topic_ids: vec![topic_id], resume_from: vec![] };
+        let mut alice_events = apps.topic_message_app.subscribe_topics(&alice, &req).await.unwrap();
+        let mut bob_events = apps.topic_message_app.subscribe_topics(&bob, &req).await.unwrap();
+
+        let posted = apps.topic_message_app.post_message(&alice, post(topic_id, &alice, "hello")).await.unwrap();
+        let typing = RequestNotifyTyping { topic_id, username: bob.username.to_owned(), stopped: false };
+        apps.topic_message_app.notify_typing(&bob, &typing).await.unwrap();
+
+        assert_eq!(bob_events.next().await.unwrap(), Some(TopicEvent::MessageCreated(posted.clone())));
+        match bob_events.next().await.unwrap() {
+            Some(TopicEvent::Notification(notification)) => {
+                assert_eq!((notification.topic_id, notification.from_user.as_str()), (topic_id, "alice"));
+            }
+            event => panic!("expected a notification, got {event:?}"),
+        }
+        assert_eq!(alice_events.next().await.unwrap(), Some(TopicEvent::MessageCreated(posted)));
+        assert!(matches!(alice_events.next().await.unwrap(), Some(TopicEvent::Typing(_))));
+    }
+}
@@ -1,14 +1,18 @@
 use anyhow::bail;
 use charybdis::types::{Text, Timeuuid};
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
-use uptop_core::common::result::{AppError, AppResult};
+use uptop_core::common::result::AppResult;
 use validator::Validate;
+use crate::application::pagination::MAX_PAGE_SIZE;
+use crate::application::error::ApplicationError;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestUpdateTopicMessage {
     pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    #[serde(default)]
     pub from_user_id: Timeuuid,
+    #[validate(length(min = 1))]
     pub message: Text,
 }
 
@@ -16,41 +20,319 @@ impl RequestUpdateTopicMessage {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
             topic_id: self.topic_id,
+            message_id: self.message_id,
             from_user_id: self.from_user_id,
             message: self.message,
         })
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestPostTopicMessage {
+    pub topic_id: Timeuuid,
+    #[serde(default)]
+    pub from_user_id: Timeuuid,
+    #[validate(length(min = 1))]
+    pub message: Text,
+    /// Reply in the thread under this message instead of the topic timeline.
+    #[serde(default)]
+    pub parent_id: Option<Timeuuid>,
+    /// A message of the same topic to quote.
+    #[serde(default)]
+    pub quoted_message_id: Option<Timeuuid>,
+}
+
+impl RequestPostTopicMessage {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            from_user_id: self.from_user_id,
+            message: self.message,
+            parent_id: self.parent_id,
+            quoted_message_id: self.quoted_message_id,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestForwardTopicMessage {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub to_topic_id: Timeuuid,
+    #[serde(default)]
+    pub from_user_id: Timeuuid,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestGetMessagesByTopicId {
     pub topic_id: Timeuuid,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
+    /// Only messages older than this message id, newest first.
+    pub before: Option<Timeuuid>,
+    /// Only messages newer than this message id, oldest first.
+    pub after: Option<Timeuuid>,
 }
 
 impl RequestGetMessagesByTopicId {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            page_size: self.page_size,
+            page_token: self.page_token,
+            before: self.before,
+            after: self.after,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetTopicMessage {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestGetMessageRevisions {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
+}
+
+impl RequestGetMessageRevisions {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
             topic_id: self.topic_id,
+            message_id: self.message_id,
+            page_size: self.page_size,
+            page_token: self.page_token,
         })
     }
 }
 
-#[derive(Debug, Error)]
-pub enum RequestFindLatestMessageError {
-    #[error("LatestMessage not found")]
-    LatestMessageNotFound,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestGetThreadMessages {
+    pub topic_id: Timeuuid,
+    pub parent_id: Timeuuid,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
+}
+
+impl RequestGetThreadMessages {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            parent_id: self.parent_id,
+            page_size: self.page_size,
+            page_token: self.page_token,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestDeleteTopicMessage {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    /// Leave a tombstone everyone sees, rather than hiding it for the caller only.
+    #[serde(default)]
+    pub for_everyone: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetHiddenMessages {
+    pub user_id: Timeuuid,
+    pub topic_id: Timeuuid,
+}
+
+/// Longest accepted emoji in bytes, enough for joined sequences like family emoji.
+const MAX_EMOJI_LEN: u64 = 64;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestReactTopicMessage {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    #[serde(default)]
+    pub user_id: Timeuuid,
+    /// A unicode emoji, or a custom shortcode such as `:party_parrot:`.
+    #[validate(length(min = 1, max = MAX_EMOJI_LEN))]
+    pub emoji: Text,
+}
+
+impl RequestReactTopicMessage {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+        if !is_emoji(&self.emoji) {
+            bail!(ApplicationError::invalid_field(
+                "emoji",
+                "must be a unicode emoji or a :shortcode:"
+            ));
+        }
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            message_id: self.message_id,
+            user_id: self.user_id,
+            emoji: self.emoji,
+        })
+    }
+}
+
+/// Shortcodes are ASCII names between colons. Anything else must be free of
+/// ASCII letters, whitespace and control characters, which keeps plain text
+/// out while accepting skin tones, joiners and flags. Keycaps such as 1️⃣
+/// start with an ASCII digit, `#` or `*`.
+fn is_emoji(emoji: &str) -> bool {
+    if let Some(name) = emoji.strip_prefix(':').and_then(|rest| rest.strip_suffix(':')) {
+        return !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'));
+    }
+    !emoji.is_ascii()
+        && emoji.chars().all(|c| {
+            !c.is_control() && !c.is_whitespace() && (!c.is_ascii() || c.is_ascii_digit() || matches!(c, '#' | '*'))
+        })
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetMessageReactions {
+    pub topic_id: Timeuuid,
+    pub message_ids: Vec<Timeuuid>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestPinTopicMessage {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    #[serde(default)]
+    pub user_id: Timeuuid,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetPinnedMessages {
+    pub topic_id: Timeuuid,
+}
+
+/// Marks everything up to and including `message_id` as read.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestMarkRead {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    #[serde(default)]
+    pub user_id: Timeuuid,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetReadCursor {
+    pub topic_id: Timeuuid,
+    pub user_id: Timeuuid,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetReadCursorsByTopicId {
+    pub topic_id: Timeuuid,
+}
+
+/// Most messages replayed per topic when a subscription resumes. A client
+/// further behind pages the timeline with `after` instead.
+pub const MAX_REPLAYED_MESSAGES: i32 = 200;
+
+/// The newest message a client already has in one topic.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestTopicCursor {
+    pub topic_id: Timeuuid,
+    pub last_message_id: Timeuuid,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestSubscribeTopics {
+    #[validate(length(min = 1))]
+    pub topic_ids: Vec<Timeuuid>,
+    /// Resume points: the timeline messages after each cursor are replayed
+    /// before live events. Topics without a cursor are not replayed.
+    #[serde(default)]
+    pub resume_from: Vec<RequestTopicCursor>,
+}
+
+impl RequestSubscribeTopics {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        let mut cursor_topic_ids: Vec<&Timeuuid> = self.resume_from.iter().map(|cursor| &cursor.topic_id).collect();
+        cursor_topic_ids.sort_unstable();
+        if cursor_topic_ids.windows(2).any(|pair| pair[0] == pair[1])
+            || cursor_topic_ids.iter().any(|topic_id| !self.topic_ids.contains(topic_id))
+        {
+            bail!(ApplicationError::invalid_field(
+                "resume_from",
+                "must hold at most one cursor per subscribed topic"
+            ));
+        }
+
+        Ok(Self {
+            topic_ids: self.topic_ids,
+            resume_from: self.resume_from,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestNotifyTyping {
+    pub topic_id: Timeuuid,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
+    /// Clears the indicator straight away instead of waiting for it to lapse.
+    #[serde(default)]
+    pub stopped: bool,
+}
+
+impl RequestNotifyTyping {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            username: self.username,
+            stopped: self.stopped,
+        })
+    }
 }
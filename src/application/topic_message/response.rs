@@ -1,13 +1,81 @@
-use charybdis::types::{Text, Timeuuid};
+use charybdis::types::{Text, Timestamp, Timeuuid};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uptop_core::common::result::AppResult;
+use crate::domain::message_reaction::entity::MessageReaction;
+use crate::domain::read_cursor::entity::ReadCursor;
 use crate::domain::topic_message::entity::TopicMessage;
+use crate::domain::topic_message_revision::entity::TopicMessageRevision;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PublicTopicMessage {
     pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
     pub from_user_id: Timeuuid,
     pub message: Text,
+    pub created_at: Timestamp,
+    pub kind: Text,
+    pub edited: bool,
+    pub edited_at: Option<Timestamp>,
+    /// A tombstone: deleted for everyone, `message` is empty.
+    pub deleted: bool,
+    pub deleted_at: Option<Timestamp>,
+    pub parent_id: Option<Timeuuid>,
+    pub reply_count: i32,
+    pub last_reply_at: Option<Timestamp>,
+    pub quote: Option<PublicQuotedMessage>,
+    pub forwarded_from: Option<PublicForwardedFrom>,
+    /// Only filled when listing; events and single message responses leave it empty.
+    pub reactions: Vec<PublicReaction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicQuotedMessage {
+    pub message_id: Timeuuid,
+    pub from_user_id: Timeuuid,
+    pub message: Text,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicForwardedFrom {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub from_user_id: Timeuuid,
+}
+
+/// How many users reacted to a message with one emoji.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicReaction {
+    pub emoji: Text,
+    pub count: i32,
+    pub reacted_by_me: bool,
+}
+
+impl PublicReaction {
+    /// Groups reactions per message and emoji, emoji in the order they were
+    /// first used on the message.
+    pub fn tally(reactions: &[MessageReaction], user_id: Timeuuid) -> HashMap<Timeuuid, Vec<PublicReaction>> {
+        let mut reactions: Vec<&MessageReaction> = reactions.iter().collect();
+        reactions.sort_by_key(|reaction| reaction.reacted_at);
+
+        let mut tallies: HashMap<Timeuuid, Vec<PublicReaction>> = HashMap::new();
+        for reaction in reactions {
+            let tally = tallies.entry(reaction.message_id).or_default();
+            let reacted_by_me = reaction.user_id == user_id;
+            match tally.iter_mut().find(|public| public.emoji == reaction.emoji) {
+                Some(public) => {
+                    public.count += 1;
+                    public.reacted_by_me |= reacted_by_me;
+                }
+                None => tally.push(PublicReaction {
+                    emoji: reaction.emoji.to_owned(),
+                    count: 1,
+                    reacted_by_me,
+                }),
+            }
+        }
+        tallies
+    }
 }
 
 impl TryFrom<&TopicMessage> for PublicTopicMessage {
@@ -16,8 +84,92 @@ impl TryFrom<&TopicMessage> for PublicTopicMessage {
     fn try_from(topic_message: &TopicMessage) -> AppResult<Self> {
         Ok(Self {
             topic_id: topic_message.topic_id,
+            message_id: topic_message.message_id,
             message: (*topic_message.message).parse()?,
             from_user_id: topic_message.from_user_id,
+            created_at: topic_message.created_at,
+            kind: topic_message.kind().to_string(),
+            edited: topic_message.edited_at.is_some(),
+            edited_at: topic_message.edited_at,
+            deleted: topic_message.is_deleted(),
+            deleted_at: topic_message.deleted_at,
+            parent_id: topic_message.parent_id,
+            reply_count: topic_message.reply_count.unwrap_or_default(),
+            last_reply_at: topic_message.last_reply_at,
+            quote: topic_message.quoted_message_id.map(|message_id| PublicQuotedMessage {
+                message_id,
+                from_user_id: topic_message.quoted_from_user_id.unwrap_or_default(),
+                message: topic_message.quoted_message.to_owned().unwrap_or_default(),
+            }),
+            forwarded_from: topic_message.forwarded_from_message_id.map(|message_id| PublicForwardedFrom {
+                topic_id: topic_message.forwarded_from_topic_id.unwrap_or_default(),
+                message_id,
+                from_user_id: topic_message.forwarded_from_user_id.unwrap_or_default(),
+            }),
+            reactions: vec![],
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicTopicMessageRevision {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub revision_id: Timeuuid,
+    pub edited_by: Timeuuid,
+    pub edited_at: Timestamp,
+    /// The body this edit replaced.
+    pub message: Text,
+}
+
+impl TryFrom<&TopicMessageRevision> for PublicTopicMessageRevision {
+    type Error = anyhow::Error;
+
+    fn try_from(revision: &TopicMessageRevision) -> AppResult<Self> {
+        Ok(Self {
+            topic_id: revision.topic_id,
+            message_id: revision.message_id,
+            revision_id: revision.revision_id,
+            edited_by: revision.edited_by,
+            edited_at: revision.edited_at,
+            message: revision.message.to_owned(),
         })
     }
 }
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicReadCursor {
+    pub topic_id: Timeuuid,
+    pub user_id: Timeuuid,
+    pub last_read_message_id: Timeuuid,
+    pub read_at: Timestamp,
+}
+
+impl TryFrom<&ReadCursor> for PublicReadCursor {
+    type Error = anyhow::Error;
+
+    fn try_from(read_cursor: &ReadCursor) -> AppResult<Self> {
+        Ok(Self {
+            topic_id: read_cursor.topic_id,
+            user_id: read_cursor.user_id,
+            last_read_message_id: read_cursor.last_read_message_id,
+            read_at: read_cursor.read_at,
+        })
+    }
+}
+
+/// A member who has read up to a message, and when they got there.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicMessageReader {
+    pub user_id: Timeuuid,
+    pub username: Text,
+    pub read_at: Timestamp,
+}
+
+/// A pinned message as it reads now, with who pinned it and when.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicPinnedMessage {
+    pub message: PublicTopicMessage,
+    pub pinned_by: Timeuuid,
+    pub pinned_at: Timestamp,
+}
@@ -0,0 +1,456 @@
+use crate::application::latest_message::request::RequestGetLatestMessage;
+use crate::application::notification::request::RequestRemoveTopicNotifications;
+use crate::application::pagination::MAX_PAGE_SIZE;
+use crate::application::topic::request::RequestGetTopicByPartitionKey;
+use crate::application::topic_message::request::RequestGetHiddenMessages;
+use crate::application::topic_user::request::RequestGetUsersByTopicId;
+use crate::domain::hidden_message::repository::HiddenMessageRepository;
+use crate::domain::latest_message::repository::LatestMessageRepository;
+use crate::domain::message_reaction::repository::MessageReactionRepository;
+use crate::domain::notification::repository::NotificationRepository;
+use crate::domain::pinned_message::repository::PinnedMessageRepository;
+use crate::domain::read_cursor::repository::ReadCursorRepository;
+use crate::domain::thread_message::repository::ThreadMessageRepository;
+use crate::domain::topic::repository::TopicRepository;
+use crate::domain::topic_invite::repository::TopicInviteRepository;
+use crate::domain::topic_message::repository::TopicMessageRepository;
+use crate::domain::topic_purge::{entity::TopicPurge, repository::TopicPurgeRepository};
+use crate::domain::topic_role::repository::TopicRoleRepository;
+use crate::domain::topic_user::{entity::TopicUser, repository::TopicUserRepository};
+use crate::domain::unread_counter::{entity::UnreadCounter, repository::UnreadCounterRepository};
+use crate::domain::user_topic::entity::UserTopic;
+use charybdis::types::Timeuuid;
+use chrono::Utc;
+use std::{future::Future, sync::Arc};
+use uptop_core::common::result::AppResult;
+
+/// Removes what hangs off deleted topics. A purge is recorded before the
+/// topic is marked deleted and forgotten only once it has run to the end.
+pub trait TopicPurgeAppInterface: Clone + Send + Sync + 'static {
+    /// Records that the topic is to be purged, so that the purge is resumed
+    /// after a restart. Recording a topic that ends up not deleted is harmless.
+    fn schedule_purge(&self, topic_id: Timeuuid) -> impl Future<Output = AppResult<()>> + Send;
+
+    /// Runs the purge of a scheduled topic in the background.
+    fn spawn_purge(&self, topic_id: Timeuuid);
+
+    /// Purges the topic if it is deleted, then forgets the scheduled purge.
+    /// Safe to run again after it failed halfway.
+    fn purge_topic(&self, topic_id: Timeuuid) -> impl Future<Output = AppResult<()>> + Send;
+
+    /// The topics whose purge was scheduled and has not finished.
+    fn find_pending_purges(&self) -> impl Future<Output = AppResult<Vec<Timeuuid>>> + Send;
+}
+
+/// Runs the purges left pending by the previous run of the server, one after
+/// the other. A failed purge is logged and stays pending for the next start.
+pub async fn resume_topic_purges<P: TopicPurgeAppInterface>(topic_purge_app: Arc<P>) {
+    let topic_ids = match topic_purge_app.find_pending_purges().await {
+        Ok(topic_ids) => topic_ids,
+        Err(err) => {
+            tracing::error!("{err:?}");
+            return;
+        }
+    };
+    for topic_id in topic_ids {
+        match topic_purge_app.purge_topic(topic_id).await {
+            Ok(()) => tracing::info!(%topic_id, "resumed purge of deleted topic"),
+            Err(err) => tracing::error!("purging topic {topic_id}: {err:?}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TopicPurgeApp<TP, PR, TU, TM, TH, MR, PM, RC, RR, TI, HM, LM, NR, UC>
+where
+    TP: TopicRepository,
+    PR: TopicPurgeRepository,
+    TU: TopicUserRepository,
+    TM: TopicMessageRepository,
+    TH: ThreadMessageRepository,
+    MR: MessageReactionRepository,
+    PM: PinnedMessageRepository,
+    RC: ReadCursorRepository,
+    RR: TopicRoleRepository,
+    TI: TopicInviteRepository,
+    HM: HiddenMessageRepository,
+    LM: LatestMessageRepository,
+    NR: NotificationRepository,
+    UC: UnreadCounterRepository,
+{
+    topic_repo: Arc<TP>,
+    topic_purge_repo: Arc<PR>,
+    topic_user_repo: Arc<TU>,
+    topic_message_repo: Arc<TM>,
+    thread_message_repo: Arc<TH>,
+    reaction_repo: Arc<MR>,
+    pinned_message_repo: Arc<PM>,
+    read_cursor_repo: Arc<RC>,
+    topic_role_repo: Arc<RR>,
+    topic_invite_repo: Arc<TI>,
+    hidden_message_repo: Arc<HM>,
+    latest_message_repo: Arc<LM>,
+    notification_repo: Arc<NR>,
+    unread_counter_repo: Arc<UC>,
+}
+
+impl<TP, PR, TU, TM, TH, MR, PM, RC, RR, TI, HM, LM, NR, UC> TopicPurgeApp<TP, PR, TU, TM, TH, MR, PM, RC, RR, TI, HM, LM, NR, UC>
+where
+    TP: TopicRepository,
+    PR: TopicPurgeRepository,
+    TU: TopicUserRepository,
+    TM: TopicMessageRepository,
+    TH: ThreadMessageRepository,
+    MR: MessageReactionRepository,
+    PM: PinnedMessageRepository,
+    RC: ReadCursorRepository,
+    RR: TopicRoleRepository,
+    TI: TopicInviteRepository,
+    HM: HiddenMessageRepository,
+    LM: LatestMessageRepository,
+    NR: NotificationRepository,
+    UC: UnreadCounterRepository,
+{
+    // One repository per table the purge empties; a builder would only hide that.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        topic_repo: Arc<TP>,
+        topic_purge_repo: Arc<PR>,
+        topic_user_repo: Arc<TU>,
+        topic_message_repo: Arc<TM>,
+        thread_message_repo: Arc<TH>,
+        reaction_repo: Arc<MR>,
+        pinned_message_repo: Arc<PM>,
+        read_cursor_repo: Arc<RC>,
+        topic_role_repo: Arc<RR>,
+        topic_invite_repo: Arc<TI>,
+        hidden_message_repo: Arc<HM>,
+        latest_message_repo: Arc<LM>,
+        notification_repo: Arc<NR>,
+        unread_counter_repo: Arc<UC>,
+    ) -> Self {
+        Self {
+            topic_repo,
+            topic_purge_repo,
+            topic_user_repo,
+            topic_message_repo,
+            thread_message_repo,
+            reaction_repo,
+            pinned_message_repo,
+            read_cursor_repo,
+            topic_role_repo,
+            topic_invite_repo,
+            hidden_message_repo,
+            latest_message_repo,
+            notification_repo,
+            unread_counter_repo,
+        }
+    }
+
+    async fn is_deleted(&self, topic_id: Timeuuid) -> AppResult<bool> {
+        let query = RequestGetTopicByPartitionKey {
+            topic_id,
+            page_size: Some(1),
+            page_token: None,
+        };
+        let page = self.topic_repo.find_topic_by_partition_key(&query).await?;
+        Ok(page.items.first().is_some_and(|topic| topic.is_deleted()))
+    }
+
+    async fn find_all_members(&self, topic_id: Timeuuid) -> AppResult<Vec<TopicUser>> {
+        let mut members: Vec<TopicUser> = vec![];
+        let mut query = RequestGetUsersByTopicId {
+            topic_id,
+            page_size: Some(MAX_PAGE_SIZE),
+            page_token: None,
+        };
+        loop {
+            let page = self.topic_user_repo.find_topic_users_by_partition_key(&query).await?;
+            members.extend(page.items);
+            match page.next_page_token {
+                Some(page_token) => query.page_token = Some(page_token),
+                None => return Ok(members),
+            }
+        }
+    }
+
+    /// The rows kept per member: their listing entry, notifications, unread
+    /// count and hidden messages.
+    async fn purge_member_rows(&self, topic_id: Timeuuid, members: &[TopicUser]) -> AppResult<()> {
+        let latest_keys: Vec<RequestGetLatestMessage> = members
+            .iter()
+            .map(|member| RequestGetLatestMessage {
+                user_id: member.user_id,
+                topic_id,
+            })
+            .collect();
+        self.latest_message_repo.remove_latest_messages(&latest_keys).await?;
+
+        for member in members.iter() {
+            let query = RequestRemoveTopicNotifications {
+                username: member.username.to_owned(),
+                topic_id,
+            };
+            self.notification_repo.remove_topic_notifications(&query).await?;
+            let counter = UnreadCounter {
+                user_id: member.user_id,
+                topic_id,
+                unread: 0,
+            };
+            self.unread_counter_repo.reset_unread_counter(&counter).await?;
+            let query = RequestGetHiddenMessages {
+                user_id: member.user_id,
+                topic_id,
+            };
+            self.hidden_message_repo.remove_hidden_messages(&query).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<TP, PR, TU, TM, TH, MR, PM, RC, RR, TI, HM, LM, NR, UC> TopicPurgeAppInterface for TopicPurgeApp<TP, PR, TU, TM, TH, MR, PM, RC, RR, TI, HM, LM, NR, UC>
+where
+    TP: TopicRepository,
+    PR: TopicPurgeRepository,
+    TU: TopicUserRepository,
+    TM: TopicMessageRepository,
+    TH: ThreadMessageRepository,
+    MR: MessageReactionRepository,
+    PM: PinnedMessageRepository,
+    RC: ReadCursorRepository,
+    RR: TopicRoleRepository,
+    TI: TopicInviteRepository,
+    HM: HiddenMessageRepository,
+    LM: LatestMessageRepository,
+    NR: NotificationRepository,
+    UC: UnreadCounterRepository,
+{
+    async fn schedule_purge(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let topic_purge = TopicPurge {
+            topic_id,
+            requested_at: Utc::now(),
+        };
+        self.topic_purge_repo.save_topic_purge(&topic_purge).await
+    }
+
+    fn spawn_purge(&self, topic_id: Timeuuid) {
+        let app = self.clone();
+        tokio::spawn(async move {
+            match app.purge_topic(topic_id).await {
+                Ok(()) => tracing::info!(%topic_id, "purged deleted topic"),
+                Err(err) => tracing::error!("purging topic {topic_id}: {err:?}"),
+            }
+        });
+    }
+
+    /// The topic row stays as its tombstone. Whole partitions are deleted
+    /// where the table is keyed by topic; memberships go last, as they are how
+    /// the members are found when an interrupted purge is run again.
+    async fn purge_topic(&self, topic_id: Timeuuid) -> AppResult<()> {
+        // Scheduled before the state change, which may then have failed.
+        if !self.is_deleted(topic_id).await? {
+            return self.topic_purge_repo.remove_topic_purge(topic_id).await;
+        }
+
+        let members = self.find_all_members(topic_id).await?;
+        self.purge_member_rows(topic_id, &members).await?;
+
+        self.thread_message_repo.remove_topic_threads(topic_id).await?;
+        self.topic_message_repo.purge_topic_messages(topic_id).await?;
+        self.reaction_repo.remove_topic_reactions(topic_id).await?;
+        self.pinned_message_repo.remove_topic_pins(topic_id).await?;
+        self.read_cursor_repo.remove_topic_read_cursors(topic_id).await?;
+        self.topic_role_repo.remove_topic_roles(topic_id).await?;
+        self.topic_invite_repo.remove_topic_invites(topic_id).await?;
+
+        for member in members.iter() {
+            let user_topic = UserTopic {
+                topic_id,
+                username: member.username.to_owned(),
+                created_at: member.created_at,
+                ..Default::default()
+            };
+            self.topic_user_repo.remove_topic_member(&user_topic).await?;
+        }
+
+        self.topic_purge_repo.remove_topic_purge(topic_id).await
+    }
+
+    async fn find_pending_purges(&self) -> AppResult<Vec<Timeuuid>> {
+        let topic_purges = self.topic_purge_repo.find_topic_purges().await?;
+        Ok(topic_purges.into_iter().map(|topic_purge| topic_purge.topic_id).collect())
+    }
+}
+
+#[cfg(all(test, feature = "in-memory"))]
+mod tests {
+    use super::*;
+    use crate::application::actor::Actor;
+    use crate::application::latest_message::request::RequestGetLatestMessage;
+    use crate::application::notification::request::RequestGetNotificationByUsername;
+    use crate::application::testing::{actor, TestApps};
+    use crate::application::topic::app::TopicAppInterface;
+    use crate::application::topic::request::RequestSetTopicState;
+    use crate::application::topic_message::app::TopicMessageAppInterface;
+    use crate::application::topic_message::request::{
+        RequestDeleteTopicMessage, RequestGetMessageReactions, RequestGetMessageRevisions,
+        RequestGetMessagesByTopicId, RequestGetPinnedMessages, RequestGetReadCursorsByTopicId,
+        RequestGetThreadMessages, RequestGetTopicMessage, RequestMarkRead, RequestPinTopicMessage,
+        RequestPostTopicMessage, RequestReactTopicMessage, RequestUpdateTopicMessage,
+    };
+    use crate::application::topic_role::app::TopicRoleAppInterface;
+    use crate::application::topic_role::request::{RequestGetRolesByTopicId, RequestSetTopicRole};
+    use crate::application::topic_user::app::TopicUserAppInterface;
+    use crate::application::topic_user::request::{RequestGetTopicMember, RequestInviteTopicMember};
+    use crate::domain::topic_message_revision::repository::TopicMessageRevisionRepository;
+    use crate::domain::user_topic::repository::UserTopicRepository;
+
+    fn post(topic_id: Timeuuid, author: &Actor, parent_id: Option<Timeuuid>) -> RequestPostTopicMessage {
+        RequestPostTopicMessage {
+            topic_id,
+            from_user_id: author.user_id,
+            message: "hello".to_string(),
+            parent_id,
+            quoted_message_id: None,
+        }
+    }
+
+    async fn delete_topic(apps: &TestApps, owner: &Actor, topic_id: Timeuuid) {
+        let req = RequestSetTopicState {
+            topic_id,
+            username: owner.username.to_owned(),
+            topic_state: "deleted".to_string(),
+        };
+        apps.topic_app.set_topic_state(owner, &req).await.unwrap();
+    }
+
+    async fn timeline(apps: &TestApps, topic_id: Timeuuid) -> usize {
+        let query = RequestGetMessagesByTopicId {
+            topic_id,
+            page_size: None,
+            page_token: None,
+            before: None,
+            after: None,
+        };
+        let page = apps.repos.topic_message.find_topic_message_by_partition_key(&query).await.unwrap();
+        page.items.len()
+    }
+
+    #[tokio::test]
+    async fn a_purge_removes_everything_hanging_off_the_topic() {
+        let apps = TestApps::new();
+        let (alice, bob) = (actor("alice"), actor("bob"));
+        let topic_id = apps.create_topic(&alice).await;
+        apps.join(topic_id, &bob).await;
+
+        let messages = &apps.topic_message_app;
+        let posted = messages.post_message(&alice, post(topic_id, &alice, None)).await.unwrap();
+        let message_id = posted.message_id;
+        let reply = messages.post_message(&bob, post(topic_id, &bob, Some(message_id))).await.unwrap();
+        let edit = RequestUpdateTopicMessage {
+            topic_id,
+            message_id,
+            from_user_id: alice.user_id,
+            message: "hello again".to_string(),
+        };
+        messages.update_topic_message(&alice, &edit).await.unwrap();
+        let react = RequestReactTopicMessage {
+            topic_id,
+            message_id,
+            user_id: bob.user_id,
+            emoji: "👍".to_string(),
+        };
+        messages.add_reaction(&bob, &react).await.unwrap();
+        let pin = RequestPinTopicMessage { topic_id, message_id, user_id: alice.user_id };
+        messages.pin_message(&alice, &pin).await.unwrap();
+        let read = RequestMarkRead { topic_id, message_id, user_id: bob.user_id };
+        messages.mark_read(&bob, &read).await.unwrap();
+        let hide = RequestDeleteTopicMessage { topic_id, message_id, for_everyone: false };
+        messages.delete_topic_message(&bob, &hide).await.unwrap();
+        let ban = RequestSetTopicRole {
+            topic_id,
+            username: "carol".to_string(),
+            role: "banned".to_string(),
+        };
+        apps.topic_role_app.set_topic_role(&alice, &ban).await.unwrap();
+        let invite = RequestInviteTopicMember {
+            topic_id,
+            invited_by: alice.username.to_owned(),
+            username: "dave".to_string(),
+        };
+        apps.topic_user_app.invite_topic_member(&invite).await.unwrap();
+
+        delete_topic(&apps, &alice, topic_id).await;
+        apps.topic_purge_app.purge_topic(topic_id).await.unwrap();
+
+        let repos = &apps.repos;
+        assert_eq!(timeline(&apps, topic_id).await, 0);
+        let query = RequestGetTopicMessage { topic_id, message_id: reply.message_id };
+        assert!(repos.topic_message.find_topic_message(&query).await.unwrap().is_none());
+        let query = RequestGetThreadMessages { topic_id, parent_id: message_id, page_size: None, page_token: None };
+        assert!(repos.thread_message.find_thread_messages_by_partition_key(&query).await.unwrap().items.is_empty());
+        let query = RequestGetMessageRevisions { topic_id, message_id, page_size: None, page_token: None };
+        assert!(repos.topic_message_revision.find_revisions_by_partition_key(&query).await.unwrap().items.is_empty());
+        let query = RequestGetMessageReactions { topic_id, message_ids: vec![message_id] };
+        assert!(repos.message_reaction.find_reactions_by_message_ids(&query).await.unwrap().is_empty());
+        let query = RequestGetPinnedMessages { topic_id };
+        assert!(repos.pinned_message.find_pinned_messages(&query).await.unwrap().is_empty());
+        let query = RequestGetReadCursorsByTopicId { topic_id };
+        assert!(repos.read_cursor.find_read_cursors_by_topic_id(&query).await.unwrap().is_empty());
+        let query = RequestGetRolesByTopicId { topic_id, page_size: None, page_token: None };
+        assert!(repos.topic_role.find_topic_roles_by_partition_key(&query).await.unwrap().items.is_empty());
+        let query = RequestGetTopicMember { topic_id, username: "dave".to_string() };
+        assert!(repos.topic_invite.find_topic_invite(&query).await.unwrap().is_none());
+
+        for member in [&alice, &bob] {
+            let query = RequestGetHiddenMessages { user_id: member.user_id, topic_id };
+            assert!(repos.hidden_message.find_hidden_messages(&query).await.unwrap().is_empty());
+            let query = RequestGetLatestMessage { user_id: member.user_id, topic_id };
+            assert!(repos.latest_message.find_latest_message(&query).await.unwrap().is_none());
+            let query = RequestGetNotificationByUsername {
+                username: member.username.to_owned(),
+                page_size: None,
+                page_token: None,
+            };
+            assert!(repos.notification.find_notifications_by_partition_key(&query).await.unwrap().items.is_empty());
+            let query = RequestGetTopicMember { topic_id, username: member.username.to_owned() };
+            assert!(repos.user_topic.find_user_topic(&query).await.unwrap().is_none());
+        }
+        let query = RequestGetUsersByTopicId { topic_id, page_size: None, page_token: None };
+        assert!(repos.topic_user.find_topic_users_by_partition_key(&query).await.unwrap().items.is_empty());
+
+        assert!(apps.topic_purge_app.find_pending_purges().await.unwrap().is_empty());
+        assert!(apps.topic_purge_app.is_deleted(topic_id).await.unwrap(), "the topic row stays as a tombstone");
+    }
+
+    #[tokio::test]
+    async fn a_pending_purge_is_resumed() {
+        let apps = TestApps::new();
+        let alice = actor("alice");
+        let topic_id = apps.create_topic(&alice).await;
+        apps.topic_message_app.post_message(&alice, post(topic_id, &alice, None)).await.unwrap();
+
+        // The spawned purge has not run yet: nothing in this test has yielded.
+        delete_topic(&apps, &alice, topic_id).await;
+        assert_eq!(apps.topic_purge_app.find_pending_purges().await.unwrap(), vec![topic_id]);
+
+        resume_topic_purges(Arc::clone(&apps.topic_purge_app)).await;
+        assert_eq!(timeline(&apps, topic_id).await, 0);
+        assert!(apps.topic_purge_app.find_pending_purges().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_purge_leaves_a_topic_that_was_not_deleted() {
+        let apps = TestApps::new();
+        let alice = actor("alice");
+        let topic_id = apps.create_topic(&alice).await;
+        apps.topic_message_app.post_message(&alice, post(topic_id, &alice, None)).await.unwrap();
+        let before = timeline(&apps, topic_id).await;
+
+        apps.topic_purge_app.schedule_purge(topic_id).await.unwrap();
+        apps.topic_purge_app.purge_topic(topic_id).await.unwrap();
+
+        assert_eq!(timeline(&apps, topic_id).await, before);
+        assert!(apps.topic_purge_app.find_pending_purges().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,370 @@
+use super::{
+    request::{RequestGetRolesByTopicId, RequestSetTopicRole},
+    response::PublicTopicRole,
+};
+use crate::application::actor::Actor;
+use crate::application::error::ApplicationError;
+use crate::application::live::hub::TopicEventHub;
+use crate::application::pagination::Page;
+use crate::application::topic::request::RequestGetTopicByPartitionKey;
+use crate::application::topic_user::request::RequestGetTopicMember;
+use crate::domain::topic::{entity::Topic, repository::TopicRepository};
+use crate::domain::topic_role::entity::{Capability, Role, TopicRole};
+use crate::domain::topic_role::repository::TopicRoleRepository;
+use crate::domain::user_topic::repository::UserTopicRepository;
+use anyhow::bail;
+use charybdis::types::Timeuuid;
+use chrono::Utc;
+use std::{future::Future, sync::Arc};
+use uptop_core::common::result::AppResult;
+
+/// The outcome of a permission check: the topic it was made against and the
+/// role the caller holds there.
+#[derive(Clone, Debug)]
+pub struct TopicAccess {
+    pub topic: Topic,
+    pub role: Role,
+}
+
+pub trait TopicRoleAppInterface: Clone + Send + Sync + 'static {
+    /// Deleted topics are reported missing.
+    fn find_topic(
+        &self,
+        topic_id: Timeuuid,
+    ) -> impl Future<Output=AppResult<Topic>> + Send;
+
+    /// The effective role of `username`: `topic_owners`, `topic_admins` or
+    /// membership place them in the topic, then a per-topic override replaces
+    /// that role. `None` for outsiders, whose overrides are ignored unless
+    /// they are bans.
+    fn find_role(
+        &self,
+        topic: &Topic,
+        username: &str,
+    ) -> impl Future<Output=AppResult<Option<Role>>> + Send;
+
+    /// Fails with `PermissionDenied` unless `username` holds `capability` in
+    /// the topic, and with `FailedPrecondition` when the topic's state rules
+    /// it out, e.g. posting in a locked topic.
+    fn authorize(
+        &self,
+        topic_id: Timeuuid,
+        username: &str,
+        capability: Capability,
+    ) -> impl Future<Output=AppResult<TopicAccess>> + Send;
+
+    fn set_topic_role(
+        &self,
+        actor: &Actor,
+        req: &RequestSetTopicRole,
+    ) -> impl Future<Output=AppResult<PublicTopicRole>> + Send;
+
+    fn find_list_roles_by_topic_id(
+        &self,
+        actor: &Actor,
+        query: &RequestGetRolesByTopicId,
+    ) -> impl Future<Output=AppResult<Page<PublicTopicRole>>> + Send;
+
+    /// Takes from a leaving member whatever ranks them above membership: their
+    /// place among the owners and admins, and their role override. A ban is
+    /// kept, so that leaving does not lift it.
+    fn revoke_roles(
+        &self,
+        topic_id: Timeuuid,
+        username: &str,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
+
+/// Attempts at popping a user from the owner and admin lists before giving up
+/// to concurrent changes of the topic.
+const REVOKE_ATTEMPTS: usize = 3;
+
+/// Owners may act on anyone; everyone else only on users ranked below them.
+pub fn ensure_outranks(actor: Role, target: Option<Role>) -> AppResult<()> {
+    match target {
+        Some(target) if actor != Role::Owner && target >= actor => {
+            bail!(ApplicationError::PermissionDenied {
+                msg: format!("a {} cannot act on a {}", actor.as_str(), target.as_str())
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TopicRoleApp<TR, RR, UT>
+where
+    TR: TopicRepository,
+    RR: TopicRoleRepository,
+    UT: UserTopicRepository,
+{
+    topic_repo: Arc<TR>,
+    topic_role_repo: Arc<RR>,
+    user_topic_repo: Arc<UT>,
+    hub: Arc<TopicEventHub>,
+}
+
+impl<TR, RR, UT> TopicRoleApp<TR, RR, UT>
+where
+    TR: TopicRepository,
+    RR: TopicRoleRepository,
+    UT: UserTopicRepository,
+{
+    pub fn new(
+        topic_repo: Arc<TR>,
+        topic_role_repo: Arc<RR>,
+        user_topic_repo: Arc<UT>,
+        hub: Arc<TopicEventHub>,
+    ) -> Self {
+        Self {
+            topic_repo,
+            topic_role_repo,
+            user_topic_repo,
+            hub,
+        }
+    }
+}
+
+impl<TR, RR, UT> TopicRoleAppInterface for TopicRoleApp<TR, RR, UT>
+where
+    TR: TopicRepository,
+    RR: TopicRoleRepository,
+    UT: UserTopicRepository,
+{
+    async fn find_topic(&self, topic_id: Timeuuid) -> AppResult<Topic> {
+        let query = RequestGetTopicByPartitionKey {
+            topic_id,
+            page_size: Some(1),
+            page_token: None,
+        };
+        match self.topic_repo.find_topic_by_partition_key(&query).await?.items.into_iter().next() {
+            Some(topic) if !topic.is_deleted() => Ok(topic),
+            _ => bail!(ApplicationError::NotFound { resource: "topic" }),
+        }
+    }
+
+    async fn find_role(&self, topic: &Topic, username: &str) -> AppResult<Option<Role>> {
+        let query = RequestGetTopicMember {
+            topic_id: topic.topic_id,
+            username: username.to_owned(),
+        };
+        let role = if topic.topic_owners.iter().any(|owner| owner == username) {
+            Some(Role::Owner)
+        } else if topic.topic_admins.iter().any(|admin| admin == username) {
+            Some(Role::Admin)
+        } else {
+            self.user_topic_repo
+                .find_user_topic(&query)
+                .await?
+                .map(|_| Role::Member)
+        };
+
+        // A ban holds for outsiders too, so that it keeps them from joining.
+        let overridden = self
+            .topic_role_repo
+            .find_topic_role(&query)
+            .await?
+            .map(|topic_role| topic_role.role());
+        Ok(match (role, overridden) {
+            (_, Some(Role::Banned)) => Some(Role::Banned),
+            (Some(_), Some(overridden)) => Some(overridden),
+            (role, _) => role,
+        })
+    }
+
+    async fn authorize(
+        &self,
+        topic_id: Timeuuid,
+        username: &str,
+        capability: Capability,
+    ) -> AppResult<TopicAccess> {
+        let topic = self.find_topic(topic_id).await?;
+        let role = match self.find_role(&topic, username).await? {
+            Some(role) if role.can(capability) => role,
+            _ => bail!(ApplicationError::PermissionDenied {
+                msg: format!("{username} is not allowed to {} in this topic", capability.as_str())
+            }),
+        };
+        let state = topic.state();
+        if !state.allows(capability) {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: format!("cannot {} in a topic that is {}", capability.as_str(), state.as_str())
+            });
+        }
+        Ok(TopicAccess { topic, role })
+    }
+
+    async fn set_topic_role(&self, actor: &Actor, req: &RequestSetTopicRole) -> AppResult<PublicTopicRole> {
+        let access = self
+            .authorize(req.topic_id, &actor.username, Capability::ManageSettings)
+            .await?;
+        let granted = match Role::parse(&req.role) {
+            Some(role) => role,
+            None => bail!(ApplicationError::invalid_field("role", "is not a known role")),
+        };
+
+        let current = self.find_role(&access.topic, &req.username).await?;
+        ensure_outranks(access.role, current)?;
+        ensure_outranks(access.role, Some(granted))?;
+
+        let topic_role = TopicRole {
+            topic_id: req.topic_id,
+            username: req.username.to_owned(),
+            role: granted.as_str().to_string(),
+            updated_by: actor.username.to_owned(),
+            updated_at: Utc::now(),
+        };
+        self.topic_role_repo.save_topic_role(&topic_role).await?;
+        if !granted.can(Capability::Read) {
+            self.hub.revoke_access(req.topic_id, Some(&req.username));
+        }
+        PublicTopicRole::try_from(&topic_role)
+    }
+
+    async fn find_list_roles_by_topic_id(
+        &self,
+        actor: &Actor,
+        query: &RequestGetRolesByTopicId,
+    ) -> AppResult<Page<PublicTopicRole>> {
+        self.authorize(query.topic_id, &actor.username, Capability::Read).await?;
+        self.topic_role_repo
+            .find_topic_roles_by_partition_key(query)
+            .await?
+            .try_map(|item: &TopicRole| item.try_into())
+    }
+
+    async fn revoke_roles(&self, topic_id: Timeuuid, username: &str) -> AppResult<()> {
+        let mut attempts = 0;
+        loop {
+            let topic = self.find_topic(topic_id).await?;
+            let is_owner = topic.topic_owners.iter().any(|owner| owner == username);
+            let is_admin = topic.topic_admins.iter().any(|admin| admin == username);
+            if !is_owner && !is_admin {
+                break;
+            }
+            if is_owner && topic.topic_owners.len() == 1 {
+                bail!(ApplicationError::FailedPrecondition {
+                    msg: format!("{username} is the last owner of the topic, make someone else an owner first")
+                });
+            }
+
+            let updated = Topic {
+                topic_owners: topic.topic_owners.iter().filter(|owner| *owner != username).cloned().collect(),
+                topic_admins: topic.topic_admins.iter().filter(|admin| *admin != username).cloned().collect(),
+                updated_at: Utc::now(),
+                ..topic.clone()
+            };
+            if self.topic_repo.update_topic(&updated, topic.updated_at).await? {
+                break;
+            }
+            attempts += 1;
+            if attempts == REVOKE_ATTEMPTS {
+                bail!(ApplicationError::topic_changed());
+            }
+        }
+
+        let query = RequestGetTopicMember {
+            topic_id,
+            username: username.to_owned(),
+        };
+        match self.topic_role_repo.find_topic_role(&query).await? {
+            Some(topic_role) if topic_role.role() == Role::Banned => Ok(()),
+            Some(_) => self.topic_role_repo.remove_topic_role(&query).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "in-memory"))]
+mod tests {
+    use super::*;
+    use crate::application::testing::{actor, TestApps};
+    use crate::application::topic_user::app::TopicUserAppInterface;
+    use crate::application::topic_user::request::RequestJoinTopic;
+
+    fn set_role(topic_id: Timeuuid, username: &str, role: Role) -> RequestSetTopicRole {
+        RequestSetTopicRole {
+            topic_id,
+            username: username.to_string(),
+            role: role.as_str().to_string(),
+        }
+    }
+
+    fn is_permission_denied(err: &anyhow::Error) -> bool {
+        matches!(err.downcast_ref(), Some(ApplicationError::PermissionDenied { .. }))
+    }
+
+    #[tokio::test]
+    async fn an_override_applies_to_members() {
+        let apps = TestApps::new();
+        let (alice, bob) = (actor("alice"), actor("bob"));
+        let topic_id = apps.create_topic(&alice).await;
+        apps.join(topic_id, &bob).await;
+
+        let role_app = &apps.topic_role_app;
+        assert!(is_permission_denied(
+            &role_app.authorize(topic_id, "bob", Capability::Pin).await.unwrap_err()
+        ));
+        role_app.set_topic_role(&alice, &set_role(topic_id, "bob", Role::Admin)).await.unwrap();
+        assert_eq!(role_app.authorize(topic_id, "bob", Capability::Pin).await.unwrap().role, Role::Admin);
+    }
+
+    #[tokio::test]
+    async fn an_override_grants_nothing_outside_the_topic() {
+        let apps = TestApps::new();
+        let alice = actor("alice");
+        let topic_id = apps.create_topic(&alice).await;
+
+        let role_app = &apps.topic_role_app;
+        role_app.set_topic_role(&alice, &set_role(topic_id, "bob", Role::Admin)).await.unwrap();
+        let topic = role_app.find_topic(topic_id).await.unwrap();
+        assert_eq!(role_app.find_role(&topic, "bob").await.unwrap(), None);
+        assert!(is_permission_denied(
+            &role_app.authorize(topic_id, "bob", Capability::Read).await.unwrap_err()
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_ban_holds_outside_the_topic() {
+        let apps = TestApps::new();
+        let (alice, bob) = (actor("alice"), actor("bob"));
+        let topic_id = apps.create_topic(&alice).await;
+
+        let role_app = &apps.topic_role_app;
+        role_app.set_topic_role(&alice, &set_role(topic_id, "bob", Role::Banned)).await.unwrap();
+        let topic = role_app.find_topic(topic_id).await.unwrap();
+        assert_eq!(role_app.find_role(&topic, "bob").await.unwrap(), Some(Role::Banned));
+
+        let join = RequestJoinTopic {
+            topic_id,
+            username: bob.username.to_owned(),
+            user_id: bob.user_id,
+        };
+        assert!(is_permission_denied(&apps.topic_user_app.join_topic(&join).await.unwrap_err()));
+    }
+
+    #[tokio::test]
+    async fn roles_are_granted_only_below_ones_own() {
+        let apps = TestApps::new();
+        let (alice, bob, carol) = (actor("alice"), actor("bob"), actor("carol"));
+        let topic_id = apps.create_topic(&alice).await;
+        apps.join(topic_id, &bob).await;
+        apps.join(topic_id, &carol).await;
+
+        let role_app = &apps.topic_role_app;
+        let err = role_app.set_topic_role(&bob, &set_role(topic_id, "carol", Role::ReadOnly)).await.unwrap_err();
+        assert!(is_permission_denied(&err));
+
+        role_app.set_topic_role(&alice, &set_role(topic_id, "bob", Role::Admin)).await.unwrap();
+        for (username, role) in [("carol", Role::Admin), ("alice", Role::Member)] {
+            let err = role_app.set_topic_role(&bob, &set_role(topic_id, username, role)).await.unwrap_err();
+            assert!(is_permission_denied(&err), "{username} {role:?}");
+        }
+
+        role_app.set_topic_role(&bob, &set_role(topic_id, "carol", Role::ReadOnly)).await.unwrap();
+        assert_eq!(role_app.authorize(topic_id, "carol", Capability::Read).await.unwrap().role, Role::ReadOnly);
+        assert!(is_permission_denied(
+            &role_app.authorize(topic_id, "carol", Capability::Post).await.unwrap_err()
+        ));
+    }
+}
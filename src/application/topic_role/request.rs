@@ -0,0 +1,61 @@
+use anyhow::bail;
+use charybdis::types::{Text, Timeuuid};
+use serde::{Deserialize, Serialize};
+use uptop_core::common::result::AppResult;
+use validator::Validate;
+use crate::application::error::ApplicationError;
+use crate::application::pagination::MAX_PAGE_SIZE;
+use crate::domain::topic_role::entity::Role;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestSetTopicRole {
+    pub topic_id: Timeuuid,
+    #[validate(length(min = 1))]
+    pub username: Text,
+    /// One of `owner`, `admin`, `member`, `read_only` or `banned`.
+    pub role: Text,
+}
+
+impl RequestSetTopicRole {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+        if Role::parse(&self.role).is_none() {
+            bail!(ApplicationError::invalid_field(
+                "role",
+                "must be one of owner, admin, member, read_only, banned"
+            ));
+        }
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            username: self.username,
+            role: self.role,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestGetRolesByTopicId {
+    pub topic_id: Timeuuid,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
+}
+
+impl RequestGetRolesByTopicId {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            page_size: self.page_size,
+            page_token: self.page_token,
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use serde::{Deserialize, Serialize};
+use uptop_core::common::result::AppResult;
+use crate::domain::topic_role::entity::TopicRole;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicTopicRole {
+    pub topic_id: Timeuuid,
+    pub username: Text,
+    pub role: Text,
+    pub updated_by: Text,
+    pub updated_at: Timestamp,
+}
+
+impl TryFrom<&TopicRole> for PublicTopicRole {
+    type Error = anyhow::Error;
+
+    fn try_from(topic_role: &TopicRole) -> AppResult<Self> {
+        Ok(Self {
+            topic_id: topic_role.topic_id,
+            username: topic_role.username.to_owned(),
+            role: topic_role.role.to_owned(),
+            updated_by: topic_role.updated_by.to_owned(),
+            updated_at: topic_role.updated_at,
+        })
+    }
+}
@@ -1,8 +1,32 @@
 use super::{
-    response::PublicTopicUser,
+    response::{PublicPresence, PublicTopicInvite, PublicTopicUser},
 };
-use crate::application::topic_user::request::{RequestGetUsersByTopicId, RequestUpdateTopicUser};
+use crate::application::actor::Actor;
+use crate::application::error::ApplicationError;
+use crate::application::live::event::{PresenceUpdate, TopicEvent};
+use crate::application::live::hub::TopicEventHub;
+use crate::application::live::presence::{PresenceRegistry, PresenceStatus};
+use crate::application::pagination::{Page, MAX_PAGE_SIZE};
+use crate::application::topic_message::response::PublicTopicMessage;
+use crate::application::topic_role::app::{ensure_outranks, TopicRoleAppInterface};
+use crate::application::topic_user::request::{
+    RequestGetInvitesByUsername, RequestGetPresence, RequestGetTopicMember, RequestGetUsersByTopicId,
+    RequestInviteTopicMember, RequestJoinTopic, RequestLeaveTopic, RequestRemoveTopicMember,
+    RequestRespondTopicInvite, RequestSetPresence,
+};
+use crate::application::user_topic::request::RequestGetTopicsByUsername;
+use crate::domain::topic::entity::{Topic, TopicState};
+use crate::domain::topic_invite::entity::{TopicInvite, INVITE_ACCEPTED, INVITE_DECLINED, INVITE_PENDING};
+use crate::domain::topic_invite::repository::TopicInviteRepository;
+use crate::domain::topic_message::entity::TopicMessage;
+use crate::domain::topic_message::repository::TopicMessageRepository;
+use crate::domain::topic_role::entity::{Capability, Role};
 use crate::domain::topic_user::{repository::TopicUserRepository};
+use crate::domain::user_topic::entity::UserTopic;
+use crate::domain::user_topic::repository::UserTopicRepository;
+use anyhow::bail;
+use charybdis::types::Timeuuid;
+use chrono::Utc;
 use std::{future::Future, sync::Arc};
 use uptop_core::common::result::AppResult;
 use crate::domain::topic_user::entity::TopicUser;
@@ -10,61 +34,402 @@ use crate::domain::topic_user::entity::TopicUser;
 pub trait TopicUserAppInterface: Clone + Send + Sync + 'static {
     fn find_list_users_by_topic_id(
         &self,
+        actor: &Actor,
         query: &RequestGetUsersByTopicId,
-    ) -> impl Future<Output=AppResult<Vec<PublicTopicUser>>> + Send;
+    ) -> impl Future<Output=AppResult<Page<PublicTopicUser>>> + Send;
 
-    fn update_topic_user(
+    fn join_topic(
         &self,
-        topic_user: &RequestUpdateTopicUser,
+        req: &RequestJoinTopic,
     ) -> impl Future<Output=AppResult<PublicTopicUser>> + Send;
+
+    fn leave_topic(
+        &self,
+        req: &RequestLeaveTopic,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn invite_topic_member(
+        &self,
+        req: &RequestInviteTopicMember,
+    ) -> impl Future<Output=AppResult<PublicTopicInvite>> + Send;
+
+    fn respond_topic_invite(
+        &self,
+        req: &RequestRespondTopicInvite,
+    ) -> impl Future<Output=AppResult<PublicTopicInvite>> + Send;
+
+    fn remove_topic_member(
+        &self,
+        req: &RequestRemoveTopicMember,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn find_list_invites_by_username(
+        &self,
+        query: &RequestGetInvitesByUsername,
+    ) -> impl Future<Output=AppResult<Page<PublicTopicInvite>>> + Send;
+
+    fn set_presence(
+        &self,
+        req: &RequestSetPresence,
+    ) -> impl Future<Output=AppResult<PublicPresence>> + Send;
+
+    fn find_list_presence(
+        &self,
+        query: &RequestGetPresence,
+    ) -> impl Future<Output=AppResult<Vec<PublicPresence>>> + Send;
 }
 
 #[derive(Clone, Debug)]
-pub struct TopicUserApp<TP>
+pub struct TopicUserApp<TP, UT, TI, RA, TM>
 where
     TP: TopicUserRepository,
+    UT: UserTopicRepository,
+    TI: TopicInviteRepository,
+    RA: TopicRoleAppInterface,
+    TM: TopicMessageRepository,
 {
     topic_user_repo: Arc<TP>,
+    user_topic_repo: Arc<UT>,
+    topic_invite_repo: Arc<TI>,
+    topic_role_app: Arc<RA>,
+    topic_message_repo: Arc<TM>,
+    hub: Arc<TopicEventHub>,
+    presence: Arc<PresenceRegistry>,
 }
 
-impl<TP> TopicUserApp<TP>
+impl<TP, UT, TI, RA, TM> TopicUserApp<TP, UT, TI, RA, TM>
 where
     TP: TopicUserRepository,
+    UT: UserTopicRepository,
+    TI: TopicInviteRepository,
+    RA: TopicRoleAppInterface,
+    TM: TopicMessageRepository,
 {
-    pub fn new(topic_user_repo: Arc<TP>) -> Self {
-        Self { topic_user_repo }
+    pub fn new(
+        topic_user_repo: Arc<TP>,
+        user_topic_repo: Arc<UT>,
+        topic_invite_repo: Arc<TI>,
+        topic_role_app: Arc<RA>,
+        topic_message_repo: Arc<TM>,
+        hub: Arc<TopicEventHub>,
+        presence: Arc<PresenceRegistry>,
+    ) -> Self {
+        Self {
+            topic_user_repo,
+            user_topic_repo,
+            topic_invite_repo,
+            topic_role_app,
+            topic_message_repo,
+            hub,
+            presence,
+        }
     }
 }
 
-impl<TP> TopicUserAppInterface for TopicUserApp<TP>
+impl<TP, UT, TI, RA, TM> TopicUserApp<TP, UT, TI, RA, TM>
 where
     TP: TopicUserRepository,
+    UT: UserTopicRepository,
+    TI: TopicInviteRepository,
+    RA: TopicRoleAppInterface,
+    TM: TopicMessageRepository,
+{
+    async fn find_member(&self, topic_id: Timeuuid, username: &str) -> AppResult<Option<UserTopic>> {
+        let query = RequestGetTopicMember {
+            topic_id,
+            username: username.to_owned(),
+        };
+        self.user_topic_repo.find_user_topic(&query).await
+    }
+
+    /// Users banned from a topic cannot come back by joining it, and nobody
+    /// joins a conversation: its members are set when it is opened.
+    async fn find_joinable_topic(&self, topic_id: Timeuuid, username: &str) -> AppResult<Topic> {
+        let topic = self.topic_role_app.find_topic(topic_id).await?;
+        if topic.is_conversation() {
+            bail!(ApplicationError::PermissionDenied {
+                msg: "conversations cannot be joined".to_string()
+            });
+        }
+        if topic.state() == TopicState::Archived {
+            bail!(ApplicationError::FailedPrecondition {
+                msg: "archived topics cannot be joined".to_string()
+            });
+        }
+        match self.topic_role_app.find_role(&topic, username).await? {
+            Some(Role::Banned) => bail!(ApplicationError::PermissionDenied {
+                msg: format!("{username} is banned from this topic")
+            }),
+            _ => Ok(topic),
+        }
+    }
+
+    async fn add_member(
+        &self,
+        topic: &Topic,
+        username: &str,
+        user_id: Timeuuid,
+        accepted_invite: Option<&TopicInvite>,
+    ) -> AppResult<TopicUser> {
+        let joined_at = Utc::now();
+        let topic_user = TopicUser {
+            topic_id: topic.topic_id,
+            username: username.to_owned(),
+            user_id,
+            created_at: joined_at,
+        };
+        let user_topic = UserTopic {
+            topic_id: topic.topic_id,
+            username: username.to_owned(),
+            created_at: joined_at,
+            topic_kind: Some(topic.kind().to_string()),
+            topic_state: topic.topic_state.to_owned(),
+        };
+        self.topic_user_repo
+            .add_topic_member(&topic_user, &user_topic, accepted_invite)
+            .await?;
+        Ok(topic_user)
+    }
+
+    /// Presence changes reach every topic the user is in, so walk their topics page by page.
+    async fn find_all_topic_ids(&self, username: &str) -> AppResult<Vec<Timeuuid>> {
+        let mut topic_ids: Vec<Timeuuid> = vec![];
+        let mut query = RequestGetTopicsByUsername {
+            username: username.to_owned(),
+            topic_kind: None,
+            include_archived: true,
+            page_size: Some(MAX_PAGE_SIZE),
+            page_token: None,
+        };
+        loop {
+            let page = self.user_topic_repo.find_user_topics_by_partition_key(&query).await?;
+            topic_ids.extend(page.items.iter().map(|user_topic| user_topic.topic_id));
+            match page.next_page_token {
+                Some(page_token) => query.page_token = Some(page_token),
+                None => return Ok(topic_ids),
+            }
+        }
+    }
+
+    fn public_presence(&self, username: &str) -> PublicPresence {
+        let (status, last_seen_at) = self.presence.presence(username);
+        PublicPresence {
+            username: username.to_owned(),
+            status: status.as_str().to_string(),
+            last_seen_at,
+        }
+    }
+
+    /// Records a membership change in the topic timeline and tells subscribers.
+    async fn post_system_message(&self, topic_id: Timeuuid, message: String) -> AppResult<()> {
+        let topic_message = TopicMessage::system(topic_id, message);
+        self.topic_message_repo.create_topic_message(&topic_message).await?;
+        self.hub.publish(TopicEvent::MessageCreated(PublicTopicMessage::try_from(&topic_message)?));
+        Ok(())
+    }
+}
+
+impl<TP, UT, TI, RA, TM> TopicUserAppInterface for TopicUserApp<TP, UT, TI, RA, TM>
+where
+    TP: TopicUserRepository,
+    UT: UserTopicRepository,
+    TI: TopicInviteRepository,
+    RA: TopicRoleAppInterface,
+    TM: TopicMessageRepository,
 {
     async fn find_list_users_by_topic_id(
         &self,
+        actor: &Actor,
         query: &RequestGetUsersByTopicId,
-    ) -> AppResult<Vec<PublicTopicUser>> {
-        let mut result: Vec<PublicTopicUser> = vec![];
+    ) -> AppResult<Page<PublicTopicUser>> {
+        self.topic_role_app
+            .authorize(query.topic_id, &actor.username, Capability::Read)
+            .await?;
         self.topic_user_repo
             .find_topic_users_by_partition_key(query)
-            .await
-            .map(|ref topic_user: Vec<TopicUser>| {
-                for item in topic_user.iter() {
-                    result.push(item.try_into().expect("Can not parse data!"));
-                }
-            })
-            .expect("TODO: panic message");
-        Ok(result)
+            .await?
+            .try_map(|item: &TopicUser| item.try_into())
     }
 
-    async fn update_topic_user(&self, topic_user: &RequestUpdateTopicUser) -> AppResult<PublicTopicUser> {
-        self.topic_user_repo
-            .update_topic_users(topic_user)
-            .await
-            .map(|topic_user| PublicTopicUser::try_from(&topic_user).unwrap())
+    async fn join_topic(&self, req: &RequestJoinTopic) -> AppResult<PublicTopicUser> {
+        let topic = self.find_joinable_topic(req.topic_id, &req.username).await?;
+        if self.find_member(req.topic_id, &req.username).await?.is_some() {
+            bail!(ApplicationError::AlreadyExists { resource: "topic member" });
+        }
+
+        // Joining with an invite outstanding answers it.
+        let query = RequestGetTopicMember {
+            topic_id: req.topic_id,
+            username: req.username.to_owned(),
+        };
+        let accepted_invite = match self.topic_invite_repo.find_topic_invite(&query).await? {
+            Some(invite) if invite.is_pending() => Some(TopicInvite {
+                status: INVITE_ACCEPTED.to_string(),
+                updated_at: Utc::now(),
+                ..invite
+            }),
+            _ => None,
+        };
+
+        let topic_user = self
+            .add_member(&topic, &req.username, req.user_id, accepted_invite.as_ref())
+            .await?;
+        self.post_system_message(req.topic_id, format!("{} joined", req.username)).await?;
+        PublicTopicUser::try_from(&topic_user)
+    }
+
+    async fn leave_topic(&self, req: &RequestLeaveTopic) -> AppResult<()> {
+        let user_topic = match self.find_member(req.topic_id, &req.username).await? {
+            Some(user_topic) => user_topic,
+            None => bail!(ApplicationError::NotFound { resource: "topic member" }),
+        };
+
+        // Roles first: an admin whose membership is gone but whose name is
+        // still listed would keep acting as one.
+        self.topic_role_app.revoke_roles(req.topic_id, &req.username).await?;
+        self.topic_user_repo.remove_topic_member(&user_topic).await?;
+        self.hub.revoke_access(req.topic_id, Some(&req.username));
+        self.post_system_message(req.topic_id, format!("{} left", req.username)).await
     }
 
-    // async fn get_full_field_topic_user(&self, query: &RequestGetTopicUserByTopicUserName) -> AppResult<TopicUser> {
-    //     self.latest_message_repo.find_latest_message(query).await
-    // }
+    async fn invite_topic_member(&self, req: &RequestInviteTopicMember) -> AppResult<PublicTopicInvite> {
+        self.topic_role_app
+            .authorize(req.topic_id, &req.invited_by, Capability::Invite)
+            .await?;
+
+        if self.find_member(req.topic_id, &req.username).await?.is_some() {
+            bail!(ApplicationError::AlreadyExists { resource: "topic member" });
+        }
+
+        let query = RequestGetTopicMember {
+            topic_id: req.topic_id,
+            username: req.username.to_owned(),
+        };
+        if let Some(invite) = self.topic_invite_repo.find_topic_invite(&query).await? {
+            if invite.is_pending() {
+                bail!(ApplicationError::AlreadyExists { resource: "topic invite" });
+            }
+        }
+
+        // A declined or stale invite is replaced, so a user can be invited again.
+        let now = Utc::now();
+        let invite = TopicInvite {
+            username: req.username.to_owned(),
+            topic_id: req.topic_id,
+            invited_by: req.invited_by.to_owned(),
+            status: INVITE_PENDING.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.topic_invite_repo.save_topic_invite(&invite).await?;
+        PublicTopicInvite::try_from(&invite)
+    }
+
+    async fn respond_topic_invite(&self, req: &RequestRespondTopicInvite) -> AppResult<PublicTopicInvite> {
+        let query = RequestGetTopicMember {
+            topic_id: req.topic_id,
+            username: req.username.to_owned(),
+        };
+        let invite = match self.topic_invite_repo.find_topic_invite(&query).await? {
+            Some(invite) if invite.is_pending() => invite,
+            Some(_) => bail!(ApplicationError::FailedPrecondition {
+                msg: "invite was already answered".to_string()
+            }),
+            None => bail!(ApplicationError::NotFound { resource: "topic invite" }),
+        };
+
+        let status = match req.accept {
+            true => INVITE_ACCEPTED,
+            false => INVITE_DECLINED,
+        };
+        let invite = TopicInvite {
+            status: status.to_string(),
+            updated_at: Utc::now(),
+            ..invite
+        };
+
+        match req.accept {
+            true => {
+                let topic = self.find_joinable_topic(req.topic_id, &req.username).await?;
+                self.add_member(&topic, &req.username, req.user_id, Some(&invite)).await?;
+                self.post_system_message(req.topic_id, format!("{} joined", req.username)).await?;
+            }
+            false => self.topic_invite_repo.save_topic_invite(&invite).await?,
+        }
+
+        PublicTopicInvite::try_from(&invite)
+    }
+
+    async fn remove_topic_member(&self, req: &RequestRemoveTopicMember) -> AppResult<()> {
+        let access = self
+            .topic_role_app
+            .authorize(req.topic_id, &req.removed_by, Capability::Invite)
+            .await?;
+        let target = self.topic_role_app.find_role(&access.topic, &req.username).await?;
+        ensure_outranks(access.role, target)?;
+
+        let user_topic = match self.find_member(req.topic_id, &req.username).await? {
+            Some(user_topic) => user_topic,
+            None => bail!(ApplicationError::NotFound { resource: "topic member" }),
+        };
+
+        self.topic_role_app.revoke_roles(req.topic_id, &req.username).await?;
+        self.topic_user_repo.remove_topic_member(&user_topic).await?;
+        self.hub.revoke_access(req.topic_id, Some(&req.username));
+        self.post_system_message(
+            req.topic_id,
+            format!("{} removed {}", req.removed_by, req.username),
+        )
+        .await
+    }
+
+    async fn find_list_invites_by_username(
+        &self,
+        query: &RequestGetInvitesByUsername,
+    ) -> AppResult<Page<PublicTopicInvite>> {
+        self.topic_invite_repo
+            .find_topic_invites_by_partition_key(query)
+            .await?
+            .try_map(|item: &TopicInvite| item.try_into())
+    }
+
+    /// Clients repeat this as a heartbeat; only an actual change of status is announced.
+    async fn set_presence(&self, req: &RequestSetPresence) -> AppResult<PublicPresence> {
+        let status = match PresenceStatus::parse(&req.status) {
+            Some(status) => status,
+            None => bail!(ApplicationError::invalid_field("status", "must be one of online, away, offline")),
+        };
+        let topic_ids = self.find_all_topic_ids(&req.username).await?;
+        let previous = self.presence.set_presence(&req.username, status, topic_ids.clone());
+
+        let presence = self.public_presence(&req.username);
+        if previous != status {
+            if let Some(last_seen_at) = presence.last_seen_at {
+                for topic_id in topic_ids {
+                    self.hub.publish(TopicEvent::Presence(PresenceUpdate {
+                        topic_id,
+                        username: req.username.to_owned(),
+                        status: presence.status.to_owned(),
+                        last_seen_at,
+                    }));
+                }
+            }
+        }
+        Ok(presence)
+    }
+
+    /// Usernames that are not members of the topic are left out rather than reported offline.
+    async fn find_list_presence(&self, query: &RequestGetPresence) -> AppResult<Vec<PublicPresence>> {
+        self.topic_role_app
+            .authorize(query.topic_id, &query.username, Capability::Read)
+            .await?;
+
+        let mut presence: Vec<PublicPresence> = vec![];
+        for username in query.usernames.iter() {
+            if self.find_member(query.topic_id, username).await?.is_some() {
+                presence.push(self.public_presence(username));
+            }
+        }
+        Ok(presence)
+    }
 }
@@ -1,24 +1,58 @@
 use anyhow::bail;
 use charybdis::types::{Text, Timeuuid};
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
-use uptop_core::common::result::{AppError, AppResult};
+use uptop_core::common::result::AppResult;
 use validator::Validate;
+use crate::application::pagination::MAX_PAGE_SIZE;
+use crate::application::error::ApplicationError;
+use crate::application::live::presence::PresenceStatus;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
-pub struct RequestUpdateTopicUser {
+pub struct RequestGetUsersByTopicId {
+    pub topic_id: Timeuuid,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
+}
+
+impl RequestGetUsersByTopicId {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            page_size: self.page_size,
+            page_token: self.page_token,
+        })
+    }
+}
+
+/// Looks up one member, or one invite, of a topic.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestGetTopicMember {
+    pub topic_id: Timeuuid,
+    #[validate(length(min = 1))]
+    pub username: Text,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestJoinTopic {
     pub topic_id: Timeuuid,
+    #[serde(default)]
+    #[validate(length(min = 1))]
     pub username: Text,
+    #[serde(default)]
     pub user_id: Timeuuid,
 }
 
-impl RequestUpdateTopicUser {
+impl RequestJoinTopic {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
@@ -30,27 +64,180 @@ impl RequestUpdateTopicUser {
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
-pub struct RequestGetUsersByTopicId {
+pub struct RequestLeaveTopic {
     pub topic_id: Timeuuid,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
 }
 
-impl RequestGetUsersByTopicId {
+impl RequestLeaveTopic {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
         Ok(Self {
             topic_id: self.topic_id,
+            username: self.username,
         })
     }
 }
 
-#[derive(Debug, Error)]
-pub enum RequestFindLatestMessageError {
-    #[error("LatestMessage not found")]
-    LatestMessageNotFound,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestInviteTopicMember {
+    pub topic_id: Timeuuid,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub invited_by: Text,
+    #[validate(length(min = 1))]
+    pub username: Text,
+}
+
+impl RequestInviteTopicMember {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            invited_by: self.invited_by,
+            username: self.username,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestRespondTopicInvite {
+    pub topic_id: Timeuuid,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
+    #[serde(default)]
+    pub user_id: Timeuuid,
+    pub accept: bool,
+}
+
+impl RequestRespondTopicInvite {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            username: self.username,
+            user_id: self.user_id,
+            accept: self.accept,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestRemoveTopicMember {
+    pub topic_id: Timeuuid,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub removed_by: Text,
+    #[validate(length(min = 1))]
+    pub username: Text,
+}
+
+impl RequestRemoveTopicMember {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            removed_by: self.removed_by,
+            username: self.username,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestGetInvitesByUsername {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
+}
+
+impl RequestGetInvitesByUsername {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            username: self.username,
+            page_size: self.page_size,
+            page_token: self.page_token,
+        })
+    }
+}
+
+/// Most usernames one presence query may ask about.
+pub const MAX_PRESENCE_USERNAMES: u64 = 100;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestSetPresence {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
+    /// One of `online`, `away` or `offline`.
+    pub status: Text,
+}
+
+impl RequestSetPresence {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+        if PresenceStatus::parse(&self.status).is_none() {
+            bail!(ApplicationError::invalid_field("status", "must be one of online, away, offline"));
+        }
+
+        Ok(Self {
+            username: self.username,
+            status: self.status,
+        })
+    }
+}
+
+/// Asks for the presence of some members of a topic, usually the ones on screen.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct RequestGetPresence {
+    pub topic_id: Timeuuid,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub username: Text,
+    #[validate(length(min = 1, max = MAX_PRESENCE_USERNAMES))]
+    pub usernames: Vec<Text>,
+}
+
+impl RequestGetPresence {
+    pub fn try_into_domain(self) -> AppResult<Self> {
+        match self.validate() {
+            Ok(_) => (),
+            Err(err) => bail!(ApplicationError::from(err)),
+        };
+
+        Ok(Self {
+            topic_id: self.topic_id,
+            username: self.username,
+            usernames: self.usernames,
+        })
+    }
 }
@@ -1,6 +1,7 @@
-use charybdis::types::{Text, Timeuuid};
+use charybdis::types::{Text, Timestamp, Timeuuid};
 use serde::{Deserialize, Serialize};
 use uptop_core::common::result::AppResult;
+use crate::domain::topic_invite::entity::TopicInvite;
 use crate::domain::topic_user::entity::TopicUser;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -21,3 +22,36 @@ impl TryFrom<&TopicUser> for PublicTopicUser {
         })
     }
 }
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicTopicInvite {
+    pub topic_id: Timeuuid,
+    pub username: Text,
+    pub invited_by: Text,
+    pub status: Text,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+impl TryFrom<&TopicInvite> for PublicTopicInvite {
+    type Error = anyhow::Error;
+
+    fn try_from(topic_invite: &TopicInvite) -> AppResult<Self> {
+        Ok(Self {
+            topic_id: topic_invite.topic_id,
+            username: topic_invite.username.to_owned(),
+            invited_by: topic_invite.invited_by.to_owned(),
+            status: topic_invite.status.to_owned(),
+            created_at: topic_invite.created_at,
+            updated_at: topic_invite.updated_at,
+        })
+    }
+}
+
+/// A member's status; `last_seen_at` is absent when they have not been seen since the server started.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicPresence {
+    pub username: Text,
+    pub status: Text,
+    pub last_seen_at: Option<Timestamp>,
+}
@@ -0,0 +1,73 @@
+use crate::application::actor::Actor;
+use crate::domain::user_identity::{entity::UserIdentity, repository::UserIdentityRepository};
+use charybdis::types::{Text, Timeuuid};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::mpsc;
+
+const RECORD_QUEUE_CAPACITY: usize = 1024;
+
+/// Remembers the user id of every authenticated caller, so conversations can
+/// name other users by username alone. Identities come only from verified
+/// tokens, never from request fields.
+#[derive(Clone, Debug)]
+pub struct IdentityRecorder {
+    /// What this process already stored, one entry per user seen.
+    stored: Arc<Mutex<HashMap<Text, Timeuuid>>>,
+    sender: mpsc::Sender<Actor>,
+}
+
+impl IdentityRecorder {
+    /// The recorder and the queue [`record_identities`] drains.
+    pub fn new() -> (Self, mpsc::Receiver<Actor>) {
+        let (sender, receiver) = mpsc::channel(RECORD_QUEUE_CAPACITY);
+        let recorder = Self {
+            stored: Arc::new(Mutex::new(HashMap::new())),
+            sender,
+        };
+        (recorder, receiver)
+    }
+
+    /// Queues `actor` unless it is stored already. Never waits: with the
+    /// queue full the identity is left for one of the caller's next calls.
+    pub fn record(&self, actor: &Actor) {
+        let mut stored = self.stored.lock().unwrap_or_else(PoisonError::into_inner);
+        if stored.get(&actor.username) == Some(&actor.user_id) {
+            return;
+        }
+        match self.sender.try_send(actor.clone()) {
+            Ok(()) => {
+                stored.insert(actor.username.to_owned(), actor.user_id);
+            }
+            Err(err) => tracing::debug!("identity of {} not queued: {err}", actor.username),
+        }
+    }
+
+    /// Lets a failed write be retried on the user's next call.
+    fn forget(&self, actor: &Actor) {
+        let mut stored = self.stored.lock().unwrap_or_else(PoisonError::into_inner);
+        if stored.get(&actor.username) == Some(&actor.user_id) {
+            stored.remove(&actor.username);
+        }
+    }
+}
+
+/// Stores the identities queued by `recorder`, for as long as the process runs.
+pub async fn record_identities<UI: UserIdentityRepository>(
+    repo: Arc<UI>,
+    recorder: IdentityRecorder,
+    mut receiver: mpsc::Receiver<Actor>,
+) {
+    while let Some(actor) = receiver.recv().await {
+        let identity = UserIdentity {
+            username: actor.username.to_owned(),
+            user_id: actor.user_id,
+            updated_at: Utc::now(),
+        };
+        if let Err(err) = repo.save_user_identity(&identity).await {
+            tracing::warn!("cannot record the identity of {}: {err:?}", actor.username);
+            recorder.forget(&actor);
+        }
+    }
+}
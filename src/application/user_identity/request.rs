@@ -0,0 +1,7 @@
+use charybdis::types::Text;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RequestGetUserIdentity {
+    pub username: Text,
+}
@@ -1,22 +1,19 @@
 use super::{
     response::PublicUserTopic,
 };
-use crate::application::user_topic::request::{RequestGetTopicsByUsername, RequestUpdateUserTopic};
+use crate::application::user_topic::request::RequestGetTopicsByUsername;
 use crate::domain::user_topic::{repository::UserTopicRepository};
+use crate::application::pagination::Page;
 use std::{future::Future, sync::Arc};
 use uptop_core::common::result::AppResult;
+use crate::domain::topic::entity::TopicState;
 use crate::domain::user_topic::entity::UserTopic;
 
 pub trait UserTopicAppInterface: Clone + Send + Sync + 'static {
     fn find_list_topics_by_username(
         &self,
         query: &RequestGetTopicsByUsername,
-    ) -> impl Future<Output=AppResult<Vec<PublicUserTopic>>> + Send;
-
-    fn update_user_topic(
-        &self,
-        user_topic: &RequestUpdateUserTopic,
-    ) -> impl Future<Output=AppResult<PublicUserTopic>> + Send;
+    ) -> impl Future<Output=AppResult<Page<PublicUserTopic>>> + Send;
 }
 
 #[derive(Clone, Debug)]
@@ -43,25 +40,20 @@ where
     async fn find_list_topics_by_username(
         &self,
         query: &RequestGetTopicsByUsername,
-    ) -> AppResult<Vec<PublicUserTopic>> {
-        let mut result: Vec<PublicUserTopic> = vec![];
-        self.user_topic_repo
-            .find_user_topics_by_partition_key(query)
-            .await
-            .map(|ref user_topic: Vec<UserTopic>| {
-                for item in user_topic.iter() {
-                    result.push(item.try_into().expect("Can not parse data!"));
-                }
-            })
-            .expect("TODO: panic message");
-        Ok(result)
-    }
-
-    async fn update_user_topic(&self, user_topic: &RequestUpdateUserTopic) -> AppResult<PublicUserTopic> {
-        self.user_topic_repo
-            .update_user_topics(user_topic)
-            .await
-            .map(|user_topic| PublicUserTopic::try_from(&user_topic).unwrap())
+    ) -> AppResult<Page<PublicUserTopic>> {
+        let mut user_topics = self.user_topic_repo.find_user_topics_by_partition_key(query).await?;
+        // Rows without a kind or state predate them, which a query cannot match
+        // on, so both are filtered here and pages may come back short. Rows of
+        // deleted topics linger until the topic is purged.
+        user_topics.items.retain(|user_topic| match user_topic.state() {
+            TopicState::Deleted => false,
+            TopicState::Archived => query.include_archived,
+            _ => true,
+        });
+        if let Some(topic_kind) = &query.topic_kind {
+            user_topics.items.retain(|user_topic| user_topic.kind() == topic_kind);
+        }
+        user_topics.try_map(|item: &UserTopic| item.try_into())
     }
 
     // async fn get_full_field_user_topic(&self, query: &RequestGetUserTopicByUserTopicName) -> AppResult<UserTopic> {
@@ -1,54 +1,49 @@
 use anyhow::bail;
-use charybdis::types::{Text, Timeuuid};
+use charybdis::types::Text;
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
-use uptop_core::common::result::{AppError, AppResult};
+use uptop_core::common::result::AppResult;
 use validator::Validate;
-
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
-pub struct RequestUpdateUserTopic {
-    pub topic_id: Timeuuid,
-    pub username: Text,
-}
-
-impl RequestUpdateUserTopic {
-    pub fn try_into_domain(self) -> AppResult<Self> {
-        match self.validate() {
-            Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
-        };
-
-        Ok(Self {
-            topic_id: self.topic_id,
-            username: self.username,
-        })
-    }
-}
+use crate::application::pagination::MAX_PAGE_SIZE;
+use crate::application::error::ApplicationError;
+use crate::domain::topic::entity::{TOPIC_KIND_DIRECT, TOPIC_KIND_GROUP, TOPIC_KIND_GROUP_DIRECT};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
 pub struct RequestGetTopicsByUsername {
+    #[serde(default)]
     pub username: Text,
+    /// Only topics of this kind, `group`, `direct` or `group_direct`; every topic when absent.
+    #[serde(default)]
+    pub topic_kind: Option<Text>,
+    /// Archived topics are left out unless asked for.
+    #[serde(default)]
+    pub include_archived: bool,
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: Option<i32>,
+    pub page_token: Option<String>,
 }
 
 impl RequestGetTopicsByUsername {
     pub fn try_into_domain(self) -> AppResult<Self> {
         match self.validate() {
             Ok(_) => (),
-            Err(err) => bail!(AppError::BadRequest {
-                msg: err.to_string()
-            }),
+            Err(err) => bail!(ApplicationError::from(err)),
         };
 
+        if let Some(topic_kind) = &self.topic_kind {
+            if ![TOPIC_KIND_GROUP, TOPIC_KIND_DIRECT, TOPIC_KIND_GROUP_DIRECT].contains(&topic_kind.as_str()) {
+                bail!(ApplicationError::invalid_field(
+                    "topic_kind",
+                    "must be one of group, direct, group_direct"
+                ));
+            }
+        }
+
         Ok(Self {
             username: self.username,
+            topic_kind: self.topic_kind,
+            include_archived: self.include_archived,
+            page_size: self.page_size,
+            page_token: self.page_token,
         })
     }
 }
-
-#[derive(Debug, Error)]
-pub enum RequestFindLatestMessageError {
-    #[error("LatestMessage not found")]
-    LatestMessageNotFound,
-}
@@ -8,6 +8,8 @@ pub struct PublicUserTopic {
     pub topic_id: Timeuuid,
     pub username: Text,
     pub created_at: Timestamp,
+    pub topic_kind: Text,
+    pub topic_state: Text,
 }
 
 impl TryFrom<&UserTopic> for PublicUserTopic {
@@ -18,6 +20,8 @@ impl TryFrom<&UserTopic> for PublicUserTopic {
             topic_id: user_topic.topic_id,
             username: (*user_topic.username).parse()?,
             created_at: user_topic.created_at,
+            topic_kind: user_topic.kind().to_string(),
+            topic_state: user_topic.state().as_str().to_string(),
         })
     }
 }
@@ -0,0 +1,102 @@
+//! Read throughput of the shared session under concurrent load.
+//!
+//! Runs the same topic message query at increasing concurrency, once with the
+//! repositories sharing the session directly and once with every query behind a
+//! single process wide lock, which is how the session used to be shared.
+//!
+//! Usage: `cargo run --release --bin bench_session -- <topic_id> [requests]`
+
+use charybdis::types::Timeuuid;
+use message::application::topic_message::request::RequestGetMessagesByTopicId;
+use message::domain::topic_message::repository::TopicMessageRepository;
+use message::infrastructure::persistence::{
+    create_message_session, statement_cache_size, MessageRepositories,
+};
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use uptop_core::common::result::AppResult;
+use uptop_core::infrastructure::cassandra::create_db_session;
+
+const CONCURRENCY_LEVELS: [usize; 5] = [1, 4, 16, 64, 256];
+const DEFAULT_REQUESTS: usize = 10_000;
+
+#[tokio::main]
+async fn main() -> AppResult<()> {
+    dotenv::dotenv().ok();
+
+    let mut args = env::args().skip(1);
+    let topic_id: Timeuuid = args
+        .next()
+        .expect("usage: bench_session <topic_id> [requests]")
+        .parse()?;
+    let requests: usize = match args.next() {
+        Some(requests) => requests.parse()?,
+        None => DEFAULT_REQUESTS,
+    };
+
+    let session = create_message_session(create_db_session().await, statement_cache_size());
+    let repos = Arc::new(MessageRepositories::new(session));
+    let query = RequestGetMessagesByTopicId {
+        topic_id,
+        page_size: None,
+        page_token: None,
+        before: None,
+        after: None,
+    };
+
+    println!("{:>11} {:>10} {:>12} {:>12}", "concurrency", "mode", "elapsed ms", "ops/s");
+    for concurrency in CONCURRENCY_LEVELS {
+        for serialized in [false, true] {
+            let elapsed = run(&repos, &query, concurrency, requests, serialized).await?;
+            println!(
+                "{:>11} {:>10} {:>12} {:>12.0}",
+                concurrency,
+                if serialized { "locked" } else { "shared" },
+                elapsed.as_millis(),
+                requests as f64 / elapsed.as_secs_f64(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn run(
+    repos: &Arc<MessageRepositories>,
+    query: &RequestGetMessagesByTopicId,
+    concurrency: usize,
+    requests: usize,
+    serialized: bool,
+) -> AppResult<Duration> {
+    let lock = Arc::new(Mutex::new(()));
+    let per_task = requests / concurrency;
+    let started = Instant::now();
+
+    let mut tasks = Vec::with_capacity(concurrency);
+    for _ in 0..concurrency {
+        let repos = Arc::clone(repos);
+        let query = query.clone();
+        let lock = Arc::clone(&lock);
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..per_task {
+                let _guard = match serialized {
+                    true => Some(lock.lock().await),
+                    false => None,
+                };
+                repos
+                    .topic_message
+                    .find_topic_message_by_partition_key(&query)
+                    .await?;
+            }
+            AppResult::Ok(())
+        }));
+    }
+
+    for task in tasks {
+        task.await??;
+    }
+
+    Ok(started.elapsed())
+}
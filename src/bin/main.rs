@@ -1,96 +1,32 @@
-use message::application::topic::app::{TopicApp};
-use message::infrastructure::persistence::IDRepositories;
-use message::interfaces::actions::MessageModuleServices;
-use message::interfaces::topic_handler::{on_create_new_topic, on_find_topic, TopicHandler};
-use scylla::CachingSession;
+use message::application::latest_message::app::{
+    reconcile_unread_counters_every, unread_reconcile_interval, LatestMessageApp,
+};
+use message::application::live::hub::TopicEventHub;
+use message::application::live::presence::{expire_presence_every_second, PresenceRegistry};
+use message::application::notification::app::NotificationApp;
+use message::application::topic::app::TopicApp;
+use message::application::topic_purge::app::{resume_topic_purges, TopicPurgeApp};
+use message::application::topic_message::app::{edit_window, max_pinned_messages, TopicMessageApp};
+use message::application::topic_role::app::TopicRoleApp;
+use message::application::topic_user::app::TopicUserApp;
+use message::application::user_identity::app::{record_identities, IdentityRecorder};
+use message::application::user_topic::app::UserTopicApp;
+use message::infrastructure::migration::Migrator;
+use message::infrastructure::persistence::{
+    create_message_session, statement_cache_size, MessageRepositories,
+};
+use message::interfaces::grpc::auth::{AuthInterceptor, JwtVerifier};
+use message::interfaces::grpc::proto::legacy::message_server::MessageServer;
+use message::interfaces::grpc::proto::v1::message_service_server::MessageServiceServer;
+use message::interfaces::grpc::proto::FILE_MESSAGE_DESCRIPTOR_SET;
+use message::interfaces::grpc::service::MessageGrpcService;
+use message::interfaces::message_handler::MessageHandler;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tonic::{transport::Server, Request, Response, Status};
+use tonic::transport::Server;
 use uptop_core::common::result::AppResult;
 use uptop_core::common::trace::tracing_init;
 use uptop_core::infrastructure::cassandra::{create_db_session, create_keyspace};
 
-mod message_proto {
-    tonic::include_proto!("message");
-}
-
-use message_proto::message_server::{Message, MessageServer};
-use message_proto::{MessageRequest, MessageResponse};
-
-struct MessageService {
-    repositories: Arc<IDRepositories>,
-}
-
-impl MessageService {
-    fn new(repos: IDRepositories) -> Self {
-        Self {
-            repositories: Arc::new(repos),
-        }
-    }
-}
-
-#[tonic::async_trait]
-impl Message for MessageService {
-    async fn send_message(
-        &self,
-        request: Request<MessageRequest>,
-    ) -> Result<Response<MessageResponse>, Status> {
-        // Extract the inner message from the request
-        let payload = request.into_inner();
-        let command = payload.id;
-        let message = payload.message;
-
-        let mut status_code = "OK".to_string();
-        let mut response: MessageResponse = MessageResponse {
-            id: "Internal Server Error".to_owned(),
-            message: "Please try again!".to_owned(),
-        };
-
-        let topic_app = TopicApp::new(Arc::new(self.repositories.topic.clone()));
-        let user_handler = TopicHandler {
-            topic_app: Arc::new(topic_app),
-        };
-
-        match MessageModuleServices::action(&command) {
-            Some(MessageModuleServices::CreateTopic) => {
-                let message = match on_create_new_topic(user_handler, message).await {
-                    Ok(res) => res,
-                    Err(err) => {
-                        status_code = "ERROR".to_string();
-                        err.to_string()
-                    }
-                };
-                response = MessageResponse {
-                    id: status_code,
-                    message,
-                };
-            }
-            Some(MessageModuleServices::GetTopic) => {
-                let message = match on_find_topic(user_handler, message).await {
-                    Ok(res) => res,
-                    Err(err) => {
-                        status_code = "ERROR".to_string();
-                        err.to_string()
-                    }
-                };
-                response = MessageResponse {
-                    id: status_code,
-                    message,
-                };
-            }
-            Some(MessageModuleServices::GetTopics) => {
-                todo!()
-            }
-            Some(MessageModuleServices::UpdateTopic) => {
-                todo!()
-            }
-            _none => (),
-        }
-
-        Ok(Response::new(response))
-    }
-}
-
 #[tokio::main]
 async fn main() -> AppResult<()> {
     dotenv::dotenv().ok();
@@ -98,12 +34,99 @@ async fn main() -> AppResult<()> {
 
     let cassandra = create_db_session().await;
     create_keyspace(&cassandra).await?;
-    let cache_session = CachingSession::from(cassandra, 1);
-    let repos = IDRepositories::new(Arc::new(Mutex::new(cache_session)));
-    repos.auto_mod_identification_migrate().await?;
+    let session = create_message_session(cassandra, statement_cache_size());
+    let migrator = Migrator::new(Arc::clone(&session));
+    migrator.migrate().await?;
+    migrator.verify_schema().await?;
+    let repos = MessageRepositories::new(session);
+
+    let hub = Arc::new(TopicEventHub::default());
+    let presence = Arc::new(PresenceRegistry::default());
+    tokio::spawn(expire_presence_every_second(Arc::clone(&presence), Arc::clone(&hub)));
+    let (identity_recorder, identities) = IdentityRecorder::new();
+    tokio::spawn(record_identities(
+        Arc::new(repos.user_identity.clone()),
+        identity_recorder.clone(),
+        identities,
+    ));
+    let topic_role_app = Arc::new(TopicRoleApp::new(
+        Arc::new(repos.topic.clone()),
+        Arc::new(repos.topic_role.clone()),
+        Arc::new(repos.user_topic.clone()),
+        Arc::clone(&hub),
+    ));
+    let latest_message_app = Arc::new(LatestMessageApp::new(
+        Arc::new(repos.latest_message.clone()),
+        Arc::new(repos.unread_counter.clone()),
+        Arc::new(repos.read_cursor.clone()),
+        Arc::new(repos.topic_message.clone()),
+    ));
+    let topic_purge_app = Arc::new(TopicPurgeApp::new(
+        Arc::new(repos.topic.clone()),
+        Arc::new(repos.topic_purge.clone()),
+        Arc::new(repos.topic_user.clone()),
+        Arc::new(repos.topic_message.clone()),
+        Arc::new(repos.thread_message.clone()),
+        Arc::new(repos.message_reaction.clone()),
+        Arc::new(repos.pinned_message.clone()),
+        Arc::new(repos.read_cursor.clone()),
+        Arc::new(repos.topic_role.clone()),
+        Arc::new(repos.topic_invite.clone()),
+        Arc::new(repos.hidden_message.clone()),
+        Arc::new(repos.latest_message.clone()),
+        Arc::new(repos.notification.clone()),
+        Arc::new(repos.unread_counter.clone()),
+    ));
+    tokio::spawn(resume_topic_purges(Arc::clone(&topic_purge_app)));
+    if let Some(interval) = unread_reconcile_interval() {
+        tokio::spawn(reconcile_unread_counters_every(Arc::clone(&latest_message_app), interval));
+    }
+    let handler = Arc::new(MessageHandler {
+        topic_app: Arc::new(TopicApp::new(
+            Arc::new(repos.topic.clone()),
+            Arc::new(repos.direct_conversation.clone()),
+            Arc::new(repos.group_conversation.clone()),
+            Arc::new(repos.topic_user.clone()),
+            Arc::new(repos.user_topic.clone()),
+            Arc::new(repos.user_identity.clone()),
+            Arc::clone(&topic_role_app),
+            Arc::clone(&topic_purge_app),
+            Arc::clone(&hub),
+        )),
+        latest_message_app: Arc::clone(&latest_message_app),
+        notification_app: Arc::new(NotificationApp::new(Arc::new(repos.notification.clone()))),
+        user_topic_app: Arc::new(UserTopicApp::new(Arc::new(repos.user_topic.clone()))),
+        topic_user_app: Arc::new(TopicUserApp::new(
+            Arc::new(repos.topic_user.clone()),
+            Arc::new(repos.user_topic.clone()),
+            Arc::new(repos.topic_invite.clone()),
+            Arc::clone(&topic_role_app),
+            Arc::new(repos.topic_message.clone()),
+            Arc::clone(&hub),
+            Arc::clone(&presence),
+        )),
+        topic_message_app: Arc::new(
+            TopicMessageApp::new(
+                Arc::new(repos.topic_message.clone()),
+                Arc::new(repos.topic_message_revision.clone()),
+                Arc::new(repos.hidden_message.clone()),
+                Arc::new(repos.thread_message.clone()),
+                Arc::new(repos.message_reaction.clone()),
+                Arc::new(repos.pinned_message.clone()),
+                Arc::new(repos.read_cursor.clone()),
+                Arc::new(repos.unread_counter.clone()),
+                Arc::new(repos.topic_user.clone()),
+                Arc::new(repos.latest_message.clone()),
+                Arc::new(repos.notification.clone()),
+                Arc::clone(&topic_role_app),
+                Arc::clone(&hub),
+                Arc::clone(&presence),
+            )
+            .with_edit_window(edit_window())
+            .with_max_pinned_messages(max_pinned_messages()),
+        ),
+    });
 
-    pub(crate) const FILE_MESSAGE_DESCRIPTOR_SET: &[u8] =
-        tonic::include_file_descriptor_set!("message_descriptor");
     let reflect_sv = tonic_reflection::server::Builder::configure()
         .register_encoded_file_descriptor_set(FILE_MESSAGE_DESCRIPTOR_SET)
         .build_v1()
@@ -111,11 +134,13 @@ async fn main() -> AppResult<()> {
 
     let server_addr = "0.0.0.0:3000".parse().unwrap();
     tracing::info!(message = "Starting server on", %server_addr);
-    let msg_service = MessageService::new(repos);
+    let msg_service = MessageGrpcService::new(handler);
+    let auth = AuthInterceptor::new(Arc::new(JwtVerifier::from_env()?)).with_identity_recorder(identity_recorder);
 
     Server::builder()
         .add_service(reflect_sv)
-        .add_service(MessageServer::new(msg_service))
+        .add_service(MessageServer::with_interceptor(msg_service.clone(), auth.clone()))
+        .add_service(MessageServiceServer::with_interceptor(msg_service, auth))
         .serve(server_addr)
         .await
         .unwrap();
@@ -0,0 +1,50 @@
+//! Applies the schema migrations, or prints what would run.
+//!
+//! Usage: `cargo run --bin migrate -- [--dry-run | --verify]`
+
+use message::infrastructure::migration::Migrator;
+use message::infrastructure::persistence::{create_message_session, statement_cache_size};
+use std::env;
+use uptop_core::common::result::AppResult;
+use uptop_core::common::trace::tracing_init;
+use uptop_core::infrastructure::cassandra::{create_db_session, create_keyspace};
+
+#[tokio::main]
+async fn main() -> AppResult<()> {
+    dotenv::dotenv().ok();
+    let _gaurd = tracing_init();
+
+    let cassandra = create_db_session().await;
+    create_keyspace(&cassandra).await?;
+    let migrator = Migrator::new(create_message_session(cassandra, statement_cache_size()));
+
+    match env::args().nth(1).as_deref() {
+        Some("--dry-run") => {
+            let pending = migrator.pending().await?;
+            if pending.is_empty() {
+                println!("schema is up to date");
+            }
+            for migration in pending {
+                println!("-- {:04} {} ({})", migration.version, migration.name, migration.checksum());
+                for statement in migration.statements() {
+                    println!("{statement};\n");
+                }
+                if migration.backfill.is_some() {
+                    println!("-- followed by a backfill\n");
+                }
+            }
+        }
+        Some("--verify") => {
+            migrator.verify_schema().await?;
+            println!("schema matches the models");
+        }
+        _ => {
+            for migration in migrator.migrate().await? {
+                println!("applied {:04} {}", migration.version, migration.name);
+            }
+            migrator.verify_schema().await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Text, Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// The single direct conversation between two users. `user_a` sorts before
+/// `user_b`, so whichever of them opens it finds the same row.
+#[charybdis_model(
+    table_name = uptop.direct_conversations,
+    partition_keys = [user_a, user_b],
+    clustering_keys = [],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct DirectConversation {
+    pub user_a: Text,
+    pub user_b: Text,
+    pub topic_id: Timeuuid,
+    /// Creation time of the topic, which is clustered by it.
+    pub created_at: Timestamp,
+}
+
+impl DirectConversation {
+    /// The two usernames in key order.
+    pub fn participants(username: &str, other: &str) -> (Text, Text) {
+        match username <= other {
+            true => (username.to_owned(), other.to_owned()),
+            false => (other.to_owned(), username.to_owned()),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use super::entity::DirectConversation;
+use crate::application::topic::request::RequestGetDirectConversation;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait DirectConversationRepository: Clone + Send + Sync + 'static {
+    fn find_direct_conversation(
+        &self,
+        query: &RequestGetDirectConversation,
+    ) -> impl Future<Output=AppResult<Option<DirectConversation>>> + Send;
+
+    /// Stores `conversation` unless its pair already has one, and returns the
+    /// one stored. Concurrent calls for a pair all get the same conversation.
+    fn create_direct_conversation(
+        &self,
+        conversation: &DirectConversation,
+    ) -> impl Future<Output=AppResult<DirectConversation>> + Send;
+}
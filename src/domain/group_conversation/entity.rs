@@ -0,0 +1,38 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{List, Text, Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The unnamed conversation of one set of users. Keyed by a digest of the
+/// sorted usernames, so opening it with the same set in any order finds it.
+#[charybdis_model(
+    table_name = uptop.group_conversations,
+    partition_keys = [member_key],
+    clustering_keys = [],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct GroupConversation {
+    pub member_key: Text,
+    /// Sorted usernames of the set.
+    pub members: List<Text>,
+    pub topic_id: Timeuuid,
+    /// Creation time of the topic, which is clustered by it.
+    pub created_at: Timestamp,
+}
+
+impl GroupConversation {
+    /// `members` must be sorted and free of duplicates.
+    pub fn member_key(members: &[Text]) -> Text {
+        let mut digest = Sha256::new();
+        for member in members {
+            // Length prefixed, so no two sets share an encoding.
+            digest.update((member.len() as u64).to_be_bytes());
+            digest.update(member.as_bytes());
+        }
+        format!("{:x}", digest.finalize())
+    }
+}
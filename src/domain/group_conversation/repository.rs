@@ -0,0 +1,26 @@
+use super::entity::GroupConversation;
+use crate::application::topic::request::RequestGetGroupConversation;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait GroupConversationRepository: Clone + Send + Sync + 'static {
+    fn find_group_conversation(
+        &self,
+        query: &RequestGetGroupConversation,
+    ) -> impl Future<Output=AppResult<Option<GroupConversation>>> + Send;
+
+    /// Stores `conversation` unless its set already has one, and returns the
+    /// one stored. Concurrent calls for a set all get the same conversation.
+    fn create_group_conversation(
+        &self,
+        conversation: &GroupConversation,
+    ) -> impl Future<Output=AppResult<GroupConversation>> + Send;
+
+    /// Points the set at `conversation` if it still points at `previous`'s
+    /// topic, and returns the one stored either way.
+    fn replace_group_conversation(
+        &self,
+        previous: &GroupConversation,
+        conversation: &GroupConversation,
+    ) -> impl Future<Output=AppResult<GroupConversation>> + Send;
+}
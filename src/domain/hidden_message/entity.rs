@@ -0,0 +1,21 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// A message one user deleted for themselves. Everyone else still sees it.
+#[charybdis_model(
+    table_name = uptop.hidden_messages,
+    partition_keys = [user_id, topic_id],
+    clustering_keys = [message_id],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct HiddenMessage {
+    pub user_id: Timeuuid,
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub hidden_at: Timestamp,
+}
@@ -0,0 +1,23 @@
+use super::entity::HiddenMessage;
+use crate::application::topic_message::request::RequestGetHiddenMessages;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait HiddenMessageRepository: Clone + Send + Sync + 'static {
+    /// Every message the user hid in the topic.
+    fn find_hidden_messages(
+        &self,
+        query: &RequestGetHiddenMessages,
+    ) -> impl Future<Output=AppResult<Vec<HiddenMessage>>> + Send;
+
+    fn save_hidden_message(
+        &self,
+        hidden_message: &HiddenMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Drops everything the user hid in the topic.
+    fn remove_hidden_messages(
+        &self,
+        query: &RequestGetHiddenMessages,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
@@ -7,11 +7,11 @@ use serde::{Deserialize, Serialize};
 #[charybdis_model(
     table_name = uptop.latest_messages,
     partition_keys = [user_id],
-    clustering_keys = [],
+    clustering_keys = [topic_id],
     global_secondary_indexes = [],
     
 )]
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct LatestMessage {
     pub latest_message_id: Timeuuid,
     pub latest_message_content: Text,
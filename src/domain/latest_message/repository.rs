@@ -1,8 +1,10 @@
 use super::entity::LatestMessage;
 use crate::application::latest_message::request::{
+    RequestGetLatestMessage,
     RequestGetLatestMessagesByUserId,
     RequestUpdateLatestMessage,
 };
+use crate::application::pagination::Page;
 use std::future::Future;
 use uptop_core::common::result::AppResult;
 
@@ -10,10 +12,25 @@ pub trait LatestMessageRepository: Clone + Send + Sync + 'static {
     fn find_latest_message_by_partition_key(
         &self,
         query: &RequestGetLatestMessagesByUserId,
-    ) -> impl Future<Output=AppResult<Vec<LatestMessage>>> + Send;
+    ) -> impl Future<Output=AppResult<Page<LatestMessage>>> + Send;
+
+    fn find_latest_message(
+        &self,
+        query: &RequestGetLatestMessage,
+    ) -> impl Future<Output=AppResult<Option<LatestMessage>>> + Send;
 
     fn update_latest_message(
         &self,
         latest_message: &RequestUpdateLatestMessage,
     ) -> impl Future<Output=AppResult<LatestMessage>> + Send;
+
+    fn upsert_latest_messages(
+        &self,
+        latest_messages: &[LatestMessage],
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn remove_latest_messages(
+        &self,
+        keys: &[RequestGetLatestMessage],
+    ) -> impl Future<Output=AppResult<()>> + Send;
 }
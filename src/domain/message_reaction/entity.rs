@@ -0,0 +1,24 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Text, Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// One user's reaction with one emoji. Reactions live beside the message
+/// rather than on it, so reacting never rewrites the message row.
+#[charybdis_model(
+    table_name = uptop.message_reactions,
+    partition_keys = [topic_id],
+    clustering_keys = [message_id, emoji, user_id],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct MessageReaction {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    /// A unicode emoji or a custom `:shortcode:`.
+    pub emoji: Text,
+    pub user_id: Timeuuid,
+    pub reacted_at: Timestamp,
+}
@@ -0,0 +1,36 @@
+use super::entity::MessageReaction;
+use crate::application::topic_message::request::{RequestGetMessageReactions, RequestGetTopicMessage};
+use charybdis::types::Timeuuid;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait MessageReactionRepository: Clone + Send + Sync + 'static {
+    /// Every reaction on the given messages of one topic.
+    fn find_reactions_by_message_ids(
+        &self,
+        query: &RequestGetMessageReactions,
+    ) -> impl Future<Output=AppResult<Vec<MessageReaction>>> + Send;
+
+    /// Reacting twice with the same emoji keeps a single reaction.
+    fn save_reaction(
+        &self,
+        reaction: &MessageReaction,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn remove_reaction(
+        &self,
+        reaction: &MessageReaction,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Drops every reaction on a message that was deleted or purged.
+    fn remove_message_reactions(
+        &self,
+        query: &RequestGetTopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Drops every reaction in the topic at once, one partition delete.
+    fn remove_topic_reactions(
+        &self,
+        topic_id: Timeuuid,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
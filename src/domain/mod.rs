@@ -4,4 +4,17 @@ pub mod topic_message;
 pub mod topic_user;
 pub mod user_topic;
 pub mod notification;
+pub mod topic_invite;
+pub mod topic_role;
 
+pub mod topic_message_revision;
+pub mod hidden_message;
+pub mod thread_message;
+pub mod message_reaction;
+pub mod read_cursor;
+pub mod unread_counter;
+pub mod direct_conversation;
+pub mod group_conversation;
+pub mod pinned_message;
+pub mod user_identity;
+pub mod topic_purge;
@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
     global_secondary_indexes = [],
 
 )]
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Notification {
     pub topic_id: Timeuuid,
     pub username: Text,
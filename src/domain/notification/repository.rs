@@ -1,16 +1,23 @@
 use super::entity::Notification;
+use crate::application::pagination::Page;
 use std::future::Future;
 use uptop_core::common::result::AppResult;
-use crate::application::notification::request::{RequestGetNotificationByUsername, RequestUpdateNotification};
+use crate::application::notification::request::{RequestGetNotificationByUsername, RequestRemoveTopicNotifications};
 
 pub trait NotificationRepository: Clone + Send + Sync + 'static {
     fn find_notifications_by_partition_key(
         &self,
         query: &RequestGetNotificationByUsername,
-    ) -> impl Future<Output=AppResult<Vec<Notification>>> + Send;
+    ) -> impl Future<Output=AppResult<Page<Notification>>> + Send;
 
-    fn update_notifications(
+    fn create_notifications(
         &self,
-        topic_message: &RequestUpdateNotification,
-    ) -> impl Future<Output=AppResult<Notification>> + Send;
+        notifications: &[Notification],
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Drops the user's notifications about one topic.
+    fn remove_topic_notifications(
+        &self,
+        query: &RequestRemoveTopicNotifications,
+    ) -> impl Future<Output=AppResult<()>> + Send;
 }
@@ -0,0 +1,22 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// A message pinned to the top of its topic. The whole partition is one
+/// topic's pins, which is capped, so it is always read in full.
+#[charybdis_model(
+    table_name = uptop.pinned_messages,
+    partition_keys = [topic_id],
+    clustering_keys = [message_id],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct PinnedMessage {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub pinned_by: Timeuuid,
+    pub pinned_at: Timestamp,
+}
@@ -0,0 +1,29 @@
+use super::entity::PinnedMessage;
+use crate::application::topic_message::request::{RequestGetPinnedMessages, RequestGetTopicMessage};
+use charybdis::types::Timeuuid;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait PinnedMessageRepository: Clone + Send + Sync + 'static {
+    /// Every pin of the topic, in no particular order.
+    fn find_pinned_messages(
+        &self,
+        query: &RequestGetPinnedMessages,
+    ) -> impl Future<Output=AppResult<Vec<PinnedMessage>>> + Send;
+
+    fn save_pinned_message(
+        &self,
+        pinned_message: &PinnedMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Removing a message that is not pinned does nothing.
+    fn remove_pinned_message(
+        &self,
+        query: &RequestGetTopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn remove_topic_pins(
+        &self,
+        topic_id: Timeuuid,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
@@ -0,0 +1,25 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// How far one user has read a topic. Everything created up to
+/// `last_read_at` counts as read.
+#[charybdis_model(
+    table_name = uptop.read_cursors,
+    partition_keys = [topic_id],
+    clustering_keys = [user_id],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct ReadCursor {
+    pub topic_id: Timeuuid,
+    pub user_id: Timeuuid,
+    pub last_read_message_id: Timeuuid,
+    /// Creation time of the last read message, which cursors are compared by.
+    pub last_read_at: Timestamp,
+    /// When the user marked it read.
+    pub read_at: Timestamp,
+}
@@ -0,0 +1,29 @@
+use super::entity::ReadCursor;
+use crate::application::topic_message::request::{RequestGetReadCursor, RequestGetReadCursorsByTopicId};
+use charybdis::types::Timeuuid;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait ReadCursorRepository: Clone + Send + Sync + 'static {
+    fn find_read_cursor(
+        &self,
+        query: &RequestGetReadCursor,
+    ) -> impl Future<Output=AppResult<Option<ReadCursor>>> + Send;
+
+    /// The cursor of every user who ever read the topic, including past members.
+    fn find_read_cursors_by_topic_id(
+        &self,
+        query: &RequestGetReadCursorsByTopicId,
+    ) -> impl Future<Output=AppResult<Vec<ReadCursor>>> + Send;
+
+    fn save_read_cursor(
+        &self,
+        read_cursor: &ReadCursor,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Drops the cursor of everyone who read the topic.
+    fn remove_topic_read_cursors(
+        &self,
+        topic_id: Timeuuid,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
@@ -0,0 +1,22 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// Index of the replies to one message, oldest first. The replies themselves
+/// are rows of `topic_replies`, read as `TopicMessage` carrying the `parent_id`.
+#[charybdis_model(
+    table_name = uptop.thread_messages,
+    partition_keys = [topic_id, parent_id],
+    clustering_keys = [message_id],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct ThreadMessage {
+    pub topic_id: Timeuuid,
+    pub parent_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub created_at: Timestamp,
+}
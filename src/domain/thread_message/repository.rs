@@ -0,0 +1,39 @@
+use crate::application::pagination::Page;
+use crate::application::topic_message::request::RequestGetThreadMessages;
+use crate::domain::topic_message::entity::TopicMessage;
+use charybdis::types::Timeuuid;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait ThreadMessageRepository: Clone + Send + Sync + 'static {
+    /// Writes the reply and its thread entry together.
+    fn create_thread_reply(
+        &self,
+        reply: &TopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Drops the thread entry of a reply that no longer exists.
+    fn remove_thread_reply(
+        &self,
+        reply: &TopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn find_thread_messages_by_partition_key(
+        &self,
+        query: &RequestGetThreadMessages,
+    ) -> impl Future<Output=AppResult<Page<TopicMessage>>> + Send;
+
+    /// Recounts the thread under `parent` and stores the reply count and
+    /// last reply time on it, returning the updated parent.
+    fn refresh_reply_stats(
+        &self,
+        parent: &TopicMessage,
+    ) -> impl Future<Output=AppResult<TopicMessage>> + Send;
+
+    /// Drops the thread entries of every thread in the topic. The threads are
+    /// found from the replies, so this must run before those are purged.
+    fn remove_topic_threads(
+        &self,
+        topic_id: Timeuuid,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
@@ -1,4 +1,5 @@
 use crate::application::topic::request::{RequestCreateTopic, RequestUpdateTopic};
+use crate::domain::topic_role::entity::Capability;
 use charybdis::{
     macros::charybdis_model,
     types::{List, Text, Timestamp, Timeuuid},
@@ -13,10 +14,10 @@ use uptop_core::common::{result::AppResult, utils::now_timeuuid};
     clustering_keys = [created_at],
     global_secondary_indexes = [topic_name],
     table_options = r#"
-        CLUSTERING ORDER BY (created_adt DESC);
+        CLUSTERING ORDER BY (created_at DESC);
     "#
 )]
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Topic {
     pub topic_id: Timeuuid,
     pub topic_name: Text,
@@ -25,6 +26,121 @@ pub struct Topic {
     pub topic_admins: List<Text>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
+    /// [`TOPIC_KIND_GROUP`], [`TOPIC_KIND_DIRECT`] or [`TOPIC_KIND_GROUP_DIRECT`].
+    /// Topics created before kinds existed have none and are groups.
+    pub topic_kind: Option<Text>,
+    /// A [`TopicState`]; topics created before states existed have none and are active.
+    pub topic_state: Option<Text>,
+}
+
+pub const TOPIC_KIND_GROUP: &str = "group";
+pub const TOPIC_KIND_DIRECT: &str = "direct";
+/// An unnamed conversation of a set of users, until promoted to a group.
+pub const TOPIC_KIND_GROUP_DIRECT: &str = "group_direct";
+
+/// Where a topic is in its lifecycle. Only owners move it from one state to
+/// another, and nothing leaves `Deleted`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopicState {
+    Active,
+    /// Read-only: nothing can be posted, edited or pinned, but moderators can
+    /// still remove messages and members come and go.
+    Locked,
+    /// Read-only and left out of its members' topic lists unless asked for.
+    Archived,
+    /// Gone for everyone; what hangs off it is removed in the background.
+    Deleted,
+}
+
+impl TopicState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TopicState::Active => "active",
+            TopicState::Locked => "locked",
+            TopicState::Archived => "archived",
+            TopicState::Deleted => "deleted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<TopicState> {
+        match value {
+            "active" => Some(TopicState::Active),
+            "locked" => Some(TopicState::Locked),
+            "archived" => Some(TopicState::Archived),
+            "deleted" => Some(TopicState::Deleted),
+            _ => None,
+        }
+    }
+
+    /// Reads a stored state. None predates states and is active; unknown
+    /// values, e.g. written by a newer build, are treated as locked.
+    pub fn from_stored(value: Option<&str>) -> TopicState {
+        match value {
+            Some(state) => TopicState::parse(state).unwrap_or(TopicState::Locked),
+            None => TopicState::Active,
+        }
+    }
+
+    /// What the state leaves possible, whatever the caller's role. Settings
+    /// stay editable in locked and archived topics so they can be reopened.
+    pub fn allows(&self, capability: Capability) -> bool {
+        match self {
+            TopicState::Active => true,
+            TopicState::Locked => !matches!(
+                capability,
+                Capability::Post | Capability::EditOwn | Capability::EditAny | Capability::Pin
+            ),
+            TopicState::Archived => matches!(capability, Capability::Read | Capability::ManageSettings),
+            TopicState::Deleted => false,
+        }
+    }
+}
+
+impl Topic {
+    pub fn kind(&self) -> &str {
+        self.topic_kind.as_deref().unwrap_or(TOPIC_KIND_GROUP)
+    }
+
+    pub fn state(&self) -> TopicState {
+        TopicState::from_stored(self.topic_state.as_deref())
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.state() == TopicState::Deleted
+    }
+
+    /// Direct and group direct conversations, whose members are fixed when
+    /// opened and which only they can see.
+    pub fn is_conversation(&self) -> bool {
+        matches!(self.kind(), TOPIC_KIND_DIRECT | TOPIC_KIND_GROUP_DIRECT)
+    }
+
+    /// The topic with `changes` applied and `updated_at` moved on. Pushing a
+    /// name already listed or popping one that is not leaves the list as is.
+    pub fn with_update(&self, changes: &RequestUpdateTopic) -> Topic {
+        Topic {
+            topic_name: changes.topic_name.to_owned().unwrap_or_else(|| self.topic_name.to_owned()),
+            topic_description: changes
+                .topic_description
+                .to_owned()
+                .or_else(|| self.topic_description.to_owned()),
+            topic_owners: push_pop(&self.topic_owners, &changes.push_to_owners, &changes.pop_to_owners),
+            topic_admins: push_pop(&self.topic_admins, &changes.push_to_admins, &changes.pop_to_admins),
+            updated_at: Utc::now(),
+            ..self.clone()
+        }
+    }
+}
+
+fn push_pop(names: &[Text], pushed: &Option<Vec<String>>, popped: &Option<Vec<String>>) -> List<Text> {
+    let mut names = names.to_vec();
+    for name in pushed.iter().flatten() {
+        if !names.contains(name) {
+            names.push(name.to_owned());
+        }
+    }
+    names.retain(|name| !popped.iter().flatten().any(|popped| popped == name));
+    names
 }
 
 impl TryFrom<RequestCreateTopic> for Topic {
@@ -40,14 +156,8 @@ impl TryFrom<RequestCreateTopic> for Topic {
         topic.topic_name = value.topic_name;
         topic.topic_owners = value.topic_owners;
         topic.topic_admins = value.topic_admins;
+        topic.topic_kind = Some(TOPIC_KIND_GROUP.to_string());
+        topic.topic_state = Some(TopicState::Active.as_str().to_string());
         Ok(topic)
     }
 }
-
-impl TryFrom<RequestUpdateTopic> for Topic {
-    type Error = anyhow::Error;
-
-    fn try_from(_value: RequestUpdateTopic) -> AppResult<Self> {
-        todo!()
-    }
-}
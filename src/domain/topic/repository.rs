@@ -1,8 +1,9 @@
 use super::entity::Topic;
 use crate::application::topic::request::{
     RequestGetTopicByIndexKey, RequestGetTopicByPartitionKey, RequestGetTopicByPrimaryKey,
-    RequestUpdateTopic,
 };
+use crate::application::pagination::Page;
+use charybdis::types::Timestamp;
 use std::future::Future;
 use uptop_core::common::result::AppResult;
 
@@ -15,7 +16,7 @@ pub trait TopicRepository: Clone + Send + Sync + 'static {
     fn find_topic_by_partition_key(
         &self,
         query: &RequestGetTopicByPartitionKey,
-    ) -> impl Future<Output = AppResult<Vec<Topic>>> + Send;
+    ) -> impl Future<Output = AppResult<Page<Topic>>> + Send;
 
     fn find_topic_by_primary_key(
         &self,
@@ -25,10 +26,14 @@ pub trait TopicRepository: Clone + Send + Sync + 'static {
     fn find_topic_by_index_key(
         &self,
         query: &RequestGetTopicByIndexKey,
-    ) -> impl Future<Output = AppResult<Vec<Topic>>> + Send;
+    ) -> impl Future<Output = AppResult<Page<Topic>>> + Send;
 
-    fn update_topic<'u>(
+    /// Overwrites the name, description, owners, admins, kind and state of an
+    /// existing topic, provided its stored `updated_at` is still
+    /// `expected_updated_at`. `false` when another write got there first.
+    fn update_topic(
         &self,
-        topic: &'u RequestUpdateTopic,
-    ) -> impl Future<Output = AppResult<Topic>> + Send;
+        topic: &Topic,
+        expected_updated_at: Timestamp,
+    ) -> impl Future<Output = AppResult<bool>> + Send;
 }
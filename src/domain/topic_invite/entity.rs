@@ -0,0 +1,32 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Text, Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+#[charybdis_model(
+    table_name = uptop.topic_invites,
+    partition_keys = [username],
+    clustering_keys = [topic_id],
+    global_secondary_indexes = [topic_id],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct TopicInvite {
+    pub username: Text,
+    pub topic_id: Timeuuid,
+    pub invited_by: Text,
+    pub status: Text,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+pub const INVITE_PENDING: &str = "pending";
+pub const INVITE_ACCEPTED: &str = "accepted";
+pub const INVITE_DECLINED: &str = "declined";
+
+impl TopicInvite {
+    pub fn is_pending(&self) -> bool {
+        self.status == INVITE_PENDING
+    }
+}
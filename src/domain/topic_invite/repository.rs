@@ -0,0 +1,30 @@
+use super::entity::TopicInvite;
+use crate::application::pagination::Page;
+use crate::application::topic_user::request::{RequestGetInvitesByUsername, RequestGetTopicMember};
+use charybdis::types::Timeuuid;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait TopicInviteRepository: Clone + Send + Sync + 'static {
+    fn find_topic_invite(
+        &self,
+        query: &RequestGetTopicMember,
+    ) -> impl Future<Output=AppResult<Option<TopicInvite>>> + Send;
+
+    fn find_topic_invites_by_partition_key(
+        &self,
+        query: &RequestGetInvitesByUsername,
+    ) -> impl Future<Output=AppResult<Page<TopicInvite>>> + Send;
+
+    fn save_topic_invite(
+        &self,
+        topic_invite: &TopicInvite,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Drops every invite to the topic, whatever its status. Invites are
+    /// keyed by invitee, so they are found through the index on the topic.
+    fn remove_topic_invites(
+        &self,
+        topic_id: Timeuuid,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
@@ -1,20 +1,147 @@
+use crate::application::topic_message::request::RequestPostTopicMessage;
 use charybdis::{
     macros::charybdis_model,
     types::{Text, Timestamp, Timeuuid},
 };
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use uptop_core::common::{result::AppResult, utils::now_timeuuid};
 
 #[charybdis_model(
     table_name = uptop.topic_messages,
     partition_keys = [topic_id],
-    clustering_keys = [created_at],
+    clustering_keys = [message_id],
     global_secondary_indexes = [],
-
+    table_options = r#"
+        CLUSTERING ORDER BY (message_id DESC);
+    "#
 )]
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct TopicMessage {
     pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
     pub from_user_id: Timeuuid,
     pub message: Text,
     pub created_at: Timestamp,
+    /// `USER_MESSAGE` or `SYSTEM_MESSAGE`; rows written before kinds existed have none.
+    pub kind: Option<Text>,
+    /// When the body was last edited, none if it never was.
+    pub edited_at: Option<Timestamp>,
+    /// Set once the message is deleted for everyone; the row stays as a
+    /// tombstone holding its place in the timeline, with the body cleared.
+    pub deleted_at: Option<Timestamp>,
+    pub deleted_by: Option<Timeuuid>,
+    /// The message this one replies to in a thread.
+    pub parent_id: Option<Timeuuid>,
+    /// Kept on the parent of a thread, none until the first reply.
+    pub reply_count: Option<i32>,
+    pub last_reply_at: Option<Timestamp>,
+    /// Snapshot of the quoted message as it read when this one was posted.
+    pub quoted_message_id: Option<Timeuuid>,
+    pub quoted_from_user_id: Option<Timeuuid>,
+    pub quoted_message: Option<Text>,
+    /// Where a forwarded message was first posted, and by whom.
+    pub forwarded_from_topic_id: Option<Timeuuid>,
+    pub forwarded_from_message_id: Option<Timeuuid>,
+    pub forwarded_from_user_id: Option<Timeuuid>,
+}
+
+pub const USER_MESSAGE: &str = "user";
+/// Written by the service to record a change in the topic, such as a member joining.
+pub const SYSTEM_MESSAGE: &str = "system";
+
+impl TopicMessage {
+    pub fn system(topic_id: Timeuuid, message: Text) -> Self {
+        TopicMessage {
+            topic_id,
+            message_id: now_timeuuid(),
+            message,
+            created_at: Utc::now(),
+            kind: Some(SYSTEM_MESSAGE.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn kind(&self) -> &str {
+        self.kind.as_deref().unwrap_or(USER_MESSAGE)
+    }
+
+    /// Copies `source` into `topic_id` as a new message from `from_user_id`.
+    /// Provenance points at the original, even when `source` is itself a forward.
+    pub fn forwarded(topic_id: Timeuuid, from_user_id: Timeuuid, source: &TopicMessage) -> Self {
+        let (origin_topic_id, origin_message_id, origin_user_id) = match source.forwarded_from_message_id {
+            Some(message_id) => (
+                source.forwarded_from_topic_id.unwrap_or(source.topic_id),
+                message_id,
+                source.forwarded_from_user_id.unwrap_or(source.from_user_id),
+            ),
+            None => (source.topic_id, source.message_id, source.from_user_id),
+        };
+        TopicMessage {
+            topic_id,
+            message_id: now_timeuuid(),
+            from_user_id,
+            message: source.message.to_owned(),
+            created_at: Utc::now(),
+            kind: Some(USER_MESSAGE.to_string()),
+            forwarded_from_topic_id: Some(origin_topic_id),
+            forwarded_from_message_id: Some(origin_message_id),
+            forwarded_from_user_id: Some(origin_user_id),
+            ..Default::default()
+        }
+    }
+
+    pub fn quoting(self, quoted: &TopicMessage) -> Self {
+        TopicMessage {
+            quoted_message_id: Some(quoted.message_id),
+            quoted_from_user_id: Some(quoted.from_user_id),
+            quoted_message: Some(quoted.message.to_owned()),
+            ..self
+        }
+    }
+
+    pub fn is_reply(&self) -> bool {
+        self.parent_id.is_some()
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    pub fn tombstone(&self, deleted_by: Timeuuid, deleted_at: Timestamp) -> Self {
+        TopicMessage {
+            message: Text::new(),
+            quoted_message: None,
+            deleted_at: Some(deleted_at),
+            deleted_by: Some(deleted_by),
+            ..self.clone()
+        }
+    }
+}
+
+impl TryFrom<RequestPostTopicMessage> for TopicMessage {
+    type Error = anyhow::Error;
+
+    fn try_from(value: RequestPostTopicMessage) -> AppResult<Self> {
+        Ok(TopicMessage {
+            topic_id: value.topic_id,
+            message_id: now_timeuuid(),
+            from_user_id: value.from_user_id,
+            message: value.message,
+            created_at: Utc::now(),
+            kind: Some(USER_MESSAGE.to_string()),
+            edited_at: None,
+            deleted_at: None,
+            deleted_by: None,
+            parent_id: value.parent_id,
+            reply_count: None,
+            last_reply_at: None,
+            quoted_message_id: value.quoted_message_id,
+            quoted_from_user_id: None,
+            quoted_message: None,
+            forwarded_from_topic_id: None,
+            forwarded_from_message_id: None,
+            forwarded_from_user_id: None,
+        })
+    }
 }
@@ -1,16 +1,50 @@
 use super::entity::TopicMessage;
-use crate::application::topic_message::request::{RequestGetMessagesByTopicId, RequestUpdateTopicMessage};
+use crate::domain::topic_message_revision::entity::TopicMessageRevision;
+use crate::application::topic_message::request::{RequestGetMessagesByTopicId, RequestGetTopicMessage, RequestUpdateTopicMessage};
+use crate::application::pagination::Page;
+use charybdis::types::Timeuuid;
 use std::future::Future;
 use uptop_core::common::result::AppResult;
 
 pub trait TopicMessageRepository: Clone + Send + Sync + 'static {
+    fn create_topic_message<'c>(
+        &self,
+        topic_message: &'c TopicMessage,
+    ) -> impl Future<Output=AppResult<&'c TopicMessage>> + Send;
+
     fn find_topic_message_by_partition_key(
         &self,
         query: &RequestGetMessagesByTopicId,
-    ) -> impl Future<Output=AppResult<Vec<TopicMessage>>> + Send;
+    ) -> impl Future<Output=AppResult<Page<TopicMessage>>> + Send;
 
+    fn find_topic_message(
+        &self,
+        query: &RequestGetTopicMessage,
+    ) -> impl Future<Output=AppResult<Option<TopicMessage>>> + Send;
+
+    /// Replaces the body and records `revision` in the same write.
     fn update_topic_message(
         &self,
         topic_message: &RequestUpdateTopicMessage,
+        revision: &TopicMessageRevision,
     ) -> impl Future<Output=AppResult<TopicMessage>> + Send;
+
+    /// Overwrites the message with its tombstone and drops its revisions.
+    fn delete_topic_message(
+        &self,
+        tombstone: &TopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Removes the message and its revisions for good.
+    fn purge_topic_message(
+        &self,
+        query: &RequestGetTopicMessage,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Removes every message of the topic, timeline and replies, with their
+    /// revisions: one read of the message ids, then a delete per partition.
+    fn purge_topic_messages(
+        &self,
+        topic_id: Timeuuid,
+    ) -> impl Future<Output=AppResult<()>> + Send;
 }
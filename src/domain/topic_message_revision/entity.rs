@@ -0,0 +1,42 @@
+use crate::domain::topic_message::entity::TopicMessage;
+use charybdis::{
+    macros::charybdis_model,
+    types::{Text, Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+use uptop_core::common::utils::now_timeuuid;
+
+/// One edit of a message: who made it, when, and the body it replaced.
+/// Rows are only ever inserted, newest first.
+#[charybdis_model(
+    table_name = uptop.topic_message_revisions,
+    partition_keys = [topic_id, message_id],
+    clustering_keys = [revision_id],
+    global_secondary_indexes = [],
+    table_options = r#"
+        CLUSTERING ORDER BY (revision_id DESC);
+    "#
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct TopicMessageRevision {
+    pub topic_id: Timeuuid,
+    pub message_id: Timeuuid,
+    pub revision_id: Timeuuid,
+    pub edited_by: Timeuuid,
+    pub edited_at: Timestamp,
+    /// The body as it read before this edit.
+    pub message: Text,
+}
+
+impl TopicMessageRevision {
+    pub fn of(previous: &TopicMessage, edited_by: Timeuuid, edited_at: Timestamp) -> Self {
+        TopicMessageRevision {
+            topic_id: previous.topic_id,
+            message_id: previous.message_id,
+            revision_id: now_timeuuid(),
+            edited_by,
+            edited_at,
+            message: previous.message.to_owned(),
+        }
+    }
+}
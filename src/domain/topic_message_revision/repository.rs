@@ -0,0 +1,14 @@
+use super::entity::TopicMessageRevision;
+use crate::application::pagination::Page;
+use crate::application::topic_message::request::RequestGetMessageRevisions;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+/// Read side only: revisions are written together with the edit they record,
+/// by `TopicMessageRepository::update_topic_message`.
+pub trait TopicMessageRevisionRepository: Clone + Send + Sync + 'static {
+    fn find_revisions_by_partition_key(
+        &self,
+        query: &RequestGetMessageRevisions,
+    ) -> impl Future<Output=AppResult<Page<TopicMessageRevision>>> + Send;
+}
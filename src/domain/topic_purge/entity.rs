@@ -0,0 +1,20 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// A deleted topic whose messages, memberships and the rest are still being
+/// removed. It outlives a restart, so the purge can be resumed.
+#[charybdis_model(
+    table_name = uptop.topic_purges,
+    partition_keys = [topic_id],
+    clustering_keys = [],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct TopicPurge {
+    pub topic_id: Timeuuid,
+    pub requested_at: Timestamp,
+}
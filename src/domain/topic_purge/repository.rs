@@ -0,0 +1,13 @@
+use super::entity::TopicPurge;
+use charybdis::types::Timeuuid;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait TopicPurgeRepository: Clone + Send + Sync + 'static {
+    /// Every purge not finished yet. Finished ones are removed, so there are few.
+    fn find_topic_purges(&self) -> impl Future<Output=AppResult<Vec<TopicPurge>>> + Send;
+
+    fn save_topic_purge(&self, topic_purge: &TopicPurge) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn remove_topic_purge(&self, topic_id: Timeuuid) -> impl Future<Output=AppResult<()>> + Send;
+}
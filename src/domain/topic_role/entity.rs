@@ -0,0 +1,108 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Text, Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// A role granted to one user in one topic. It takes precedence over the
+/// role derived from `topic_owners`, `topic_admins` and membership, but only
+/// grants anything while the user is in the topic. Bans hold regardless.
+#[charybdis_model(
+    table_name = uptop.topic_roles,
+    partition_keys = [topic_id],
+    clustering_keys = [username],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct TopicRole {
+    pub topic_id: Timeuuid,
+    pub username: Text,
+    pub role: Text,
+    pub updated_by: Text,
+    pub updated_at: Timestamp,
+}
+
+/// Roles in ascending rank, so they compare the way they outrank each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Banned,
+    ReadOnly,
+    Member,
+    Admin,
+    Owner,
+}
+
+/// What a role allows in a topic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Capability {
+    /// Read messages and follow the topic.
+    Read,
+    Post,
+    EditOwn,
+    EditAny,
+    Delete,
+    Pin,
+    /// Invite users, and remove members ranked below the caller.
+    Invite,
+    /// Change the topic itself and the roles of its users.
+    ManageSettings,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Banned => "banned",
+            Role::ReadOnly => "read_only",
+            Role::Member => "member",
+            Role::Admin => "admin",
+            Role::Owner => "owner",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Role> {
+        match value {
+            "banned" => Some(Role::Banned),
+            "read_only" => Some(Role::ReadOnly),
+            "member" => Some(Role::Member),
+            "admin" => Some(Role::Admin),
+            "owner" => Some(Role::Owner),
+            _ => None,
+        }
+    }
+
+    pub fn can(&self, capability: Capability) -> bool {
+        match self {
+            Role::Owner => true,
+            Role::Admin => true,
+            Role::Member => matches!(
+                capability,
+                Capability::Read | Capability::Post | Capability::EditOwn
+            ),
+            Role::ReadOnly => capability == Capability::Read,
+            Role::Banned => false,
+        }
+    }
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Read => "read",
+            Capability::Post => "post",
+            Capability::EditOwn => "edit_own",
+            Capability::EditAny => "edit_any",
+            Capability::Delete => "delete",
+            Capability::Pin => "pin",
+            Capability::Invite => "invite",
+            Capability::ManageSettings => "manage_settings",
+        }
+    }
+}
+
+impl TopicRole {
+    /// Unknown values, e.g. written by a newer build, grant nothing.
+    pub fn role(&self) -> Role {
+        Role::parse(&self.role).unwrap_or(Role::Banned)
+    }
+}
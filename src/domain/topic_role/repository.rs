@@ -0,0 +1,35 @@
+use super::entity::TopicRole;
+use crate::application::pagination::Page;
+use crate::application::topic_role::request::RequestGetRolesByTopicId;
+use crate::application::topic_user::request::RequestGetTopicMember;
+use charybdis::types::Timeuuid;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait TopicRoleRepository: Clone + Send + Sync + 'static {
+    fn find_topic_role(
+        &self,
+        query: &RequestGetTopicMember,
+    ) -> impl Future<Output=AppResult<Option<TopicRole>>> + Send;
+
+    fn find_topic_roles_by_partition_key(
+        &self,
+        query: &RequestGetRolesByTopicId,
+    ) -> impl Future<Output=AppResult<Page<TopicRole>>> + Send;
+
+    fn save_topic_role(
+        &self,
+        topic_role: &TopicRole,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    fn remove_topic_role(
+        &self,
+        query: &RequestGetTopicMember,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Drops every override in the topic, bans included.
+    fn remove_topic_roles(
+        &self,
+        topic_id: Timeuuid,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
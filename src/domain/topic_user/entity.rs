@@ -4,14 +4,16 @@ use charybdis::{
 };
 use serde::{Deserialize, Serialize};
 
+/// One member of a topic. Clustered by join time, then username, so members
+/// who join in the same millisecond do not overwrite each other.
 #[charybdis_model(
-    table_name = uptop.topic_user,
+    table_name = uptop.topic_members,
     partition_keys = [topic_id],
-    clustering_keys = [created_at],
+    clustering_keys = [created_at, username],
     global_secondary_indexes = [],
 
 )]
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct TopicUser {
     pub topic_id: Timeuuid,
     pub username: Text,
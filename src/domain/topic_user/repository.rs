@@ -1,16 +1,29 @@
 use super::entity::TopicUser;
+use crate::domain::topic_invite::entity::TopicInvite;
+use crate::domain::user_topic::entity::UserTopic;
+use crate::application::pagination::Page;
 use std::future::Future;
 use uptop_core::common::result::AppResult;
-use crate::application::topic_user::request::{RequestGetUsersByTopicId, RequestUpdateTopicUser};
+use crate::application::topic_user::request::RequestGetUsersByTopicId;
 
 pub trait TopicUserRepository: Clone + Send + Sync + 'static {
     fn find_topic_users_by_partition_key(
         &self,
         query: &RequestGetUsersByTopicId,
-    ) -> impl Future<Output=AppResult<Vec<TopicUser>>> + Send;
+    ) -> impl Future<Output=AppResult<Page<TopicUser>>> + Send;
 
-    fn update_topic_users(
+    /// Writes both membership views, and the answered invite if any, in one logged batch.
+    fn add_topic_member(
         &self,
-        topic_message: &RequestUpdateTopicUser,
-    ) -> impl Future<Output=AppResult<TopicUser>> + Send;
+        topic_user: &TopicUser,
+        user_topic: &UserTopic,
+        accepted_invite: Option<&TopicInvite>,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Deletes both membership views in one logged batch. The `user_topic`
+    /// row carries the join time that clusters the `topic_user` row.
+    fn remove_topic_member(
+        &self,
+        user_topic: &UserTopic,
+    ) -> impl Future<Output=AppResult<()>> + Send;
 }
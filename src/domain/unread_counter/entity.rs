@@ -0,0 +1,13 @@
+use charybdis::types::Timeuuid;
+use serde::{Deserialize, Serialize};
+
+/// Unread messages of one user in one topic, a row of the
+/// `uptop.unread_counters` counter table. Counters can only be incremented,
+/// never inserted or set, so the row is written with plain updates rather
+/// than through a model.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct UnreadCounter {
+    pub user_id: Timeuuid,
+    pub topic_id: Timeuuid,
+    pub unread: i64,
+}
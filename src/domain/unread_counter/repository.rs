@@ -0,0 +1,32 @@
+use super::entity::UnreadCounter;
+use crate::application::latest_message::request::{RequestGetUnreadCounters, RequestGetUnreadSummary};
+use crate::application::pagination::Page;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait UnreadCounterRepository: Clone + Send + Sync + 'static {
+    /// Every counter of the user.
+    fn find_unread_counters_by_user_id(
+        &self,
+        query: &RequestGetUnreadSummary,
+    ) -> impl Future<Output=AppResult<Vec<UnreadCounter>>> + Send;
+
+    /// Walks the counters of all users, for reconciliation.
+    fn find_unread_counters(
+        &self,
+        query: &RequestGetUnreadCounters,
+    ) -> impl Future<Output=AppResult<Page<UnreadCounter>>> + Send;
+
+    /// Adds each counter's `unread` to the stored count.
+    fn increment_unread_counters(
+        &self,
+        increments: &[UnreadCounter],
+    ) -> impl Future<Output=AppResult<()>> + Send;
+
+    /// Moves the stored count to `unread`, by adding the difference to what
+    /// is stored so increments racing with it are kept.
+    fn reset_unread_counter(
+        &self,
+        counter: &UnreadCounter,
+    ) -> impl Future<Output=AppResult<()>> + Send;
+}
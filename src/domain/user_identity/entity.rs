@@ -0,0 +1,22 @@
+use charybdis::{
+    macros::charybdis_model,
+    types::{Text, Timestamp, Timeuuid},
+};
+use serde::{Deserialize, Serialize};
+
+/// The user id a username was last seen with in a verified token. Users are
+/// named by username, so this is how the user id of someone other than the
+/// caller is found.
+#[charybdis_model(
+    table_name = uptop.user_identities,
+    partition_keys = [username],
+    clustering_keys = [],
+    global_secondary_indexes = [],
+
+)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct UserIdentity {
+    pub username: Text,
+    pub user_id: Timeuuid,
+    pub updated_at: Timestamp,
+}
@@ -0,0 +1,2 @@
+pub(crate) mod entity;
+pub mod repository;
@@ -0,0 +1,13 @@
+use super::entity::UserIdentity;
+use crate::application::user_identity::request::RequestGetUserIdentity;
+use std::future::Future;
+use uptop_core::common::result::AppResult;
+
+pub trait UserIdentityRepository: Clone + Send + Sync + 'static {
+    fn find_user_identity(
+        &self,
+        query: &RequestGetUserIdentity,
+    ) -> impl Future<Output=AppResult<Option<UserIdentity>>> + Send;
+
+    fn save_user_identity(&self, identity: &UserIdentity) -> impl Future<Output=AppResult<()>> + Send;
+}
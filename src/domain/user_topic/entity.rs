@@ -2,18 +2,33 @@ use charybdis::{
     macros::charybdis_model,
     types::{Text, Timestamp, Timeuuid},
 };
+use crate::domain::topic::entity::{TopicState, TOPIC_KIND_GROUP};
 use serde::{Deserialize, Serialize};
 
 #[charybdis_model(
     table_name = uptop.user_topic,
-    partition_keys = [topic_id],
-    clustering_keys = [created_at],
+    partition_keys = [username],
+    clustering_keys = [topic_id],
     global_secondary_indexes = [],
 
 )]
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct UserTopic {
     pub topic_id: Timeuuid,
     pub username: Text,
     pub created_at: Timestamp,
+    /// Copied from the topic so listings can tell direct conversations apart.
+    pub topic_kind: Option<Text>,
+    /// Copied from the topic too, so archived topics can be left out of listings.
+    pub topic_state: Option<Text>,
+}
+
+impl UserTopic {
+    pub fn kind(&self) -> &str {
+        self.topic_kind.as_deref().unwrap_or(TOPIC_KIND_GROUP)
+    }
+
+    pub fn state(&self) -> TopicState {
+        TopicState::from_stored(self.topic_state.as_deref())
+    }
 }
\ No newline at end of file
@@ -1,16 +1,18 @@
 use super::entity::UserTopic;
+use crate::application::pagination::Page;
 use std::future::Future;
 use uptop_core::common::result::AppResult;
-use crate::application::user_topic::request::{RequestGetTopicsByUsername, RequestUpdateUserTopic};
+use crate::application::topic_user::request::RequestGetTopicMember;
+use crate::application::user_topic::request::RequestGetTopicsByUsername;
 
 pub trait UserTopicRepository: Clone + Send + Sync + 'static {
     fn find_user_topics_by_partition_key(
         &self,
         query: &RequestGetTopicsByUsername,
-    ) -> impl Future<Output=AppResult<Vec<UserTopic>>> + Send;
+    ) -> impl Future<Output=AppResult<Page<UserTopic>>> + Send;
 
-    fn update_user_topics(
+    fn find_user_topic(
         &self,
-        topic_message: &RequestUpdateUserTopic,
-    ) -> impl Future<Output=AppResult<UserTopic>> + Send;
+        query: &RequestGetTopicMember,
+    ) -> impl Future<Output=AppResult<Option<UserTopic>>> + Send;
 }
@@ -0,0 +1,227 @@
+use crate::application::error::ApplicationError;
+use crate::application::pagination::{decode_page_token, encode_page_token, page_size_or_default, Page};
+use crate::infrastructure::memory::direct_conversation_repository::DirectConversationMemoryRepo;
+use crate::infrastructure::memory::group_conversation_repository::GroupConversationMemoryRepo;
+use crate::infrastructure::memory::hidden_message_repository::HiddenMessageMemoryRepo;
+use crate::infrastructure::memory::latest_message_repository::LatestMessageMemoryRepo;
+use crate::infrastructure::memory::message_reaction_repository::MessageReactionMemoryRepo;
+use crate::infrastructure::memory::notification_repository::NotificationMemoryRepo;
+use crate::infrastructure::memory::pinned_message_repository::PinnedMessageMemoryRepo;
+use crate::infrastructure::memory::read_cursor_repository::ReadCursorMemoryRepo;
+use crate::infrastructure::memory::topic_invite_repository::TopicInviteMemoryRepo;
+use crate::infrastructure::memory::topic_role_repository::TopicRoleMemoryRepo;
+use crate::infrastructure::memory::thread_message_repository::ThreadMessageMemoryRepo;
+use crate::infrastructure::memory::topic_message_repository::TopicMessageMemoryRepo;
+use crate::infrastructure::memory::topic_message_revision_repository::TopicMessageRevisionMemoryRepo;
+use crate::infrastructure::memory::topic_purge_repository::TopicPurgeMemoryRepo;
+use crate::infrastructure::memory::topic_repository::TopicMemoryRepo;
+use crate::infrastructure::memory::topic_user_repository::TopicUserMemoryRepo;
+use crate::infrastructure::memory::unread_counter_repository::UnreadCounterMemoryRepo;
+use crate::infrastructure::memory::user_identity_repository::UserIdentityMemoryRepo;
+use crate::infrastructure::memory::user_topic_repository::UserTopicMemoryRepo;
+use anyhow::bail;
+use std::collections::BTreeMap;
+use std::sync::{Arc, PoisonError, RwLock};
+use uptop_core::common::result::AppResult;
+
+pub mod topic_repository;
+pub mod latest_message_repository;
+pub mod topic_message_repository;
+pub mod topic_user_repository;
+pub mod user_topic_repository;
+pub mod notification_repository;
+pub mod topic_invite_repository;
+pub mod topic_role_repository;
+pub mod topic_message_revision_repository;
+pub mod hidden_message_repository;
+pub mod thread_message_repository;
+pub mod message_reaction_repository;
+pub mod read_cursor_repository;
+pub mod unread_counter_repository;
+pub mod direct_conversation_repository;
+pub mod group_conversation_repository;
+pub mod pinned_message_repository;
+pub mod user_identity_repository;
+pub mod topic_purge_repository;
+
+/// Repositories keeping their rows in process, for running the application
+/// layer without a cluster. Each one mirrors the keys and ordering of its table.
+#[derive(Clone, Debug)]
+pub struct MemoryRepositories {
+    pub topic: TopicMemoryRepo,
+    pub topic_message: TopicMessageMemoryRepo,
+    pub latest_message: LatestMessageMemoryRepo,
+    pub topic_user: TopicUserMemoryRepo,
+    pub user_topic: UserTopicMemoryRepo,
+    pub notification: NotificationMemoryRepo,
+    pub topic_invite: TopicInviteMemoryRepo,
+    pub topic_role: TopicRoleMemoryRepo,
+    pub topic_message_revision: TopicMessageRevisionMemoryRepo,
+    pub hidden_message: HiddenMessageMemoryRepo,
+    pub thread_message: ThreadMessageMemoryRepo,
+    pub message_reaction: MessageReactionMemoryRepo,
+    pub read_cursor: ReadCursorMemoryRepo,
+    pub unread_counter: UnreadCounterMemoryRepo,
+    pub direct_conversation: DirectConversationMemoryRepo,
+    pub group_conversation: GroupConversationMemoryRepo,
+    pub pinned_message: PinnedMessageMemoryRepo,
+    pub user_identity: UserIdentityMemoryRepo,
+    pub topic_purge: TopicPurgeMemoryRepo,
+}
+
+impl MemoryRepositories {
+    pub fn new() -> Self {
+        let user_topic = UserTopicMemoryRepo::new();
+        let topic_invite = TopicInviteMemoryRepo::new();
+        let topic_message_revision = TopicMessageRevisionMemoryRepo::new();
+        let topic_message = TopicMessageMemoryRepo::with_views(&topic_message_revision);
+        Self {
+            topic: TopicMemoryRepo::new(),
+            thread_message: ThreadMessageMemoryRepo::with_views(&topic_message),
+            topic_message,
+            latest_message: LatestMessageMemoryRepo::new(),
+            topic_user: TopicUserMemoryRepo::with_views(&user_topic, &topic_invite),
+            user_topic,
+            notification: NotificationMemoryRepo::new(),
+            topic_invite,
+            topic_role: TopicRoleMemoryRepo::new(),
+            topic_message_revision,
+            hidden_message: HiddenMessageMemoryRepo::new(),
+            message_reaction: MessageReactionMemoryRepo::new(),
+            read_cursor: ReadCursorMemoryRepo::new(),
+            unread_counter: UnreadCounterMemoryRepo::new(),
+            direct_conversation: DirectConversationMemoryRepo::new(),
+            group_conversation: GroupConversationMemoryRepo::new(),
+            pinned_message: PinnedMessageMemoryRepo::new(),
+            user_identity: UserIdentityMemoryRepo::new(),
+            topic_purge: TopicPurgeMemoryRepo::new(),
+        }
+    }
+}
+
+impl Default for MemoryRepositories {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A table held in memory: partitions by partition key, rows within a
+/// partition sorted ascending by clustering key. Clones share the same rows.
+#[derive(Debug)]
+pub(crate) struct Table<P, C, T> {
+    partitions: Arc<RwLock<BTreeMap<P, BTreeMap<C, T>>>>,
+}
+
+impl<P, C, T> Clone for Table<P, C, T> {
+    fn clone(&self) -> Self {
+        Self {
+            partitions: Arc::clone(&self.partitions),
+        }
+    }
+}
+
+impl<P, C, T> Default for Table<P, C, T> {
+    fn default() -> Self {
+        Self {
+            partitions: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+}
+
+impl<P: Ord, C: Ord, T: Clone> Table<P, C, T> {
+    /// Inserts or overwrites the row, like an INSERT or UPDATE on the table.
+    pub(crate) fn upsert(&self, partition_key: P, clustering_key: C, row: T) {
+        let mut partitions = self.partitions.write().unwrap_or_else(PoisonError::into_inner);
+        partitions.entry(partition_key).or_default().insert(clustering_key, row);
+    }
+
+    /// Overwrites an existing row when `condition` holds for it, like an
+    /// `UPDATE ... IF`. Returns whether the row was written.
+    pub(crate) fn update_if<F>(&self, partition_key: &P, clustering_key: &C, condition: F, row: T) -> bool
+    where
+        F: FnOnce(&T) -> bool,
+    {
+        let mut partitions = self.partitions.write().unwrap_or_else(PoisonError::into_inner);
+        match partitions
+            .get_mut(partition_key)
+            .and_then(|partition| partition.get_mut(clustering_key))
+        {
+            Some(stored) if condition(stored) => {
+                *stored = row;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn remove(&self, partition_key: &P, clustering_key: &C) -> Option<T> {
+        let mut partitions = self.partitions.write().unwrap_or_else(PoisonError::into_inner);
+        partitions
+            .get_mut(partition_key)
+            .and_then(|partition| partition.remove(clustering_key))
+    }
+
+    pub(crate) fn remove_partition(&self, partition_key: &P) {
+        let mut partitions = self.partitions.write().unwrap_or_else(PoisonError::into_inner);
+        partitions.remove(partition_key);
+    }
+
+    pub(crate) fn get(&self, partition_key: &P, clustering_key: &C) -> Option<T> {
+        let partitions = self.partitions.read().unwrap_or_else(PoisonError::into_inner);
+        partitions
+            .get(partition_key)
+            .and_then(|partition| partition.get(clustering_key))
+            .cloned()
+    }
+
+    /// Rows of one partition in ascending clustering order.
+    pub(crate) fn partition(&self, partition_key: &P) -> Vec<T> {
+        let partitions = self.partitions.read().unwrap_or_else(PoisonError::into_inner);
+        partitions
+            .get(partition_key)
+            .map(|partition| partition.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Every row matching `filter`, the in-memory counterpart of a secondary index lookup.
+    pub(crate) fn scan<F>(&self, mut filter: F) -> Vec<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let partitions = self.partitions.read().unwrap_or_else(PoisonError::into_inner);
+        partitions
+            .values()
+            .flat_map(|partition| partition.values())
+            .filter(|row| filter(row))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Pages already ordered rows. The page token is the offset of the next row,
+/// encoded the same way as the driver paging state so clients cannot tell the two apart.
+pub(crate) fn page<T>(
+    rows: Vec<T>,
+    page_size: Option<i32>,
+    page_token: &Option<String>,
+) -> AppResult<Page<T>> {
+    let offset = match decode_page_token(page_token)? {
+        Some(raw_bytes) => match <[u8; 8]>::try_from(raw_bytes.as_slice()) {
+            Ok(offset) => u64::from_be_bytes(offset) as usize,
+            Err(_) => bail!(ApplicationError::invalid_field("page_token", "is not a valid page token")),
+        },
+        None => 0,
+    };
+    let page_size = page_size_or_default(page_size).max(1) as usize;
+
+    let end = offset.saturating_add(page_size);
+    let next_page_token = match end < rows.len() {
+        true => Some(encode_page_token(&(end as u64).to_be_bytes())),
+        false => None,
+    };
+
+    Ok(Page {
+        items: rows.into_iter().skip(offset).take(page_size).collect(),
+        next_page_token,
+    })
+}
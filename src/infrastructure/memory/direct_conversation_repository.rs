@@ -0,0 +1,46 @@
+use crate::application::topic::request::RequestGetDirectConversation;
+use crate::domain::direct_conversation::{entity::DirectConversation, repository::DirectConversationRepository};
+use charybdis::types::Text;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use uptop_core::common::result::AppResult;
+
+/// Keyed by the pair alone, so it is a map rather than a [`Table`](super::Table);
+/// the lock stands in for the lightweight transaction.
+#[derive(Clone, Debug, Default)]
+pub struct DirectConversationMemoryRepo {
+    direct_conversations: Arc<Mutex<HashMap<(Text, Text), DirectConversation>>>,
+}
+
+impl DirectConversationMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DirectConversationRepository for DirectConversationMemoryRepo {
+    async fn find_direct_conversation(
+        &self,
+        query: &RequestGetDirectConversation,
+    ) -> AppResult<Option<DirectConversation>> {
+        let direct_conversations = self
+            .direct_conversations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        Ok(direct_conversations
+            .get(&(query.user_a.to_owned(), query.user_b.to_owned()))
+            .cloned())
+    }
+
+    async fn create_direct_conversation(&self, conversation: &DirectConversation) -> AppResult<DirectConversation> {
+        let mut direct_conversations = self
+            .direct_conversations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let key = (conversation.user_a.to_owned(), conversation.user_b.to_owned());
+        Ok(direct_conversations
+            .entry(key)
+            .or_insert_with(|| conversation.clone())
+            .clone())
+    }
+}
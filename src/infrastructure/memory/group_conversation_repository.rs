@@ -0,0 +1,64 @@
+use crate::application::topic::request::RequestGetGroupConversation;
+use crate::domain::group_conversation::{entity::GroupConversation, repository::GroupConversationRepository};
+use anyhow::anyhow;
+use charybdis::types::Text;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use uptop_core::common::result::AppResult;
+
+/// A map under a lock, like the direct conversation repository, so the
+/// conditional writes are atomic.
+#[derive(Clone, Debug, Default)]
+pub struct GroupConversationMemoryRepo {
+    group_conversations: Arc<Mutex<HashMap<Text, GroupConversation>>>,
+}
+
+impl GroupConversationMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GroupConversationRepository for GroupConversationMemoryRepo {
+    async fn find_group_conversation(
+        &self,
+        query: &RequestGetGroupConversation,
+    ) -> AppResult<Option<GroupConversation>> {
+        let group_conversations = self
+            .group_conversations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        Ok(group_conversations.get(&query.member_key).cloned())
+    }
+
+    async fn create_group_conversation(&self, conversation: &GroupConversation) -> AppResult<GroupConversation> {
+        let mut group_conversations = self
+            .group_conversations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        Ok(group_conversations
+            .entry(conversation.member_key.to_owned())
+            .or_insert_with(|| conversation.clone())
+            .clone())
+    }
+
+    async fn replace_group_conversation(
+        &self,
+        previous: &GroupConversation,
+        conversation: &GroupConversation,
+    ) -> AppResult<GroupConversation> {
+        let mut group_conversations = self
+            .group_conversations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        match group_conversations.get_mut(&conversation.member_key) {
+            Some(stored) => {
+                if stored.topic_id == previous.topic_id {
+                    *stored = conversation.clone();
+                }
+                Ok(stored.clone())
+            }
+            None => Err(anyhow!("group conversation was neither written nor found")),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::application::topic_message::request::RequestGetHiddenMessages;
+use crate::domain::hidden_message::{entity::HiddenMessage, repository::HiddenMessageRepository};
+use crate::infrastructure::memory::Table;
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct HiddenMessageMemoryRepo {
+    hidden_messages: Table<(Timeuuid, Timeuuid), Timeuuid, HiddenMessage>,
+}
+
+impl HiddenMessageMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl HiddenMessageRepository for HiddenMessageMemoryRepo {
+    async fn find_hidden_messages(&self, query: &RequestGetHiddenMessages) -> AppResult<Vec<HiddenMessage>> {
+        Ok(self.hidden_messages.partition(&(query.user_id, query.topic_id)))
+    }
+
+    async fn save_hidden_message(&self, hidden_message: &HiddenMessage) -> AppResult<()> {
+        self.hidden_messages.upsert(
+            (hidden_message.user_id, hidden_message.topic_id),
+            hidden_message.message_id,
+            hidden_message.clone(),
+        );
+        Ok(())
+    }
+
+    async fn remove_hidden_messages(&self, query: &RequestGetHiddenMessages) -> AppResult<()> {
+        self.hidden_messages.remove_partition(&(query.user_id, query.topic_id));
+        Ok(())
+    }
+}
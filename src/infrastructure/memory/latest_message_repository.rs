@@ -0,0 +1,66 @@
+use crate::application::latest_message::request::{
+    RequestGetLatestMessage, RequestGetLatestMessagesByUserId, RequestUpdateLatestMessage,
+};
+use crate::application::pagination::Page;
+use crate::domain::latest_message::{entity::LatestMessage, repository::LatestMessageRepository};
+use crate::infrastructure::memory::{page, Table};
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct LatestMessageMemoryRepo {
+    latest_messages: Table<Timeuuid, Timeuuid, LatestMessage>,
+}
+
+impl LatestMessageMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LatestMessageRepository for LatestMessageMemoryRepo {
+    async fn find_latest_message_by_partition_key(
+        &self,
+        query: &RequestGetLatestMessagesByUserId,
+    ) -> AppResult<Page<LatestMessage>> {
+        let latest_messages = self.latest_messages.partition(&query.user_id);
+        page(latest_messages, query.page_size, &query.page_token)
+    }
+
+    async fn find_latest_message(&self, query: &RequestGetLatestMessage) -> AppResult<Option<LatestMessage>> {
+        Ok(self.latest_messages.get(&query.user_id, &query.topic_id))
+    }
+
+    async fn update_latest_message(&self, latest_message: &RequestUpdateLatestMessage) -> AppResult<LatestMessage> {
+        // An UPDATE writes every regular column, so unset ones are reset like on the cluster.
+        let result = LatestMessage {
+            user_id: latest_message.user_id,
+            latest_message_id: latest_message.latest_message_id,
+            latest_message_content: latest_message.latest_message_content.to_owned(),
+            topic_id: latest_message.topic_id,
+            ..Default::default()
+        };
+
+        self.latest_messages
+            .upsert(result.user_id, result.topic_id, result.clone());
+        Ok(result)
+    }
+
+    async fn upsert_latest_messages(&self, latest_messages: &[LatestMessage]) -> AppResult<()> {
+        for latest_message in latest_messages {
+            self.latest_messages.upsert(
+                latest_message.user_id,
+                latest_message.topic_id,
+                latest_message.clone(),
+            );
+        }
+        Ok(())
+    }
+
+    async fn remove_latest_messages(&self, keys: &[RequestGetLatestMessage]) -> AppResult<()> {
+        for key in keys {
+            self.latest_messages.remove(&key.user_id, &key.topic_id);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::application::topic_message::request::{RequestGetMessageReactions, RequestGetTopicMessage};
+use crate::domain::message_reaction::{entity::MessageReaction, repository::MessageReactionRepository};
+use crate::infrastructure::memory::Table;
+use charybdis::types::{Text, Timeuuid};
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct MessageReactionMemoryRepo {
+    message_reactions: Table<Timeuuid, (Timeuuid, Text, Timeuuid), MessageReaction>,
+}
+
+impl MessageReactionMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MessageReactionRepository for MessageReactionMemoryRepo {
+    async fn find_reactions_by_message_ids(
+        &self,
+        query: &RequestGetMessageReactions,
+    ) -> AppResult<Vec<MessageReaction>> {
+        let mut reactions = self.message_reactions.partition(&query.topic_id);
+        reactions.retain(|reaction| query.message_ids.contains(&reaction.message_id));
+        Ok(reactions)
+    }
+
+    async fn save_reaction(&self, reaction: &MessageReaction) -> AppResult<()> {
+        self.message_reactions.upsert(
+            reaction.topic_id,
+            (reaction.message_id, reaction.emoji.to_owned(), reaction.user_id),
+            reaction.clone(),
+        );
+        Ok(())
+    }
+
+    async fn remove_reaction(&self, reaction: &MessageReaction) -> AppResult<()> {
+        self.message_reactions.remove(
+            &reaction.topic_id,
+            &(reaction.message_id, reaction.emoji.to_owned(), reaction.user_id),
+        );
+        Ok(())
+    }
+
+    async fn remove_message_reactions(&self, query: &RequestGetTopicMessage) -> AppResult<()> {
+        for reaction in self.message_reactions.partition(&query.topic_id) {
+            if reaction.message_id == query.message_id {
+                self.remove_reaction(&reaction).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn remove_topic_reactions(&self, topic_id: Timeuuid) -> AppResult<()> {
+        self.message_reactions.remove_partition(&topic_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use crate::application::notification::request::{
+    RequestGetNotificationByUsername, RequestRemoveTopicNotifications,
+};
+use crate::application::pagination::Page;
+use crate::domain::notification::{entity::Notification, repository::NotificationRepository};
+use crate::infrastructure::memory::{page, Table};
+use charybdis::types::{Text, Timestamp};
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct NotificationMemoryRepo {
+    notifications: Table<Text, Timestamp, Notification>,
+}
+
+impl NotificationMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NotificationRepository for NotificationMemoryRepo {
+    async fn find_notifications_by_partition_key(
+        &self,
+        query: &RequestGetNotificationByUsername,
+    ) -> AppResult<Page<Notification>> {
+        let notifications = self.notifications.partition(&query.username);
+        page(notifications, query.page_size, &query.page_token)
+    }
+
+    async fn create_notifications(&self, notifications: &[Notification]) -> AppResult<()> {
+        for notification in notifications {
+            self.notifications.upsert(
+                notification.username.to_owned(),
+                notification.created_at,
+                notification.clone(),
+            );
+        }
+        Ok(())
+    }
+
+    async fn remove_topic_notifications(&self, query: &RequestRemoveTopicNotifications) -> AppResult<()> {
+        for notification in self.notifications.partition(&query.username) {
+            if notification.topic_id == query.topic_id {
+                self.notifications.remove(&query.username, &notification.created_at);
+            }
+        }
+        Ok(())
+    }
+}
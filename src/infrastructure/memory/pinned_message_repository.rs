@@ -0,0 +1,38 @@
+use crate::application::topic_message::request::{RequestGetPinnedMessages, RequestGetTopicMessage};
+use crate::domain::pinned_message::{entity::PinnedMessage, repository::PinnedMessageRepository};
+use crate::infrastructure::memory::Table;
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct PinnedMessageMemoryRepo {
+    pinned_messages: Table<Timeuuid, Timeuuid, PinnedMessage>,
+}
+
+impl PinnedMessageMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PinnedMessageRepository for PinnedMessageMemoryRepo {
+    async fn find_pinned_messages(&self, query: &RequestGetPinnedMessages) -> AppResult<Vec<PinnedMessage>> {
+        Ok(self.pinned_messages.partition(&query.topic_id))
+    }
+
+    async fn save_pinned_message(&self, pinned_message: &PinnedMessage) -> AppResult<()> {
+        self.pinned_messages
+            .upsert(pinned_message.topic_id, pinned_message.message_id, pinned_message.clone());
+        Ok(())
+    }
+
+    async fn remove_pinned_message(&self, query: &RequestGetTopicMessage) -> AppResult<()> {
+        self.pinned_messages.remove(&query.topic_id, &query.message_id);
+        Ok(())
+    }
+
+    async fn remove_topic_pins(&self, topic_id: Timeuuid) -> AppResult<()> {
+        self.pinned_messages.remove_partition(&topic_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use crate::application::topic_message::request::{RequestGetReadCursor, RequestGetReadCursorsByTopicId};
+use crate::domain::read_cursor::{entity::ReadCursor, repository::ReadCursorRepository};
+use crate::infrastructure::memory::Table;
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct ReadCursorMemoryRepo {
+    read_cursors: Table<Timeuuid, Timeuuid, ReadCursor>,
+}
+
+impl ReadCursorMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ReadCursorRepository for ReadCursorMemoryRepo {
+    async fn find_read_cursor(&self, query: &RequestGetReadCursor) -> AppResult<Option<ReadCursor>> {
+        Ok(self.read_cursors.get(&query.topic_id, &query.user_id))
+    }
+
+    async fn find_read_cursors_by_topic_id(
+        &self,
+        query: &RequestGetReadCursorsByTopicId,
+    ) -> AppResult<Vec<ReadCursor>> {
+        Ok(self.read_cursors.partition(&query.topic_id))
+    }
+
+    async fn save_read_cursor(&self, read_cursor: &ReadCursor) -> AppResult<()> {
+        self.read_cursors
+            .upsert(read_cursor.topic_id, read_cursor.user_id, read_cursor.clone());
+        Ok(())
+    }
+
+    async fn remove_topic_read_cursors(&self, topic_id: Timeuuid) -> AppResult<()> {
+        self.read_cursors.remove_partition(&topic_id);
+        Ok(())
+    }
+}
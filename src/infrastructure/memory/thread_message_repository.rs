@@ -0,0 +1,94 @@
+use crate::application::pagination::Page;
+use crate::application::topic_message::request::RequestGetThreadMessages;
+use crate::domain::thread_message::{entity::ThreadMessage, repository::ThreadMessageRepository};
+use crate::domain::topic_message::entity::TopicMessage;
+use crate::infrastructure::memory::topic_message_repository::TopicMessageMemoryRepo;
+use crate::infrastructure::memory::{page, Table};
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+/// Replies and the reply stats of their parents live with the topic
+/// messages, so this repository holds the same tables as the topic message repository.
+#[derive(Clone, Debug, Default)]
+pub struct ThreadMessageMemoryRepo {
+    thread_messages: Table<(Timeuuid, Timeuuid), Timeuuid, ThreadMessage>,
+    topic_messages: Table<Timeuuid, Timeuuid, TopicMessage>,
+    topic_replies: Table<Timeuuid, Timeuuid, TopicMessage>,
+}
+
+impl ThreadMessageMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_views(topic_message: &TopicMessageMemoryRepo) -> Self {
+        Self {
+            thread_messages: Table::default(),
+            topic_messages: topic_message.topic_messages.clone(),
+            topic_replies: topic_message.topic_replies.clone(),
+        }
+    }
+}
+
+impl ThreadMessageRepository for ThreadMessageMemoryRepo {
+    async fn create_thread_reply(&self, reply: &TopicMessage) -> AppResult<()> {
+        let parent_id = reply.parent_id.unwrap_or_default();
+        self.topic_replies
+            .upsert(reply.topic_id, reply.message_id, reply.clone());
+        self.thread_messages.upsert(
+            (reply.topic_id, parent_id),
+            reply.message_id,
+            ThreadMessage {
+                topic_id: reply.topic_id,
+                parent_id,
+                message_id: reply.message_id,
+                created_at: reply.created_at,
+            },
+        );
+        Ok(())
+    }
+
+    async fn remove_thread_reply(&self, reply: &TopicMessage) -> AppResult<()> {
+        let parent_id = reply.parent_id.unwrap_or_default();
+        self.thread_messages
+            .remove(&(reply.topic_id, parent_id), &reply.message_id);
+        Ok(())
+    }
+
+    async fn find_thread_messages_by_partition_key(
+        &self,
+        query: &RequestGetThreadMessages,
+    ) -> AppResult<Page<TopicMessage>> {
+        let entries = self.thread_messages.partition(&(query.topic_id, query.parent_id));
+        let entries = page(entries, query.page_size, &query.page_token)?;
+        Ok(Page {
+            items: entries
+                .items
+                .iter()
+                .filter_map(|entry| self.topic_replies.get(&entry.topic_id, &entry.message_id))
+                .collect(),
+            next_page_token: entries.next_page_token,
+        })
+    }
+
+    async fn refresh_reply_stats(&self, parent: &TopicMessage) -> AppResult<TopicMessage> {
+        let entries = self.thread_messages.partition(&(parent.topic_id, parent.message_id));
+        let mut updated = match self.topic_messages.get(&parent.topic_id, &parent.message_id) {
+            Some(updated) => updated,
+            None => parent.clone(),
+        };
+        updated.reply_count = Some(entries.len() as i32);
+        updated.last_reply_at = entries.iter().map(|entry| entry.created_at).max();
+        self.topic_messages
+            .upsert(updated.topic_id, updated.message_id, updated.clone());
+        Ok(updated)
+    }
+
+    async fn remove_topic_threads(&self, topic_id: Timeuuid) -> AppResult<()> {
+        for reply in self.topic_replies.partition(&topic_id) {
+            self.thread_messages
+                .remove_partition(&(topic_id, reply.parent_id.unwrap_or_default()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use crate::application::pagination::Page;
+use crate::application::topic_user::request::{RequestGetInvitesByUsername, RequestGetTopicMember};
+use crate::domain::topic_invite::{entity::TopicInvite, repository::TopicInviteRepository};
+use crate::infrastructure::memory::{page, Table};
+use charybdis::types::{Text, Timeuuid};
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct TopicInviteMemoryRepo {
+    pub(super) topic_invites: Table<Text, Timeuuid, TopicInvite>,
+}
+
+impl TopicInviteMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TopicInviteRepository for TopicInviteMemoryRepo {
+    async fn find_topic_invite(&self, query: &RequestGetTopicMember) -> AppResult<Option<TopicInvite>> {
+        Ok(self.topic_invites.get(&query.username, &query.topic_id))
+    }
+
+    async fn find_topic_invites_by_partition_key(
+        &self,
+        query: &RequestGetInvitesByUsername,
+    ) -> AppResult<Page<TopicInvite>> {
+        let topic_invites = self.topic_invites.partition(&query.username);
+        page(topic_invites, query.page_size, &query.page_token)
+    }
+
+    async fn save_topic_invite(&self, topic_invite: &TopicInvite) -> AppResult<()> {
+        self.topic_invites.upsert(
+            topic_invite.username.to_owned(),
+            topic_invite.topic_id,
+            topic_invite.clone(),
+        );
+        Ok(())
+    }
+
+    async fn remove_topic_invites(&self, topic_id: Timeuuid) -> AppResult<()> {
+        for topic_invite in self.topic_invites.scan(|topic_invite| topic_invite.topic_id == topic_id) {
+            self.topic_invites.remove(&topic_invite.username, &topic_id);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use crate::application::error::ApplicationError;
+use crate::application::pagination::Page;
+use crate::application::topic_message::request::{
+    RequestGetMessagesByTopicId, RequestGetTopicMessage, RequestUpdateTopicMessage,
+};
+use crate::domain::topic_message::{entity::TopicMessage, repository::TopicMessageRepository};
+use crate::domain::topic_message_revision::entity::TopicMessageRevision;
+use crate::infrastructure::memory::topic_message_revision_repository::TopicMessageRevisionMemoryRepo;
+use crate::infrastructure::memory::{page, Table};
+use anyhow::anyhow;
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+/// Edits record a revision, so this repository holds the same table as the
+/// revision repository. Replies are kept in their own table, apart from the timeline.
+#[derive(Clone, Debug, Default)]
+pub struct TopicMessageMemoryRepo {
+    pub(super) topic_messages: Table<Timeuuid, Timeuuid, TopicMessage>,
+    pub(super) topic_replies: Table<Timeuuid, Timeuuid, TopicMessage>,
+    revisions: Table<(Timeuuid, Timeuuid), Timeuuid, TopicMessageRevision>,
+}
+
+impl TopicMessageMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_views(revision: &TopicMessageRevisionMemoryRepo) -> Self {
+        Self {
+            topic_messages: Table::default(),
+            topic_replies: Table::default(),
+            revisions: revision.revisions.clone(),
+        }
+    }
+
+    fn table_of(&self, topic_message: &TopicMessage) -> &Table<Timeuuid, Timeuuid, TopicMessage> {
+        match topic_message.is_reply() {
+            true => &self.topic_replies,
+            false => &self.topic_messages,
+        }
+    }
+}
+
+impl TopicMessageRepository for TopicMessageMemoryRepo {
+    async fn create_topic_message<'c>(&self, topic_message: &'c TopicMessage) -> AppResult<&'c TopicMessage> {
+        self.topic_messages.upsert(
+            topic_message.topic_id,
+            topic_message.message_id,
+            topic_message.clone(),
+        );
+        Ok(topic_message)
+    }
+
+    async fn find_topic_message_by_partition_key(
+        &self,
+        query: &RequestGetMessagesByTopicId,
+    ) -> AppResult<Page<TopicMessage>> {
+        let mut topic_messages: Vec<TopicMessage> = self
+            .topic_messages
+            .partition(&query.topic_id)
+            .into_iter()
+            .filter(|item| query.before.is_none_or(|before| item.message_id < before))
+            .filter(|item| query.after.is_none_or(|after| item.message_id > after))
+            .collect();
+
+        // CLUSTERING ORDER BY (message_id DESC), except that an `after` cursor
+        // alone reads forward so the page starts right after it.
+        if query.after.is_none() || query.before.is_some() {
+            topic_messages.reverse();
+        }
+
+        page(topic_messages, query.page_size, &query.page_token)
+    }
+
+    async fn find_topic_message(&self, query: &RequestGetTopicMessage) -> AppResult<Option<TopicMessage>> {
+        Ok(self
+            .topic_messages
+            .get(&query.topic_id, &query.message_id)
+            .or_else(|| self.topic_replies.get(&query.topic_id, &query.message_id)))
+    }
+
+    async fn update_topic_message(
+        &self,
+        topic_message: &RequestUpdateTopicMessage,
+        revision: &TopicMessageRevision,
+    ) -> AppResult<TopicMessage> {
+        // Only the body is editable, the author and creation time stay as written.
+        let query = RequestGetTopicMessage {
+            topic_id: topic_message.topic_id,
+            message_id: topic_message.message_id,
+        };
+        let mut result = match self.find_topic_message(&query).await? {
+            Some(result) => result,
+            None => return Err(anyhow!(ApplicationError::NotFound { resource: "topic message" })),
+        };
+
+        self.revisions.upsert(
+            (revision.topic_id, revision.message_id),
+            revision.revision_id,
+            revision.clone(),
+        );
+        result.message = topic_message.message.to_owned();
+        result.edited_at = Some(revision.edited_at);
+        self.table_of(&result)
+            .upsert(result.topic_id, result.message_id, result.clone());
+        Ok(result)
+    }
+
+    async fn delete_topic_message(&self, tombstone: &TopicMessage) -> AppResult<()> {
+        self.table_of(tombstone)
+            .upsert(tombstone.topic_id, tombstone.message_id, tombstone.clone());
+        self.revisions
+            .remove_partition(&(tombstone.topic_id, tombstone.message_id));
+        Ok(())
+    }
+
+    async fn purge_topic_message(&self, query: &RequestGetTopicMessage) -> AppResult<()> {
+        self.topic_messages.remove(&query.topic_id, &query.message_id);
+        self.topic_replies.remove(&query.topic_id, &query.message_id);
+        self.revisions.remove_partition(&(query.topic_id, query.message_id));
+        Ok(())
+    }
+
+    async fn purge_topic_messages(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let messages = self.topic_messages.partition(&topic_id);
+        for message in messages.iter().chain(self.topic_replies.partition(&topic_id).iter()) {
+            self.revisions.remove_partition(&(topic_id, message.message_id));
+        }
+        self.topic_messages.remove_partition(&topic_id);
+        self.topic_replies.remove_partition(&topic_id);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "in-memory"))]
+mod tests {
+    use super::*;
+    use crate::domain::thread_message::repository::ThreadMessageRepository;
+    use crate::infrastructure::memory::MemoryRepositories;
+    use uptop_core::common::utils::now_timeuuid;
+
+    async fn post(repo: &TopicMessageMemoryRepo, topic_id: Timeuuid, count: usize) -> Vec<Timeuuid> {
+        let mut message_ids = vec![];
+        for _ in 0..count {
+            let topic_message = TopicMessage {
+                topic_id,
+                message_id: now_timeuuid(),
+                ..Default::default()
+            };
+            repo.create_topic_message(&topic_message).await.unwrap();
+            message_ids.push(topic_message.message_id);
+        }
+        message_ids.sort();
+        message_ids
+    }
+
+    fn query(topic_id: Timeuuid) -> RequestGetMessagesByTopicId {
+        RequestGetMessagesByTopicId {
+            topic_id,
+            page_size: Some(2),
+            page_token: None,
+            before: None,
+            after: None,
+        }
+    }
+
+    fn ids(page: &Page<TopicMessage>) -> Vec<Timeuuid> {
+        page.items.iter().map(|item| item.message_id).collect()
+    }
+
+    #[tokio::test]
+    async fn pages_newest_first() {
+        let repo = TopicMessageMemoryRepo::new();
+        let topic_id = now_timeuuid();
+        let message_ids = post(&repo, topic_id, 3).await;
+
+        let mut query = query(topic_id);
+        let first = repo.find_topic_message_by_partition_key(&query).await.unwrap();
+        assert_eq!(ids(&first), vec![message_ids[2], message_ids[1]]);
+
+        query.page_token = first.next_page_token;
+        let second = repo.find_topic_message_by_partition_key(&query).await.unwrap();
+        assert_eq!(ids(&second), vec![message_ids[0]]);
+        assert!(second.next_page_token.is_none());
+    }
+
+    #[tokio::test]
+    async fn after_reads_forward_and_before_reads_back() {
+        let repo = TopicMessageMemoryRepo::new();
+        let topic_id = now_timeuuid();
+        let message_ids = post(&repo, topic_id, 4).await;
+
+        let mut query = query(topic_id);
+        query.after = Some(message_ids[0]);
+        let page = repo.find_topic_message_by_partition_key(&query).await.unwrap();
+        assert_eq!(ids(&page), vec![message_ids[1], message_ids[2]]);
+
+        query.after = None;
+        query.before = Some(message_ids[3]);
+        let page = repo.find_topic_message_by_partition_key(&query).await.unwrap();
+        assert_eq!(ids(&page), vec![message_ids[2], message_ids[1]]);
+    }
+
+    #[tokio::test]
+    async fn replies_stay_off_the_timeline() {
+        let repos = MemoryRepositories::new();
+        let topic_id = now_timeuuid();
+        let message_ids = post(&repos.topic_message, topic_id, 1).await;
+        let reply = TopicMessage {
+            topic_id,
+            message_id: now_timeuuid(),
+            parent_id: Some(message_ids[0]),
+            ..Default::default()
+        };
+        repos.thread_message.create_thread_reply(&reply).await.unwrap();
+
+        let page = repos
+            .topic_message
+            .find_topic_message_by_partition_key(&query(topic_id))
+            .await
+            .unwrap();
+        assert_eq!(ids(&page), message_ids);
+
+        let found = repos
+            .topic_message
+            .find_topic_message(&RequestGetTopicMessage {
+                topic_id,
+                message_id: reply.message_id,
+            })
+            .await
+            .unwrap();
+        assert!(found.is_some_and(|found| found.is_reply()));
+    }
+}
@@ -0,0 +1,31 @@
+use crate::application::pagination::Page;
+use crate::application::topic_message::request::RequestGetMessageRevisions;
+use crate::domain::topic_message_revision::{
+    entity::TopicMessageRevision, repository::TopicMessageRevisionRepository,
+};
+use crate::infrastructure::memory::{page, Table};
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct TopicMessageRevisionMemoryRepo {
+    pub(super) revisions: Table<(Timeuuid, Timeuuid), Timeuuid, TopicMessageRevision>,
+}
+
+impl TopicMessageRevisionMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TopicMessageRevisionRepository for TopicMessageRevisionMemoryRepo {
+    async fn find_revisions_by_partition_key(
+        &self,
+        query: &RequestGetMessageRevisions,
+    ) -> AppResult<Page<TopicMessageRevision>> {
+        // CLUSTERING ORDER BY (revision_id DESC)
+        let mut revisions = self.revisions.partition(&(query.topic_id, query.message_id));
+        revisions.reverse();
+        page(revisions, query.page_size, &query.page_token)
+    }
+}
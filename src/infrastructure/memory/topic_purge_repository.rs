@@ -0,0 +1,36 @@
+use crate::domain::topic_purge::{entity::TopicPurge, repository::TopicPurgeRepository};
+use charybdis::types::Timeuuid;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use uptop_core::common::result::AppResult;
+
+/// Keyed by topic alone, so it is a map rather than a [`Table`](super::Table).
+#[derive(Clone, Debug, Default)]
+pub struct TopicPurgeMemoryRepo {
+    topic_purges: Arc<Mutex<HashMap<Timeuuid, TopicPurge>>>,
+}
+
+impl TopicPurgeMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TopicPurgeRepository for TopicPurgeMemoryRepo {
+    async fn find_topic_purges(&self) -> AppResult<Vec<TopicPurge>> {
+        let topic_purges = self.topic_purges.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(topic_purges.values().cloned().collect())
+    }
+
+    async fn save_topic_purge(&self, topic_purge: &TopicPurge) -> AppResult<()> {
+        let mut topic_purges = self.topic_purges.lock().unwrap_or_else(PoisonError::into_inner);
+        topic_purges.insert(topic_purge.topic_id, topic_purge.clone());
+        Ok(())
+    }
+
+    async fn remove_topic_purge(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let mut topic_purges = self.topic_purges.lock().unwrap_or_else(PoisonError::into_inner);
+        topic_purges.remove(&topic_id);
+        Ok(())
+    }
+}
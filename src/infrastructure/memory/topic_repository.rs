@@ -0,0 +1,119 @@
+use crate::application::error::ApplicationError;
+use crate::application::pagination::Page;
+use crate::application::topic::request::{
+    RequestGetTopicByIndexKey, RequestGetTopicByPartitionKey, RequestGetTopicByPrimaryKey,
+};
+use crate::domain::topic::{entity::Topic, repository::TopicRepository};
+use crate::infrastructure::memory::{page, Table};
+use anyhow::anyhow;
+use charybdis::types::{Timestamp, Timeuuid};
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct TopicMemoryRepo {
+    topics: Table<Timeuuid, Timestamp, Topic>,
+}
+
+impl TopicMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TopicRepository for TopicMemoryRepo {
+    async fn create_topic<'c>(&self, topic: &'c Topic) -> AppResult<&'c Topic> {
+        self.topics.upsert(topic.topic_id, topic.created_at, topic.clone());
+        Ok(topic)
+    }
+
+    async fn find_topic_by_partition_key(
+        &self,
+        query: &RequestGetTopicByPartitionKey,
+    ) -> AppResult<Page<Topic>> {
+        // CLUSTERING ORDER BY (created_at DESC)
+        let mut topics = self.topics.partition(&query.topic_id);
+        topics.reverse();
+        page(topics, query.page_size, &query.page_token)
+    }
+
+    async fn find_topic_by_primary_key(
+        &self,
+        query: &RequestGetTopicByPrimaryKey,
+    ) -> AppResult<Topic> {
+        match self.topics.get(&query.topic_id, &query.created_at) {
+            Some(topic) => Ok(topic),
+            None => Err(anyhow!(ApplicationError::NotFound { resource: "topic" })),
+        }
+    }
+
+    async fn find_topic_by_index_key(
+        &self,
+        query: &RequestGetTopicByIndexKey,
+    ) -> AppResult<Page<Topic>> {
+        let topics = self.topics.scan(|topic| topic.topic_name == query.topic_name);
+        page(topics, query.page_size, &query.page_token)
+    }
+
+    async fn update_topic(&self, topic: &Topic, expected_updated_at: Timestamp) -> AppResult<bool> {
+        Ok(self.topics.update_if(
+            &topic.topic_id,
+            &topic.created_at,
+            |stored| stored.updated_at == expected_updated_at,
+            topic.clone(),
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "in-memory"))]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use uptop_core::common::utils::now_timeuuid;
+
+    #[tokio::test]
+    async fn update_topic_loses_to_an_earlier_write() {
+        let repo = TopicMemoryRepo::new();
+        let created_at = Utc::now();
+        let topic = Topic {
+            topic_id: now_timeuuid(),
+            topic_name: "general".to_string(),
+            created_at,
+            updated_at: created_at,
+            ..Default::default()
+        };
+        repo.create_topic(&topic).await.unwrap();
+
+        let first = Topic {
+            topic_name: "first".to_string(),
+            updated_at: created_at + Duration::milliseconds(1),
+            ..topic.clone()
+        };
+        assert!(repo.update_topic(&first, topic.updated_at).await.unwrap());
+
+        // Read before the first write landed, so it still expects the old `updated_at`.
+        let second = Topic {
+            topic_name: "second".to_string(),
+            updated_at: created_at + Duration::milliseconds(2),
+            ..topic.clone()
+        };
+        assert!(!repo.update_topic(&second, topic.updated_at).await.unwrap());
+
+        let query = RequestGetTopicByPrimaryKey {
+            topic_id: topic.topic_id,
+            created_at,
+        };
+        let stored = repo.find_topic_by_primary_key(&query).await.unwrap();
+        assert_eq!(stored.topic_name, "first");
+    }
+
+    #[tokio::test]
+    async fn update_topic_needs_an_existing_topic() {
+        let repo = TopicMemoryRepo::new();
+        let topic = Topic {
+            topic_id: now_timeuuid(),
+            created_at: Utc::now(),
+            ..Default::default()
+        };
+        assert!(!repo.update_topic(&topic, topic.updated_at).await.unwrap());
+    }
+}
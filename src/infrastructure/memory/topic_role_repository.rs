@@ -0,0 +1,51 @@
+use crate::application::pagination::Page;
+use crate::application::topic_role::request::RequestGetRolesByTopicId;
+use crate::application::topic_user::request::RequestGetTopicMember;
+use crate::domain::topic_role::{entity::TopicRole, repository::TopicRoleRepository};
+use crate::infrastructure::memory::{page, Table};
+use charybdis::types::{Text, Timeuuid};
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct TopicRoleMemoryRepo {
+    topic_roles: Table<Timeuuid, Text, TopicRole>,
+}
+
+impl TopicRoleMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TopicRoleRepository for TopicRoleMemoryRepo {
+    async fn find_topic_role(&self, query: &RequestGetTopicMember) -> AppResult<Option<TopicRole>> {
+        Ok(self.topic_roles.get(&query.topic_id, &query.username))
+    }
+
+    async fn find_topic_roles_by_partition_key(
+        &self,
+        query: &RequestGetRolesByTopicId,
+    ) -> AppResult<Page<TopicRole>> {
+        let topic_roles = self.topic_roles.partition(&query.topic_id);
+        page(topic_roles, query.page_size, &query.page_token)
+    }
+
+    async fn save_topic_role(&self, topic_role: &TopicRole) -> AppResult<()> {
+        self.topic_roles.upsert(
+            topic_role.topic_id,
+            topic_role.username.to_owned(),
+            topic_role.clone(),
+        );
+        Ok(())
+    }
+
+    async fn remove_topic_role(&self, query: &RequestGetTopicMember) -> AppResult<()> {
+        self.topic_roles.remove(&query.topic_id, &query.username);
+        Ok(())
+    }
+
+    async fn remove_topic_roles(&self, topic_id: Timeuuid) -> AppResult<()> {
+        self.topic_roles.remove_partition(&topic_id);
+        Ok(())
+    }
+}
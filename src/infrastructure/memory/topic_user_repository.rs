@@ -0,0 +1,155 @@
+use crate::application::pagination::Page;
+use crate::application::topic_user::request::RequestGetUsersByTopicId;
+use crate::domain::topic_user::{entity::TopicUser, repository::TopicUserRepository};
+use crate::infrastructure::memory::{page, Table};
+use crate::domain::topic_invite::entity::TopicInvite;
+use crate::domain::user_topic::entity::UserTopic;
+use crate::infrastructure::memory::topic_invite_repository::TopicInviteMemoryRepo;
+use crate::infrastructure::memory::user_topic_repository::UserTopicMemoryRepo;
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use uptop_core::common::result::AppResult;
+
+/// Membership writes touch both views and the invite, so this repository
+/// holds the same tables as the user topic and invite repositories.
+#[derive(Clone, Debug, Default)]
+pub struct TopicUserMemoryRepo {
+    topic_users: Table<Timeuuid, (Timestamp, Text), TopicUser>,
+    user_topics: Table<Text, Timeuuid, UserTopic>,
+    topic_invites: Table<Text, Timeuuid, TopicInvite>,
+}
+
+impl TopicUserMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_views(user_topic: &UserTopicMemoryRepo, topic_invite: &TopicInviteMemoryRepo) -> Self {
+        Self {
+            topic_users: Table::default(),
+            user_topics: user_topic.user_topics.clone(),
+            topic_invites: topic_invite.topic_invites.clone(),
+        }
+    }
+}
+
+impl TopicUserRepository for TopicUserMemoryRepo {
+    async fn find_topic_users_by_partition_key(
+        &self,
+        query: &RequestGetUsersByTopicId,
+    ) -> AppResult<Page<TopicUser>> {
+        let topic_users = self.topic_users.partition(&query.topic_id);
+        page(topic_users, query.page_size, &query.page_token)
+    }
+
+    async fn add_topic_member(
+        &self,
+        topic_user: &TopicUser,
+        user_topic: &UserTopic,
+        accepted_invite: Option<&TopicInvite>,
+    ) -> AppResult<()> {
+        self.topic_users.upsert(
+            topic_user.topic_id,
+            (topic_user.created_at, topic_user.username.to_owned()),
+            topic_user.clone(),
+        );
+        self.user_topics
+            .upsert(user_topic.username.to_owned(), user_topic.topic_id, user_topic.clone());
+        if let Some(invite) = accepted_invite {
+            self.topic_invites
+                .upsert(invite.username.to_owned(), invite.topic_id, invite.clone());
+        }
+        Ok(())
+    }
+
+    async fn remove_topic_member(&self, user_topic: &UserTopic) -> AppResult<()> {
+        self.topic_users.remove(
+            &user_topic.topic_id,
+            &(user_topic.created_at, user_topic.username.to_owned()),
+        );
+        self.user_topics.remove(&user_topic.username, &user_topic.topic_id);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "in-memory"))]
+mod tests {
+    use super::*;
+    use crate::application::topic_user::request::RequestGetTopicMember;
+    use crate::domain::user_topic::repository::UserTopicRepository;
+    use crate::infrastructure::memory::MemoryRepositories;
+    use chrono::Utc;
+    use uptop_core::common::utils::now_timeuuid;
+
+    fn member(topic_id: Timeuuid, username: &str, created_at: Timestamp) -> (TopicUser, UserTopic) {
+        let topic_user = TopicUser {
+            topic_id,
+            username: username.to_string(),
+            user_id: now_timeuuid(),
+            created_at,
+        };
+        let user_topic = UserTopic {
+            topic_id,
+            username: username.to_string(),
+            created_at,
+            ..Default::default()
+        };
+        (topic_user, user_topic)
+    }
+
+    async fn usernames(repos: &MemoryRepositories, topic_id: Timeuuid) -> Vec<Text> {
+        let query = RequestGetUsersByTopicId {
+            topic_id,
+            page_size: None,
+            page_token: None,
+        };
+        let page = repos.topic_user.find_topic_users_by_partition_key(&query).await.unwrap();
+        page.items.into_iter().map(|item| item.username).collect()
+    }
+
+    async fn user_topic(repos: &MemoryRepositories, topic_id: Timeuuid, username: &str) -> Option<UserTopic> {
+        let query = RequestGetTopicMember {
+            topic_id,
+            username: username.to_string(),
+        };
+        repos.user_topic.find_user_topic(&query).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn join_and_leave_write_both_views() {
+        let repos = MemoryRepositories::new();
+        let topic_id = now_timeuuid();
+        let (topic_user, user_topic_row) = member(topic_id, "alice", Utc::now());
+
+        repos
+            .topic_user
+            .add_topic_member(&topic_user, &user_topic_row, None)
+            .await
+            .unwrap();
+        assert_eq!(usernames(&repos, topic_id).await, vec!["alice"]);
+        assert!(user_topic(&repos, topic_id, "alice").await.is_some());
+
+        repos.topic_user.remove_topic_member(&user_topic_row).await.unwrap();
+        assert!(usernames(&repos, topic_id).await.is_empty());
+        assert!(user_topic(&repos, topic_id, "alice").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn members_joining_in_the_same_millisecond_are_both_kept() {
+        let repos = MemoryRepositories::new();
+        let topic_id = now_timeuuid();
+        let created_at = Utc::now();
+        for username in ["alice", "bob"] {
+            let (topic_user, user_topic_row) = member(topic_id, username, created_at);
+            repos
+                .topic_user
+                .add_topic_member(&topic_user, &user_topic_row, None)
+                .await
+                .unwrap();
+        }
+        assert_eq!(usernames(&repos, topic_id).await, vec!["alice", "bob"]);
+
+        let (_, bob) = member(topic_id, "bob", created_at);
+        repos.topic_user.remove_topic_member(&bob).await.unwrap();
+        assert_eq!(usernames(&repos, topic_id).await, vec!["alice"]);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::application::latest_message::request::{RequestGetUnreadCounters, RequestGetUnreadSummary};
+use crate::application::pagination::Page;
+use crate::domain::unread_counter::{entity::UnreadCounter, repository::UnreadCounterRepository};
+use crate::infrastructure::memory::{page, Table};
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct UnreadCounterMemoryRepo {
+    unread_counters: Table<Timeuuid, Timeuuid, UnreadCounter>,
+}
+
+impl UnreadCounterMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UnreadCounterRepository for UnreadCounterMemoryRepo {
+    async fn find_unread_counters_by_user_id(&self, query: &RequestGetUnreadSummary) -> AppResult<Vec<UnreadCounter>> {
+        Ok(self.unread_counters.partition(&query.user_id))
+    }
+
+    async fn find_unread_counters(&self, query: &RequestGetUnreadCounters) -> AppResult<Page<UnreadCounter>> {
+        page(self.unread_counters.scan(|_| true), query.page_size, &query.page_token)
+    }
+
+    async fn increment_unread_counters(&self, increments: &[UnreadCounter]) -> AppResult<()> {
+        for increment in increments {
+            let stored = self
+                .unread_counters
+                .get(&increment.user_id, &increment.topic_id)
+                .map(|counter| counter.unread)
+                .unwrap_or_default();
+            self.unread_counters.upsert(
+                increment.user_id,
+                increment.topic_id,
+                UnreadCounter {
+                    unread: stored + increment.unread,
+                    ..increment.clone()
+                },
+            );
+        }
+        Ok(())
+    }
+
+    async fn reset_unread_counter(&self, counter: &UnreadCounter) -> AppResult<()> {
+        self.unread_counters
+            .upsert(counter.user_id, counter.topic_id, counter.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use crate::application::user_identity::request::RequestGetUserIdentity;
+use crate::domain::user_identity::{entity::UserIdentity, repository::UserIdentityRepository};
+use charybdis::types::Text;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use uptop_core::common::result::AppResult;
+
+/// Keyed by username alone, so it is a map rather than a [`Table`](super::Table).
+#[derive(Clone, Debug, Default)]
+pub struct UserIdentityMemoryRepo {
+    user_identities: Arc<Mutex<HashMap<Text, UserIdentity>>>,
+}
+
+impl UserIdentityMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UserIdentityRepository for UserIdentityMemoryRepo {
+    async fn find_user_identity(&self, query: &RequestGetUserIdentity) -> AppResult<Option<UserIdentity>> {
+        let user_identities = self.user_identities.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(user_identities.get(&query.username).cloned())
+    }
+
+    async fn save_user_identity(&self, identity: &UserIdentity) -> AppResult<()> {
+        let mut user_identities = self.user_identities.lock().unwrap_or_else(PoisonError::into_inner);
+        user_identities.insert(identity.username.to_owned(), identity.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::application::pagination::Page;
+use crate::application::topic_user::request::RequestGetTopicMember;
+use crate::application::user_topic::request::RequestGetTopicsByUsername;
+use crate::domain::user_topic::{entity::UserTopic, repository::UserTopicRepository};
+use crate::infrastructure::memory::{page, Table};
+use charybdis::types::{Text, Timeuuid};
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug, Default)]
+pub struct UserTopicMemoryRepo {
+    pub(super) user_topics: Table<Text, Timeuuid, UserTopic>,
+}
+
+impl UserTopicMemoryRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UserTopicRepository for UserTopicMemoryRepo {
+    async fn find_user_topics_by_partition_key(
+        &self,
+        query: &RequestGetTopicsByUsername,
+    ) -> AppResult<Page<UserTopic>> {
+        let user_topics = self.user_topics.partition(&query.username);
+        page(user_topics, query.page_size, &query.page_token)
+    }
+
+    async fn find_user_topic(&self, query: &RequestGetTopicMember) -> AppResult<Option<UserTopic>> {
+        Ok(self.user_topics.get(&query.username, &query.topic_id))
+    }
+}
@@ -0,0 +1,386 @@
+use crate::infrastructure::migration::schema::{LiveColumn, MODEL_SCHEMAS};
+use crate::infrastructure::persistence::MessageSession;
+use anyhow::bail;
+use charybdis::types::Timestamp;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use thiserror::Error;
+use uptop_core::common::result::AppResult;
+
+pub mod backfill;
+pub mod schema;
+
+pub const KEYSPACE: &str = "uptop";
+
+/// One schema change, applied at most once and in `version` order. Applied
+/// migrations must never be edited: their checksum is recorded and rechecked.
+#[derive(Clone, Copy, Debug)]
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub cql: &'static str,
+    /// Copies data the CQL cannot, run after it. It must be safe to run again,
+    /// since a failure leaves the migration pending.
+    pub backfill: Option<Backfill>,
+}
+
+pub type Backfill = fn(MessageSession) -> Pin<Box<dyn Future<Output = AppResult<()>> + Send>>;
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        cql: include_str!("../../migrations/0001_initial_schema.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 2,
+        name: "topic_membership",
+        cql: include_str!("../../migrations/0002_topic_membership.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 3,
+        name: "topic_roles",
+        cql: include_str!("../../migrations/0003_topic_roles.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 4,
+        name: "message_revisions",
+        cql: include_str!("../../migrations/0004_message_revisions.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 5,
+        name: "message_deletion",
+        cql: include_str!("../../migrations/0005_message_deletion.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 6,
+        name: "message_threads",
+        cql: include_str!("../../migrations/0006_message_threads.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 7,
+        name: "quotes_and_forwards",
+        cql: include_str!("../../migrations/0007_quotes_and_forwards.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 8,
+        name: "message_reactions",
+        cql: include_str!("../../migrations/0008_message_reactions.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 9,
+        name: "read_cursors",
+        cql: include_str!("../../migrations/0009_read_cursors.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 10,
+        name: "unread_counters",
+        cql: include_str!("../../migrations/0010_unread_counters.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 11,
+        name: "direct_conversations",
+        cql: include_str!("../../migrations/0011_direct_conversations.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 12,
+        name: "group_conversations",
+        cql: include_str!("../../migrations/0012_group_conversations.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 13,
+        name: "pinned_messages",
+        cql: include_str!("../../migrations/0013_pinned_messages.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 14,
+        name: "topic_states",
+        cql: include_str!("../../migrations/0014_topic_states.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 15,
+        name: "topic_members",
+        cql: include_str!("../../migrations/0015_topic_members.cql"),
+        backfill: Some(backfill::copy_topic_members),
+    },
+    Migration {
+        version: 16,
+        name: "user_identities",
+        cql: include_str!("../../migrations/0016_user_identities.cql"),
+        backfill: None,
+    },
+    Migration {
+        version: 17,
+        name: "topic_replies",
+        cql: include_str!("../../migrations/0017_topic_replies.cql"),
+        backfill: Some(backfill::move_topic_replies),
+    },
+    Migration {
+        version: 18,
+        name: "topic_purges",
+        cql: include_str!("../../migrations/0018_topic_purges.cql"),
+        backfill: None,
+    },
+];
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("migration {version} ({name}) was changed after it was applied")]
+    ChecksumMismatch { version: i32, name: String },
+    #[error("migration {version} is applied but unknown to this build")]
+    UnknownVersion { version: i32 },
+    #[error("live schema diverges from the models:\n{}", problems.join("\n"))]
+    SchemaDrift { problems: Vec<String> },
+}
+
+impl Migration {
+    pub fn checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(self.cql.as_bytes()))
+    }
+
+    /// The CQL split into single statements, comments dropped, since the
+    /// driver executes one statement per request.
+    pub fn statements(&self) -> Vec<String> {
+        let cql: String = self
+            .cql
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("--"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        cql.split(';')
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct AppliedMigration {
+    name: String,
+    checksum: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Migrator {
+    db: MessageSession,
+}
+
+impl Migrator {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+
+    /// Migrations not applied yet, in order. Fails if an applied one was edited.
+    pub async fn pending(&self) -> AppResult<Vec<&'static Migration>> {
+        let applied = self.applied().await?;
+        check_applied(&applied)?;
+
+        let mut pending: Vec<&'static Migration> = MIGRATIONS
+            .iter()
+            .filter(|migration| !applied.contains_key(&migration.version))
+            .collect();
+        pending.sort_by_key(|migration| migration.version);
+        Ok(pending)
+    }
+
+    /// Applies every pending migration and returns them.
+    pub async fn migrate(&self) -> AppResult<Vec<&'static Migration>> {
+        let pending = self.pending().await?;
+
+        for migration in pending.iter() {
+            tracing::info!(version = migration.version, name = migration.name, "applying migration");
+            for statement in migration.statements() {
+                self.db.execute_unpaged(statement, ()).await?;
+            }
+            if let Some(backfill) = migration.backfill {
+                tracing::info!(version = migration.version, name = migration.name, "backfilling");
+                backfill(self.db.clone()).await?;
+            }
+
+            let applied_at: Timestamp = Utc::now();
+            self.db
+                .execute_unpaged(
+                    INSERT_SCHEMA_MIGRATION_QUERY,
+                    (migration.version, migration.name, migration.checksum(), applied_at),
+                )
+                .await?;
+        }
+
+        Ok(pending)
+    }
+
+    /// Compares the live tables with the models and fails on the first
+    /// build whose queries would not match them.
+    pub async fn verify_schema(&self) -> AppResult<()> {
+        let result = self
+            .db
+            .execute_unpaged(FIND_SCHEMA_COLUMNS_QUERY, (KEYSPACE,))
+            .await?;
+
+        let mut live: HashMap<String, Vec<LiveColumn>> = HashMap::new();
+        for row in result.rows_typed::<(String, String, String, i32, String, String)>()? {
+            let (table, name, kind, position, clustering_order, cql_type) = row?;
+            live.entry(table).or_default().push(LiveColumn {
+                name,
+                kind,
+                position,
+                clustering_order,
+                cql_type,
+            });
+        }
+
+        let mut problems: Vec<String> = vec![];
+        for schema in MODEL_SCHEMAS {
+            let columns = live.get(schema.table).map(Vec::as_slice).unwrap_or_default();
+            problems.extend(schema.diff(columns)?);
+        }
+
+        match problems.is_empty() {
+            true => Ok(()),
+            false => bail!(MigrationError::SchemaDrift { problems }),
+        }
+    }
+
+    async fn applied(&self) -> AppResult<HashMap<i32, AppliedMigration>> {
+        self.db
+            .execute_unpaged(CREATE_SCHEMA_MIGRATIONS_TABLE_QUERY, ())
+            .await?;
+
+        let result = self
+            .db
+            .execute_unpaged(FIND_SCHEMA_MIGRATIONS_QUERY, ())
+            .await?;
+
+        let mut applied = HashMap::new();
+        for row in result.rows_typed::<(i32, String, String)>()? {
+            let (version, name, checksum) = row?;
+            applied.insert(version, AppliedMigration { name, checksum });
+        }
+        Ok(applied)
+    }
+}
+
+/// Fails if an applied migration was edited since, or is missing from this build.
+fn check_applied(applied: &HashMap<i32, AppliedMigration>) -> AppResult<()> {
+    for (version, migration) in applied.iter() {
+        match MIGRATIONS.iter().find(|known| known.version == *version) {
+            Some(known) if known.checksum() != migration.checksum => {
+                bail!(MigrationError::ChecksumMismatch {
+                    version: *version,
+                    name: migration.name.to_owned(),
+                })
+            }
+            Some(_) => (),
+            None => bail!(MigrationError::UnknownVersion { version: *version }),
+        }
+    }
+    Ok(())
+}
+
+static CREATE_SCHEMA_MIGRATIONS_TABLE_QUERY: &str = r#"
+    CREATE TABLE IF NOT EXISTS uptop.schema_migrations (
+        version int,
+        name text,
+        checksum text,
+        applied_at timestamp,
+        PRIMARY KEY (version)
+    )
+"#;
+
+static FIND_SCHEMA_MIGRATIONS_QUERY: &str = r#"
+    SELECT version, name, checksum FROM uptop.schema_migrations
+"#;
+
+static INSERT_SCHEMA_MIGRATION_QUERY: &str = r#"
+    INSERT INTO uptop.schema_migrations (version, name, checksum, applied_at) VALUES (?, ?, ?, ?)
+"#;
+
+static FIND_SCHEMA_COLUMNS_QUERY: &str = r#"
+    SELECT table_name, column_name, kind, position, clustering_order, type
+    FROM system_schema.columns
+    WHERE keyspace_name = ?
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(migration: &Migration, checksum: String) -> HashMap<i32, AppliedMigration> {
+        let applied = AppliedMigration {
+            name: migration.name.to_string(),
+            checksum,
+        };
+        HashMap::from([(migration.version, applied)])
+    }
+
+    #[test]
+    fn migrations_are_numbered_in_order() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i32 + 1, "{}", migration.name);
+            assert!(!migration.statements().is_empty(), "{}", migration.name);
+        }
+    }
+
+    #[test]
+    fn statements_drop_comments_and_split_on_semicolons() {
+        let migration = Migration {
+            version: 1,
+            name: "example",
+            cql: "-- a comment; not a statement\nCREATE TABLE a (id int PRIMARY KEY);\n\n  -- another\nDROP TABLE b;\n",
+            backfill: None,
+        };
+        assert_eq!(
+            migration.statements(),
+            vec!["CREATE TABLE a (id int PRIMARY KEY)".to_string(), "DROP TABLE b".to_string()]
+        );
+    }
+
+    #[test]
+    fn an_unchanged_migration_passes_the_check() {
+        let migration = &MIGRATIONS[0];
+        check_applied(&applied(migration, migration.checksum())).unwrap();
+    }
+
+    #[test]
+    fn an_edited_migration_fails_the_check() {
+        let migration = &MIGRATIONS[0];
+        let edited = Migration {
+            cql: "CREATE TABLE uptop.edited (id int PRIMARY KEY)",
+            ..*migration
+        };
+        let err = check_applied(&applied(migration, edited.checksum())).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(MigrationError::ChecksumMismatch { version: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn a_migration_unknown_to_the_build_fails_the_check() {
+        let unknown = Migration {
+            version: MIGRATIONS.len() as i32 + 1,
+            ..MIGRATIONS[0]
+        };
+        let err = check_applied(&applied(&unknown, unknown.checksum())).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(MigrationError::UnknownVersion { .. })));
+    }
+}
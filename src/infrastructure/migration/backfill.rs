@@ -0,0 +1,96 @@
+use crate::infrastructure::persistence::MessageSession;
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use std::future::Future;
+use std::pin::Pin;
+use tokio_stream::StreamExt;
+use uptop_core::common::result::AppResult;
+
+/// Copies every membership from `topic_user` into `topic_members`. Rows keep
+/// their key values, so running it again rewrites the same rows.
+pub fn copy_topic_members(db: MessageSession) -> Pin<Box<dyn Future<Output = AppResult<()>> + Send>> {
+    Box::pin(async move {
+        let mut rows = db
+            .execute_iter(FIND_TOPIC_USERS_QUERY, ())
+            .await?
+            .into_typed::<(Timeuuid, Timestamp, Text, Timeuuid)>();
+        let mut copied: u64 = 0;
+        while let Some(row) = rows.next().await {
+            let (topic_id, created_at, username, user_id) = row?;
+            db.execute_unpaged(INSERT_TOPIC_MEMBER_QUERY, (topic_id, created_at, &username, user_id))
+                .await?;
+            copied += 1;
+        }
+        tracing::info!(copied, "copied topic members");
+        Ok(())
+    })
+}
+
+/// Moves every thread reply from `topic_messages` to `topic_replies`. Each
+/// reply is written to its new table before it is deleted from the old one,
+/// so running it again finishes what an interrupted run left.
+pub fn move_topic_replies(db: MessageSession) -> Pin<Box<dyn Future<Output = AppResult<()>> + Send>> {
+    Box::pin(async move {
+        let mut rows = db
+            .execute_iter(FIND_TOPIC_MESSAGES_QUERY, ())
+            .await?
+            .into_typed::<ReplyRow>();
+        let mut moved: u64 = 0;
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            // Timeline messages have no parent.
+            if row.9.is_none() {
+                continue;
+            }
+            let (topic_id, message_id) = (row.0, row.1);
+            db.execute_unpaged(INSERT_TOPIC_REPLY_QUERY, row).await?;
+            db.execute_unpaged(DELETE_TOPIC_MESSAGE_QUERY, (topic_id, message_id))
+                .await?;
+            moved += 1;
+        }
+        tracing::info!(moved, "moved topic replies");
+        Ok(())
+    })
+}
+
+/// The columns a reply can have set, in the order of the queries below.
+type ReplyRow = (
+    Timeuuid,
+    Timeuuid,
+    Timeuuid,
+    Text,
+    Timestamp,
+    Option<Text>,
+    Option<Timestamp>,
+    Option<Timestamp>,
+    Option<Timeuuid>,
+    Option<Timeuuid>,
+    Option<Timeuuid>,
+    Option<Timeuuid>,
+    Option<Text>,
+);
+
+static FIND_TOPIC_USERS_QUERY: &str = r#"
+    SELECT topic_id, created_at, username, user_id FROM uptop.topic_user
+"#;
+
+static INSERT_TOPIC_MEMBER_QUERY: &str = r#"
+    INSERT INTO uptop.topic_members (topic_id, created_at, username, user_id) VALUES (?, ?, ?, ?)
+"#;
+
+static FIND_TOPIC_MESSAGES_QUERY: &str = r#"
+    SELECT topic_id, message_id, from_user_id, message, created_at, kind, edited_at, deleted_at, deleted_by,
+        parent_id, quoted_message_id, quoted_from_user_id, quoted_message
+    FROM uptop.topic_messages
+"#;
+
+static INSERT_TOPIC_REPLY_QUERY: &str = r#"
+    INSERT INTO uptop.topic_replies (
+        topic_id, message_id, from_user_id, message, created_at, kind, edited_at, deleted_at, deleted_by,
+        parent_id, quoted_message_id, quoted_from_user_id, quoted_message
+    )
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+
+static DELETE_TOPIC_MESSAGE_QUERY: &str = r#"
+    DELETE FROM uptop.topic_messages WHERE topic_id = ? AND message_id = ?
+"#;
@@ -0,0 +1,476 @@
+use crate::domain::direct_conversation::entity::DirectConversation;
+use crate::domain::group_conversation::entity::GroupConversation;
+use crate::domain::hidden_message::entity::HiddenMessage;
+use crate::domain::latest_message::entity::LatestMessage;
+use crate::domain::message_reaction::entity::MessageReaction;
+use crate::domain::notification::entity::Notification;
+use crate::domain::pinned_message::entity::PinnedMessage;
+use crate::domain::read_cursor::entity::ReadCursor;
+use crate::domain::thread_message::entity::ThreadMessage;
+use crate::domain::topic::entity::Topic;
+use crate::domain::topic_invite::entity::TopicInvite;
+use crate::domain::topic_role::entity::TopicRole;
+use crate::domain::topic_message::entity::TopicMessage;
+use crate::domain::topic_message_revision::entity::TopicMessageRevision;
+use crate::domain::topic_purge::entity::TopicPurge;
+use crate::domain::topic_user::entity::TopicUser;
+use crate::domain::unread_counter::entity::UnreadCounter;
+use crate::domain::user_topic::entity::UserTopic;
+use crate::domain::user_identity::entity::UserIdentity;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use uptop_core::common::result::AppResult;
+
+/// A column as `system_schema.columns` describes it.
+#[derive(Clone, Debug)]
+pub struct LiveColumn {
+    pub name: String,
+    pub kind: String,
+    pub position: i32,
+    pub clustering_order: String,
+    pub cql_type: String,
+}
+
+/// The table a `#[charybdis_model]` expects: its keys as declared on the
+/// model and the CQL type of every field.
+#[derive(Clone, Copy, Debug)]
+pub struct ModelSchema {
+    pub table: &'static str,
+    pub partition_keys: &'static [&'static str],
+    /// Clustering columns with their `asc` / `desc` order.
+    pub clustering_keys: &'static [(&'static str, &'static str)],
+    pub columns: &'static [(&'static str, &'static str)],
+    /// Field names of the model struct, to catch this description going stale.
+    pub fields: fn() -> AppResult<BTreeSet<String>>,
+}
+
+pub const MODEL_SCHEMAS: &[ModelSchema] = &[
+    ModelSchema {
+        table: "topics",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[("created_at", "desc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("topic_name", "text"),
+            ("topic_description", "text"),
+            ("topic_owners", "list<text>"),
+            ("topic_admins", "list<text>"),
+            ("created_at", "timestamp"),
+            ("updated_at", "timestamp"),
+            ("topic_kind", "text"),
+            ("topic_state", "text"),
+        ],
+        fields: model_fields::<Topic>,
+    },
+    ModelSchema {
+        table: "topic_messages",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[("message_id", "desc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("message_id", "timeuuid"),
+            ("from_user_id", "timeuuid"),
+            ("message", "text"),
+            ("created_at", "timestamp"),
+            ("kind", "text"),
+            ("edited_at", "timestamp"),
+            ("deleted_at", "timestamp"),
+            ("deleted_by", "timeuuid"),
+            ("parent_id", "timeuuid"),
+            ("reply_count", "int"),
+            ("last_reply_at", "timestamp"),
+            ("quoted_message_id", "timeuuid"),
+            ("quoted_from_user_id", "timeuuid"),
+            ("quoted_message", "text"),
+            ("forwarded_from_topic_id", "timeuuid"),
+            ("forwarded_from_message_id", "timeuuid"),
+            ("forwarded_from_user_id", "timeuuid"),
+        ],
+        fields: model_fields::<TopicMessage>,
+    },
+    ModelSchema {
+        table: "topic_replies",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[("message_id", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("message_id", "timeuuid"),
+            ("from_user_id", "timeuuid"),
+            ("message", "text"),
+            ("created_at", "timestamp"),
+            ("kind", "text"),
+            ("edited_at", "timestamp"),
+            ("deleted_at", "timestamp"),
+            ("deleted_by", "timeuuid"),
+            ("parent_id", "timeuuid"),
+            ("reply_count", "int"),
+            ("last_reply_at", "timestamp"),
+            ("quoted_message_id", "timeuuid"),
+            ("quoted_from_user_id", "timeuuid"),
+            ("quoted_message", "text"),
+            ("forwarded_from_topic_id", "timeuuid"),
+            ("forwarded_from_message_id", "timeuuid"),
+            ("forwarded_from_user_id", "timeuuid"),
+        ],
+        fields: model_fields::<TopicMessage>,
+    },
+    ModelSchema {
+        table: "latest_messages",
+        partition_keys: &["user_id"],
+        clustering_keys: &[("topic_id", "asc")],
+        columns: &[
+            ("latest_message_id", "timeuuid"),
+            ("latest_message_content", "text"),
+            ("topic_id", "timeuuid"),
+            ("user_id", "timeuuid"),
+            ("created_at", "timestamp"),
+            ("updated_at", "timestamp"),
+        ],
+        fields: model_fields::<LatestMessage>,
+    },
+    ModelSchema {
+        table: "notification",
+        partition_keys: &["username"],
+        clustering_keys: &[("created_at", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("username", "text"),
+            ("from_user", "text"),
+            ("message", "text"),
+            ("created_at", "timestamp"),
+        ],
+        fields: model_fields::<Notification>,
+    },
+    ModelSchema {
+        table: "topic_members",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[("created_at", "asc"), ("username", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("username", "text"),
+            ("user_id", "timeuuid"),
+            ("created_at", "timestamp"),
+        ],
+        fields: model_fields::<TopicUser>,
+    },
+    ModelSchema {
+        table: "user_topic",
+        partition_keys: &["username"],
+        clustering_keys: &[("topic_id", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("username", "text"),
+            ("created_at", "timestamp"),
+            ("topic_kind", "text"),
+            ("topic_state", "text"),
+        ],
+        fields: model_fields::<UserTopic>,
+    },
+    ModelSchema {
+        table: "topic_invites",
+        partition_keys: &["username"],
+        clustering_keys: &[("topic_id", "asc")],
+        columns: &[
+            ("username", "text"),
+            ("topic_id", "timeuuid"),
+            ("invited_by", "text"),
+            ("status", "text"),
+            ("created_at", "timestamp"),
+            ("updated_at", "timestamp"),
+        ],
+        fields: model_fields::<TopicInvite>,
+    },
+    ModelSchema {
+        table: "topic_roles",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[("username", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("username", "text"),
+            ("role", "text"),
+            ("updated_by", "text"),
+            ("updated_at", "timestamp"),
+        ],
+        fields: model_fields::<TopicRole>,
+    },
+    ModelSchema {
+        table: "topic_message_revisions",
+        partition_keys: &["topic_id", "message_id"],
+        clustering_keys: &[("revision_id", "desc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("message_id", "timeuuid"),
+            ("revision_id", "timeuuid"),
+            ("edited_by", "timeuuid"),
+            ("edited_at", "timestamp"),
+            ("message", "text"),
+        ],
+        fields: model_fields::<TopicMessageRevision>,
+    },
+    ModelSchema {
+        table: "hidden_messages",
+        partition_keys: &["user_id", "topic_id"],
+        clustering_keys: &[("message_id", "asc")],
+        columns: &[
+            ("user_id", "timeuuid"),
+            ("topic_id", "timeuuid"),
+            ("message_id", "timeuuid"),
+            ("hidden_at", "timestamp"),
+        ],
+        fields: model_fields::<HiddenMessage>,
+    },
+    ModelSchema {
+        table: "thread_messages",
+        partition_keys: &["topic_id", "parent_id"],
+        clustering_keys: &[("message_id", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("parent_id", "timeuuid"),
+            ("message_id", "timeuuid"),
+            ("created_at", "timestamp"),
+        ],
+        fields: model_fields::<ThreadMessage>,
+    },
+    ModelSchema {
+        table: "message_reactions",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[("message_id", "asc"), ("emoji", "asc"), ("user_id", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("message_id", "timeuuid"),
+            ("emoji", "text"),
+            ("user_id", "timeuuid"),
+            ("reacted_at", "timestamp"),
+        ],
+        fields: model_fields::<MessageReaction>,
+    },
+    ModelSchema {
+        table: "read_cursors",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[("user_id", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("user_id", "timeuuid"),
+            ("last_read_message_id", "timeuuid"),
+            ("last_read_at", "timestamp"),
+            ("read_at", "timestamp"),
+        ],
+        fields: model_fields::<ReadCursor>,
+    },
+    ModelSchema {
+        table: "unread_counters",
+        partition_keys: &["user_id"],
+        clustering_keys: &[("topic_id", "asc")],
+        columns: &[
+            ("user_id", "timeuuid"),
+            ("topic_id", "timeuuid"),
+            ("unread", "counter"),
+        ],
+        fields: model_fields::<UnreadCounter>,
+    },
+    ModelSchema {
+        table: "direct_conversations",
+        partition_keys: &["user_a", "user_b"],
+        clustering_keys: &[],
+        columns: &[
+            ("user_a", "text"),
+            ("user_b", "text"),
+            ("topic_id", "timeuuid"),
+            ("created_at", "timestamp"),
+        ],
+        fields: model_fields::<DirectConversation>,
+    },
+    ModelSchema {
+        table: "group_conversations",
+        partition_keys: &["member_key"],
+        clustering_keys: &[],
+        columns: &[
+            ("member_key", "text"),
+            ("members", "list<text>"),
+            ("topic_id", "timeuuid"),
+            ("created_at", "timestamp"),
+        ],
+        fields: model_fields::<GroupConversation>,
+    },
+    ModelSchema {
+        table: "pinned_messages",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[("message_id", "asc")],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("message_id", "timeuuid"),
+            ("pinned_by", "timeuuid"),
+            ("pinned_at", "timestamp"),
+        ],
+        fields: model_fields::<PinnedMessage>,
+    },
+    ModelSchema {
+        table: "user_identities",
+        partition_keys: &["username"],
+        clustering_keys: &[],
+        columns: &[
+            ("username", "text"),
+            ("user_id", "timeuuid"),
+            ("updated_at", "timestamp"),
+        ],
+        fields: model_fields::<UserIdentity>,
+    },
+    ModelSchema {
+        table: "topic_purges",
+        partition_keys: &["topic_id"],
+        clustering_keys: &[],
+        columns: &[
+            ("topic_id", "timeuuid"),
+            ("requested_at", "timestamp"),
+        ],
+        fields: model_fields::<TopicPurge>,
+    },
+];
+
+/// Charybdis maps every struct field to the column of the same name.
+fn model_fields<M: Default + Serialize>() -> AppResult<BTreeSet<String>> {
+    match serde_json::to_value(M::default())? {
+        serde_json::Value::Object(fields) => Ok(fields.keys().cloned().collect()),
+        _ => Ok(BTreeSet::new()),
+    }
+}
+
+impl ModelSchema {
+    /// Every way `live` fails to serve the model. Extra live columns are
+    /// tolerated so a migration can add a column ahead of the code using it.
+    pub fn diff(&self, live: &[LiveColumn]) -> AppResult<Vec<String>> {
+        let table = self.table;
+        let mut problems: Vec<String> = vec![];
+
+        let described: BTreeSet<String> = self.columns.iter().map(|(name, _)| name.to_string()).collect();
+        let fields = (self.fields)()?;
+        for field in fields.difference(&described) {
+            problems.push(format!("{table}: model field {field} has no described column"));
+        }
+        for column in described.difference(&fields) {
+            problems.push(format!("{table}: described column {column} is not a model field"));
+        }
+
+        if live.is_empty() {
+            problems.push(format!("{table}: table does not exist"));
+            return Ok(problems);
+        }
+
+        for (name, cql_type) in self.columns {
+            match live.iter().find(|column| column.name == *name) {
+                Some(column) if column.cql_type != *cql_type => problems.push(format!(
+                    "{table}: column {name} is {}, expected {cql_type}",
+                    column.cql_type
+                )),
+                Some(_) => (),
+                None => problems.push(format!("{table}: column {name} is missing")),
+            }
+        }
+
+        let partition_keys: Vec<&str> = key_columns(live, "partition_key")
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect();
+        if partition_keys != self.partition_keys {
+            problems.push(format!(
+                "{table}: partition key is {partition_keys:?}, expected {:?}",
+                self.partition_keys
+            ));
+        }
+
+        let clustering_keys: Vec<(&str, &str)> = key_columns(live, "clustering")
+            .iter()
+            .map(|column| (column.name.as_str(), column.clustering_order.as_str()))
+            .collect();
+        if clustering_keys != self.clustering_keys {
+            problems.push(format!(
+                "{table}: clustering key is {clustering_keys:?}, expected {:?}",
+                self.clustering_keys
+            ));
+        }
+
+        Ok(problems)
+    }
+}
+
+fn key_columns<'l>(live: &'l [LiveColumn], kind: &str) -> Vec<&'l LiveColumn> {
+    let mut columns: Vec<&LiveColumn> = live.iter().filter(|column| column.kind == kind).collect();
+    columns.sort_by_key(|column| column.position);
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, kind: &str, position: i32, clustering_order: &str, cql_type: &str) -> LiveColumn {
+        LiveColumn {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            position,
+            clustering_order: clustering_order.to_string(),
+            cql_type: cql_type.to_string(),
+        }
+    }
+
+    /// The columns `system_schema.columns` lists for a table created as described.
+    fn live_columns(schema: &ModelSchema) -> Vec<LiveColumn> {
+        schema
+            .columns
+            .iter()
+            .map(|(name, cql_type)| {
+                let partition = schema.partition_keys.iter().position(|key| key == name);
+                let clustering = schema.clustering_keys.iter().position(|(key, _)| key == name);
+                match (partition, clustering) {
+                    (Some(position), _) => column(name, "partition_key", position as i32, "none", cql_type),
+                    (_, Some(position)) => {
+                        let order = schema.clustering_keys[position].1;
+                        column(name, "clustering", position as i32, order, cql_type)
+                    }
+                    _ => column(name, "regular", -1, "none", cql_type),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_model_matches_its_description() {
+        for schema in MODEL_SCHEMAS {
+            assert_eq!(schema.diff(&live_columns(schema)).unwrap(), Vec::<String>::new());
+        }
+    }
+
+    #[test]
+    fn a_missing_table_is_reported() {
+        let schema = &MODEL_SCHEMAS[0];
+        assert_eq!(schema.diff(&[]).unwrap(), vec!["topics: table does not exist".to_string()]);
+    }
+
+    #[test]
+    fn a_changed_type_or_key_order_is_reported() {
+        let schema = &MODEL_SCHEMAS[0];
+        let mut live = live_columns(schema);
+        for column in live.iter_mut() {
+            match column.name.as_str() {
+                "topic_name" => column.cql_type = "int".to_string(),
+                "created_at" => column.clustering_order = "asc".to_string(),
+                _ => (),
+            }
+        }
+        assert_eq!(
+            schema.diff(&live).unwrap(),
+            vec![
+                "topics: column topic_name is int, expected text".to_string(),
+                r#"topics: clustering key is [("created_at", "asc")], expected [("created_at", "desc")]"#.to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn an_extra_live_column_is_tolerated() {
+        let schema = &MODEL_SCHEMAS[0];
+        let mut live = live_columns(schema);
+        live.push(column("added_ahead", "regular", -1, "none", "text"));
+        assert!(schema.diff(&live).unwrap().is_empty());
+    }
+}
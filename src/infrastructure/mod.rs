@@ -1 +1,4 @@
+#[cfg(any(test, feature = "in-memory"))]
+pub mod memory;
+pub mod migration;
 pub mod persistence;
@@ -1,7 +1,31 @@
+use std::env;
 use std::sync::Arc;
-use uptop_core::common::{db_types::CassandraCacheSession, result::AppResult};
+use anyhow::anyhow;
+use scylla::statement::{PagingState, PagingStateResponse};
+use scylla::transport::errors::{DbError, QueryError};
+use scylla::frame::response::result::CqlValue;
+use scylla::{CachingSession, QueryResult, Session};
+use uptop_core::common::result::{AppError, AppResult};
+use crate::application::error::ApplicationError;
+use crate::application::pagination::{decode_page_token, encode_page_token};
+use crate::infrastructure::persistence::direct_conversation_repository::DirectConversationRepo;
+use crate::infrastructure::persistence::group_conversation_repository::GroupConversationRepo;
+use crate::infrastructure::persistence::hidden_message_repository::HiddenMessageRepo;
 use crate::infrastructure::persistence::latest_message_repository::LatestMessageRepo;
+use crate::infrastructure::persistence::message_reaction_repository::MessageReactionRepo;
+use crate::infrastructure::persistence::notification_repository::NotificationRepo;
+use crate::infrastructure::persistence::pinned_message_repository::PinnedMessageRepo;
+use crate::infrastructure::persistence::read_cursor_repository::ReadCursorRepo;
+use crate::infrastructure::persistence::thread_message_repository::ThreadMessageRepo;
+use crate::infrastructure::persistence::topic_invite_repository::TopicInviteRepo;
+use crate::infrastructure::persistence::topic_role_repository::TopicRoleRepo;
 use crate::infrastructure::persistence::topic_message_repository::TopicMessageRepo;
+use crate::infrastructure::persistence::topic_message_revision_repository::TopicMessageRevisionRepo;
+use crate::infrastructure::persistence::topic_purge_repository::TopicPurgeRepo;
+use crate::infrastructure::persistence::topic_user_repository::TopicUserRepo;
+use crate::infrastructure::persistence::unread_counter_repository::UnreadCounterRepo;
+use crate::infrastructure::persistence::user_identity_repository::UserIdentityRepo;
+use crate::infrastructure::persistence::user_topic_repository::UserTopicRepo;
 
 pub(crate) mod topic_repository;
 pub(crate) mod latest_message_repository;
@@ -9,27 +33,153 @@ pub(crate) mod topic_message_repository;
 pub(crate) mod topic_user_repository;
 pub(crate) mod user_topic_repository;
 pub(crate) mod notification_repository;
+pub(crate) mod topic_invite_repository;
+pub(crate) mod topic_role_repository;
+pub(crate) mod topic_message_revision_repository;
+pub(crate) mod hidden_message_repository;
+pub(crate) mod thread_message_repository;
+pub(crate) mod message_reaction_repository;
+pub(crate) mod read_cursor_repository;
+pub(crate) mod unread_counter_repository;
+pub(crate) mod direct_conversation_repository;
+pub(crate) mod group_conversation_repository;
+pub(crate) mod pinned_message_repository;
+pub(crate) mod user_identity_repository;
+pub(crate) mod topic_purge_repository;
+
+/// Shared by every repository. The driver session is `Sync` and pools its own
+/// connections, so concurrent queries need no lock around it.
+pub type MessageSession = Arc<CachingSession>;
+
+const DEFAULT_STATEMENT_CACHE_SIZE: usize = 512;
+
+/// Size of the prepared statement cache, read from `SCYLLA_STATEMENT_CACHE_SIZE`.
+pub fn statement_cache_size() -> usize {
+    env::var("SCYLLA_STATEMENT_CACHE_SIZE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_STATEMENT_CACHE_SIZE)
+}
+
+pub fn create_message_session(session: Session, statement_cache_size: usize) -> MessageSession {
+    Arc::new(CachingSession::from(session, statement_cache_size))
+}
 
 #[derive(Debug)]
 pub struct MessageRepositories {
     pub topic: topic_repository::TopicRepo,
     pub topic_message: TopicMessageRepo,
     pub latest_message: LatestMessageRepo,
+    pub topic_user: TopicUserRepo,
+    pub user_topic: UserTopicRepo,
+    pub notification: NotificationRepo,
+    pub topic_invite: TopicInviteRepo,
+    pub topic_role: TopicRoleRepo,
+    pub topic_message_revision: TopicMessageRevisionRepo,
+    pub hidden_message: HiddenMessageRepo,
+    pub thread_message: ThreadMessageRepo,
+    pub message_reaction: MessageReactionRepo,
+    pub read_cursor: ReadCursorRepo,
+    pub unread_counter: UnreadCounterRepo,
+    pub direct_conversation: DirectConversationRepo,
+    pub group_conversation: GroupConversationRepo,
+    pub pinned_message: PinnedMessageRepo,
+    pub user_identity: UserIdentityRepo,
+    pub topic_purge: TopicPurgeRepo,
 }
 
 impl MessageRepositories {
-    pub fn new(session: CassandraCacheSession) -> Self {
+    pub fn new(session: MessageSession) -> Self {
         Self {
             topic: topic_repository::TopicRepo::new(Arc::clone(&session)),
             topic_message: TopicMessageRepo::new(Arc::clone(&session)),
             latest_message: LatestMessageRepo::new(Arc::clone(&session)),
+            topic_user: TopicUserRepo::new(Arc::clone(&session)),
+            user_topic: UserTopicRepo::new(Arc::clone(&session)),
+            notification: NotificationRepo::new(Arc::clone(&session)),
+            topic_invite: TopicInviteRepo::new(Arc::clone(&session)),
+            topic_role: TopicRoleRepo::new(Arc::clone(&session)),
+            topic_message_revision: TopicMessageRevisionRepo::new(Arc::clone(&session)),
+            hidden_message: HiddenMessageRepo::new(Arc::clone(&session)),
+            thread_message: ThreadMessageRepo::new(Arc::clone(&session)),
+            message_reaction: MessageReactionRepo::new(Arc::clone(&session)),
+            read_cursor: ReadCursorRepo::new(Arc::clone(&session)),
+            unread_counter: UnreadCounterRepo::new(Arc::clone(&session)),
+            direct_conversation: DirectConversationRepo::new(Arc::clone(&session)),
+            group_conversation: GroupConversationRepo::new(Arc::clone(&session)),
+            pinned_message: PinnedMessageRepo::new(Arc::clone(&session)),
+            user_identity: UserIdentityRepo::new(Arc::clone(&session)),
+            topic_purge: TopicPurgeRepo::new(Arc::clone(&session)),
         }
     }
+}
+
+/// Resumes a paged query from the client supplied page token, or starts it.
+pub(crate) fn paging_state(page_token: &Option<String>) -> AppResult<PagingState> {
+    Ok(match decode_page_token(page_token)? {
+        Some(raw_bytes) => PagingState::new_from_raw_bytes(raw_bytes),
+        None => PagingState::start(),
+    })
+}
+
+pub(crate) fn next_page_token(paging_state_response: PagingStateResponse) -> Option<String> {
+    match paging_state_response {
+        PagingStateResponse::HasMorePages { state } => state
+            .as_bytes_slice()
+            .map(|raw_bytes| encode_page_token(raw_bytes)),
+        PagingStateResponse::NoMorePages => None,
+    }
+}
 
-    pub async fn auto_mod_identification_migrate(&self) -> AppResult<()> {
-        self.topic.migrate_topic_table().await?;
-        self.topic_message.migrate_topic_message_table().await?;
-        self.latest_message.migrate_latest_message_table().await?;
-        Ok(())
+/// Rows per batch when a write fans out to one partition per member. Large
+/// topics are written as several small unlogged batches, rather than one that
+/// trips the batch size thresholds.
+pub(crate) const FAN_OUT_CHUNK_SIZE: usize = 50;
+
+/// Whether a conditional (`IF ...`) write took effect. The first column of
+/// its result is `[applied]`, followed by the current row when it did not.
+pub(crate) fn lwt_applied(result: &QueryResult) -> AppResult<bool> {
+    Ok(matches!(
+        result
+            .rows
+            .as_ref()
+            .and_then(|rows| rows.first())
+            .and_then(|row| row.columns.first()),
+        Some(Some(CqlValue::Boolean(true)))
+    ))
+}
+
+/// Classifies a failed query: overload, timeouts and lost connections are
+/// worth retrying and surface as unavailable, anything else is internal.
+pub(crate) fn storage_error<E>(err: E) -> anyhow::Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let err = anyhow::Error::new(err);
+    let transient = err.chain().any(|cause| match cause.downcast_ref::<QueryError>() {
+        Some(query_error) => is_transient(query_error),
+        None => false,
+    });
+
+    match transient {
+        true => anyhow!(ApplicationError::Unavailable),
+        false => anyhow!(AppError::InternalServerError),
     }
 }
+
+fn is_transient(query_error: &QueryError) -> bool {
+    matches!(
+        query_error,
+        QueryError::IoError(_)
+            | QueryError::TimeoutError
+            | QueryError::RequestTimeout(_)
+            | QueryError::DbError(
+                DbError::Unavailable { .. }
+                    | DbError::Overloaded
+                    | DbError::IsBootstrapping
+                    | DbError::ReadTimeout { .. }
+                    | DbError::WriteTimeout { .. },
+                _
+            )
+    )
+}
@@ -0,0 +1,89 @@
+use crate::application::topic::request::RequestGetDirectConversation;
+use crate::domain::direct_conversation::{entity::DirectConversation, repository::DirectConversationRepository};
+use crate::infrastructure::persistence::{lwt_applied, storage_error, MessageSession};
+use anyhow::anyhow;
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use scylla::query::Query;
+use scylla::statement::Consistency;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct DirectConversationRepo {
+    db: MessageSession,
+}
+
+impl DirectConversationRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+
+    /// Reads at serial consistency, so a conversation created by a lightweight
+    /// transaction that just won is seen even before it reached a quorum.
+    async fn find_serial(&self, user_a: &Text, user_b: &Text) -> AppResult<Option<DirectConversation>> {
+        let session = &self.db;
+        let mut statement = Query::new(FIND_DIRECT_CONVERSATION_QUERY);
+        statement.set_consistency(Consistency::Serial);
+        let result = session.execute_unpaged(statement, (user_a, user_b)).await;
+
+        match result {
+            Ok(result) => Ok(result
+                .maybe_first_row_typed::<(Text, Text, Timeuuid, Timestamp)>()?
+                .map(|(user_a, user_b, topic_id, created_at)| DirectConversation {
+                    user_a,
+                    user_b,
+                    topic_id,
+                    created_at,
+                })),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+impl DirectConversationRepository for DirectConversationRepo {
+    async fn find_direct_conversation(
+        &self,
+        query: &RequestGetDirectConversation,
+    ) -> AppResult<Option<DirectConversation>> {
+        self.find_serial(&query.user_a, &query.user_b).await
+    }
+
+    async fn create_direct_conversation(&self, conversation: &DirectConversation) -> AppResult<DirectConversation> {
+        let session = &self.db;
+        let values = (
+            &conversation.user_a,
+            &conversation.user_b,
+            conversation.topic_id,
+            conversation.created_at,
+        );
+        let applied = match session.execute_unpaged(INSERT_DIRECT_CONVERSATION_QUERY, values).await {
+            Ok(result) => lwt_applied(&result)?,
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+        if applied {
+            return Ok(conversation.clone());
+        }
+
+        match self.find_serial(&conversation.user_a, &conversation.user_b).await? {
+            Some(existing) => Ok(existing),
+            None => Err(anyhow!("direct conversation was neither created nor found")),
+        }
+    }
+}
+
+static FIND_DIRECT_CONVERSATION_QUERY: &str = r#"
+    SELECT user_a, user_b, topic_id, created_at
+    FROM uptop.direct_conversations
+    WHERE user_a = ? AND user_b = ?
+"#;
+
+static INSERT_DIRECT_CONVERSATION_QUERY: &str = r#"
+    INSERT INTO uptop.direct_conversations (user_a, user_b, topic_id, created_at)
+    VALUES (?, ?, ?, ?)
+    IF NOT EXISTS
+"#;
@@ -0,0 +1,125 @@
+use crate::application::topic::request::RequestGetGroupConversation;
+use crate::domain::group_conversation::{entity::GroupConversation, repository::GroupConversationRepository};
+use crate::infrastructure::persistence::{lwt_applied, storage_error, MessageSession};
+use anyhow::anyhow;
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use scylla::query::Query;
+use scylla::statement::Consistency;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct GroupConversationRepo {
+    db: MessageSession,
+}
+
+impl GroupConversationRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+
+    /// Reads at serial consistency, like the direct conversation lookup.
+    async fn find_serial(&self, member_key: &Text) -> AppResult<Option<GroupConversation>> {
+        let session = &self.db;
+        let mut statement = Query::new(FIND_GROUP_CONVERSATION_QUERY);
+        statement.set_consistency(Consistency::Serial);
+        let result = session.execute_unpaged(statement, (member_key,)).await;
+
+        match result {
+            Ok(result) => Ok(result
+                .maybe_first_row_typed::<(Text, Vec<Text>, Timeuuid, Timestamp)>()?
+                .map(|(member_key, members, topic_id, created_at)| GroupConversation {
+                    member_key,
+                    members,
+                    topic_id,
+                    created_at,
+                })),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn stored(&self, member_key: &Text) -> AppResult<GroupConversation> {
+        match self.find_serial(member_key).await? {
+            Some(existing) => Ok(existing),
+            None => Err(anyhow!("group conversation was neither written nor found")),
+        }
+    }
+}
+
+impl GroupConversationRepository for GroupConversationRepo {
+    async fn find_group_conversation(
+        &self,
+        query: &RequestGetGroupConversation,
+    ) -> AppResult<Option<GroupConversation>> {
+        self.find_serial(&query.member_key).await
+    }
+
+    async fn create_group_conversation(&self, conversation: &GroupConversation) -> AppResult<GroupConversation> {
+        let session = &self.db;
+        let values = (
+            &conversation.member_key,
+            &conversation.members,
+            conversation.topic_id,
+            conversation.created_at,
+        );
+        let applied = match session.execute_unpaged(INSERT_GROUP_CONVERSATION_QUERY, values).await {
+            Ok(result) => lwt_applied(&result)?,
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+
+        match applied {
+            true => Ok(conversation.clone()),
+            false => self.stored(&conversation.member_key).await,
+        }
+    }
+
+    async fn replace_group_conversation(
+        &self,
+        previous: &GroupConversation,
+        conversation: &GroupConversation,
+    ) -> AppResult<GroupConversation> {
+        let session = &self.db;
+        let values = (
+            &conversation.members,
+            conversation.topic_id,
+            conversation.created_at,
+            &conversation.member_key,
+            previous.topic_id,
+        );
+        let applied = match session.execute_unpaged(REPLACE_GROUP_CONVERSATION_QUERY, values).await {
+            Ok(result) => lwt_applied(&result)?,
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+
+        match applied {
+            true => Ok(conversation.clone()),
+            false => self.stored(&conversation.member_key).await,
+        }
+    }
+}
+
+static FIND_GROUP_CONVERSATION_QUERY: &str = r#"
+    SELECT member_key, members, topic_id, created_at
+    FROM uptop.group_conversations
+    WHERE member_key = ?
+"#;
+
+static INSERT_GROUP_CONVERSATION_QUERY: &str = r#"
+    INSERT INTO uptop.group_conversations (member_key, members, topic_id, created_at)
+    VALUES (?, ?, ?, ?)
+    IF NOT EXISTS
+"#;
+
+static REPLACE_GROUP_CONVERSATION_QUERY: &str = r#"
+    UPDATE uptop.group_conversations SET members = ?, topic_id = ?, created_at = ?
+    WHERE member_key = ?
+    IF topic_id = ?
+"#;
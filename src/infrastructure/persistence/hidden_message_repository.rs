@@ -0,0 +1,59 @@
+use crate::application::topic_message::request::RequestGetHiddenMessages;
+use crate::domain::hidden_message::{entity::HiddenMessage, repository::HiddenMessageRepository};
+use crate::infrastructure::persistence::{storage_error, MessageSession};
+use charybdis::operations::{Find, Insert};
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct HiddenMessageRepo {
+    db: MessageSession,
+}
+
+impl HiddenMessageRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl HiddenMessageRepository for HiddenMessageRepo {
+    async fn find_hidden_messages(&self, query: &RequestGetHiddenMessages) -> AppResult<Vec<HiddenMessage>> {
+        let session = &self.db;
+        let result = HiddenMessage::find_by_partition_key_value((query.user_id, query.topic_id))
+            .execute(session)
+            .await;
+
+        match result {
+            Ok(hidden_messages) => Ok(hidden_messages.try_collect().await?),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn save_hidden_message(&self, hidden_message: &HiddenMessage) -> AppResult<()> {
+        let session = &self.db;
+        match hidden_message.insert().execute(session).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_hidden_messages(&self, query: &RequestGetHiddenMessages) -> AppResult<()> {
+        let session = &self.db;
+        match session.execute_unpaged(DELETE_HIDDEN_MESSAGES_QUERY, (query.user_id, query.topic_id)).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+static DELETE_HIDDEN_MESSAGES_QUERY: &str = r#"
+    DELETE FROM uptop.hidden_messages WHERE user_id = ? AND topic_id = ?
+"#;
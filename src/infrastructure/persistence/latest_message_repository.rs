@@ -1,69 +1,72 @@
-use crate::application::latest_message::request::{RequestGetLatestMessagesByUserId, RequestUpdateLatestMessage};
+use crate::application::latest_message::request::{RequestGetLatestMessage, RequestGetLatestMessagesByUserId, RequestUpdateLatestMessage};
 use crate::{
-    application::latest_message::request::{RequestFindLatestMessageError},
     domain::latest_message::{entity::LatestMessage, repository::LatestMessageRepository},
 };
-use anyhow::anyhow;
-use charybdis::batch::ModelBatch;
-use charybdis::operations::{Find, Insert, Update};
-use charybdis::types::Text;
-use scylla::batch::Batch;
-use std::rc::Rc;
-use charybdis::errors::CharybdisError;
-use scylla::QueryResult;
-use uptop_core::common::{
-    db_types::CassandraCacheSession,
-    result::{AppError, AppResult},
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::infrastructure::persistence::{
+    next_page_token, paging_state, storage_error, MessageSession, FAN_OUT_CHUNK_SIZE,
 };
+use charybdis::batch::ModelBatch;
+use charybdis::operations::{Find, Update};
+use uptop_core::common::result::AppResult;
 
 #[derive(Clone, Debug)]
 pub struct LatestMessageRepo {
-    db: CassandraCacheSession,
+    db: MessageSession,
 }
 
 impl LatestMessageRepo {
-    pub fn new(db: CassandraCacheSession) -> Self {
+    pub fn new(db: MessageSession) -> Self {
         Self { db }
     }
-
-    pub async fn migrate_latest_message_table(&self) -> AppResult<()> {
-        let session = self.db.lock().await;
-        session
-            .execute_unpaged(CREATE_TOPIC_TABLE_QUERY, ())
-            .await?;
-        session.execute_unpaged(CREATE_USER_ID_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_EMAIL_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_NAME_INDEX, ()).await?;
-        Ok(())
-    }
 }
 
 impl LatestMessageRepository for LatestMessageRepo {
     async fn find_latest_message_by_partition_key(
         &self,
         query: &RequestGetLatestMessagesByUserId,
-    ) -> AppResult<Vec<LatestMessage>> {
-        let session = self.db.lock().await;
+    ) -> AppResult<Page<LatestMessage>> {
+        let session = &self.db;
+        let result = LatestMessage::find_by_partition_key_value_paged((query.user_id,))
+            .page_size(page_size_or_default(query.page_size))
+            .paging_state(paging_state(&query.page_token)?)
+            .execute(session)
+            .await;
+
+        match result {
+            Ok((latest_messages, paging_state_response)) => Ok(Page {
+                items: latest_messages.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn find_latest_message(&self, query: &RequestGetLatestMessage) -> AppResult<Option<LatestMessage>> {
+        let session = &self.db;
         let result = LatestMessage {
-            user_id: query.user_id.to_owned(),
+            user_id: query.user_id,
+            topic_id: query.topic_id,
             ..Default::default()
         }
-            .find_by_partition_key()
-            .execute(&session)
+            .maybe_find_by_primary_key()
+            .execute(session)
             .await;
 
         match result {
-            Ok(latest_message) => Ok(latest_message.try_collect().await?),
+            Ok(latest_message) => Ok(latest_message),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindLatestMessageError::LatestMessageNotFound))
+                Err(storage_error(err))
             }
         }
     }
 
-
     async fn update_latest_message(&self, latest_message: &RequestUpdateLatestMessage) -> AppResult<LatestMessage> {
-        let session = self.db.lock().await;
+        let session = &self.db;
         let result = LatestMessage {
             user_id: latest_message.user_id,
             latest_message_id: latest_message.latest_message_id,
@@ -72,57 +75,52 @@ impl LatestMessageRepository for LatestMessageRepo {
             ..Default::default()
         };
 
-        match result.update().execute(&session).await {
-            Ok(_V) => {
+        match result.update().execute(session).await {
+            Ok(_) => {
                 Ok(result)
             }
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindLatestMessageError::LatestMessageNotFound))
+                Err(storage_error(err))
             }
         }
     }
-}
 
-static CREATE_TOPIC_TABLE_QUERY: &str = r#"
-    CREATE TABLE IF NOT EXISTS uptop.latest_messages (
-        latest_message_id timeuuid,
-        latest_message_name text,
-        display_name text,
-        email text,
-        password text,
-        status list<text>,
-        role text,
-        phone_number text,
-        language text,
-        address text,
-        country text,
-        region text,
-        city text,
-        post_code text,
-        owners list<timeuuid>,
-        admins list<timeuuid>,
-        organizations list<timeuuid>,
-        active_organization timeuuid,
-        other_emails list<text>,
-        email_verify_code text,
-        email_verified_at timestamp,
-        password_recovery_code text,
-        password_recovered_at timestamp,
-        created_at timestamp,
-        updated_at timestamp,
-        PRIMARY KEY ((country, region, city), latest_message_id)
-    ) WITH CLUSTERING ORDER BY (latest_message_id DESC);
-"#;
+    async fn upsert_latest_messages(&self, latest_messages: &[LatestMessage]) -> AppResult<()> {
+        let session = &self.db;
+        let result = LatestMessage::unlogged_batch()
+            .chunked_insert(session, latest_messages, FAN_OUT_CHUNK_SIZE)
+            .await;
 
-static CREATE_USER_ID_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_latest_message_id_index ON uptop.latest_messages (latest_message_id);
-"#;
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
 
-static CREATE_USER_EMAIL_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_email_index ON uptop.latest_messages (email);
-"#;
+    async fn remove_latest_messages(&self, keys: &[RequestGetLatestMessage]) -> AppResult<()> {
+        let session = &self.db;
+        let latest_messages: Vec<LatestMessage> = keys
+            .iter()
+            .map(|key| LatestMessage {
+                user_id: key.user_id,
+                topic_id: key.topic_id,
+                ..Default::default()
+            })
+            .collect();
+        let result = LatestMessage::unlogged_batch()
+            .chunked_delete(session, &latest_messages, FAN_OUT_CHUNK_SIZE)
+            .await;
 
-static CREATE_USER_NAME_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_latest_message_name_index ON uptop.latest_messages (latest_message_name);
-"#;
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::application::topic_message::request::{RequestGetMessageReactions, RequestGetTopicMessage};
+use crate::domain::message_reaction::{entity::MessageReaction, repository::MessageReactionRepository};
+use crate::infrastructure::persistence::{storage_error, MessageSession};
+use charybdis::operations::{Find, Insert};
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct MessageReactionRepo {
+    db: MessageSession,
+}
+
+impl MessageReactionRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl MessageReactionRepository for MessageReactionRepo {
+    async fn find_reactions_by_message_ids(
+        &self,
+        query: &RequestGetMessageReactions,
+    ) -> AppResult<Vec<MessageReaction>> {
+        if query.message_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let session = &self.db;
+        let result = MessageReaction::find(FIND_REACTIONS_BY_MESSAGE_IDS_QUERY, (query.topic_id, &query.message_ids))
+            .execute(session)
+            .await;
+
+        match result {
+            Ok(reactions) => Ok(reactions.try_collect().await?),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn save_reaction(&self, reaction: &MessageReaction) -> AppResult<()> {
+        let session = &self.db;
+        match reaction.insert().execute(session).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_reaction(&self, reaction: &MessageReaction) -> AppResult<()> {
+        let session = &self.db;
+        let values = (reaction.topic_id, reaction.message_id, &reaction.emoji, reaction.user_id);
+        match session.execute_unpaged(DELETE_REACTION_QUERY, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_message_reactions(&self, query: &RequestGetTopicMessage) -> AppResult<()> {
+        let session = &self.db;
+        let values = (query.topic_id, query.message_id);
+        match session.execute_unpaged(DELETE_MESSAGE_REACTIONS_QUERY, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_topic_reactions(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let session = &self.db;
+        match session.execute_unpaged(DELETE_TOPIC_REACTIONS_QUERY, (topic_id,)).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+static FIND_REACTIONS_BY_MESSAGE_IDS_QUERY: &str = r#"
+    SELECT topic_id, message_id, emoji, user_id, reacted_at
+    FROM uptop.message_reactions
+    WHERE topic_id = ? AND message_id IN ?
+"#;
+
+static DELETE_REACTION_QUERY: &str = r#"
+    DELETE FROM uptop.message_reactions WHERE topic_id = ? AND message_id = ? AND emoji = ? AND user_id = ?
+"#;
+
+static DELETE_MESSAGE_REACTIONS_QUERY: &str = r#"
+    DELETE FROM uptop.message_reactions WHERE topic_id = ? AND message_id = ?
+"#;
+
+static DELETE_TOPIC_REACTIONS_QUERY: &str = r#"
+    DELETE FROM uptop.message_reactions WHERE topic_id = ?
+"#;
@@ -0,0 +1,82 @@
+use crate::application::topic_message::request::{RequestGetPinnedMessages, RequestGetTopicMessage};
+use crate::domain::pinned_message::{entity::PinnedMessage, repository::PinnedMessageRepository};
+use crate::infrastructure::persistence::{storage_error, MessageSession};
+use charybdis::operations::{Find, Insert};
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct PinnedMessageRepo {
+    db: MessageSession,
+}
+
+impl PinnedMessageRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl PinnedMessageRepository for PinnedMessageRepo {
+    async fn find_pinned_messages(&self, query: &RequestGetPinnedMessages) -> AppResult<Vec<PinnedMessage>> {
+        let session = &self.db;
+        let result = PinnedMessage::find(FIND_PINNED_MESSAGES_QUERY, (query.topic_id,))
+            .execute(session)
+            .await;
+
+        match result {
+            Ok(pinned_messages) => Ok(pinned_messages.try_collect().await?),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn save_pinned_message(&self, pinned_message: &PinnedMessage) -> AppResult<()> {
+        let session = &self.db;
+        match pinned_message.insert().execute(session).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_pinned_message(&self, query: &RequestGetTopicMessage) -> AppResult<()> {
+        let session = &self.db;
+        let values = (query.topic_id, query.message_id);
+        match session.execute_unpaged(DELETE_PINNED_MESSAGE_QUERY, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_topic_pins(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let session = &self.db;
+        match session.execute_unpaged(DELETE_TOPIC_PINS_QUERY, (topic_id,)).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+static FIND_PINNED_MESSAGES_QUERY: &str = r#"
+    SELECT topic_id, message_id, pinned_by, pinned_at
+    FROM uptop.pinned_messages
+    WHERE topic_id = ?
+"#;
+
+static DELETE_PINNED_MESSAGE_QUERY: &str = r#"
+    DELETE FROM uptop.pinned_messages WHERE topic_id = ? AND message_id = ?
+"#;
+
+static DELETE_TOPIC_PINS_QUERY: &str = r#"
+    DELETE FROM uptop.pinned_messages WHERE topic_id = ?
+"#;
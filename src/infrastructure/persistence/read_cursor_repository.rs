@@ -0,0 +1,83 @@
+use crate::application::topic_message::request::{RequestGetReadCursor, RequestGetReadCursorsByTopicId};
+use crate::domain::read_cursor::{entity::ReadCursor, repository::ReadCursorRepository};
+use crate::infrastructure::persistence::{storage_error, MessageSession};
+use charybdis::operations::{Find, Insert};
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct ReadCursorRepo {
+    db: MessageSession,
+}
+
+impl ReadCursorRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl ReadCursorRepository for ReadCursorRepo {
+    async fn find_read_cursor(&self, query: &RequestGetReadCursor) -> AppResult<Option<ReadCursor>> {
+        let session = &self.db;
+        let result = ReadCursor {
+            topic_id: query.topic_id,
+            user_id: query.user_id,
+            ..Default::default()
+        }
+            .maybe_find_by_primary_key()
+            .execute(session)
+            .await;
+
+        match result {
+            Ok(read_cursor) => Ok(read_cursor),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn find_read_cursors_by_topic_id(
+        &self,
+        query: &RequestGetReadCursorsByTopicId,
+    ) -> AppResult<Vec<ReadCursor>> {
+        let session = &self.db;
+        let result = ReadCursor::find_by_partition_key_value((query.topic_id,))
+            .execute(session)
+            .await;
+
+        match result {
+            Ok(read_cursors) => Ok(read_cursors.try_collect().await?),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn save_read_cursor(&self, read_cursor: &ReadCursor) -> AppResult<()> {
+        let session = &self.db;
+        match read_cursor.insert().execute(session).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_topic_read_cursors(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let session = &self.db;
+        match session.execute_unpaged(DELETE_TOPIC_READ_CURSORS_QUERY, (topic_id,)).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+static DELETE_TOPIC_READ_CURSORS_QUERY: &str = r#"
+    DELETE FROM uptop.read_cursors WHERE topic_id = ?
+"#;
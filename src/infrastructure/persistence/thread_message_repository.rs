@@ -0,0 +1,219 @@
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::application::topic_message::request::RequestGetThreadMessages;
+use crate::domain::thread_message::{entity::ThreadMessage, repository::ThreadMessageRepository};
+use crate::domain::topic_message::entity::TopicMessage;
+use crate::infrastructure::persistence::{next_page_token, paging_state, storage_error, MessageSession};
+use charybdis::operations::Find;
+use charybdis::types::{Timestamp, Timeuuid};
+use scylla::batch::Batch;
+use std::collections::{HashMap, HashSet};
+use tokio_stream::StreamExt;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct ThreadMessageRepo {
+    db: MessageSession,
+}
+
+impl ThreadMessageRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl ThreadMessageRepository for ThreadMessageRepo {
+    async fn create_thread_reply(&self, reply: &TopicMessage) -> AppResult<()> {
+        let session = &self.db;
+        let parent_id = reply.parent_id.unwrap_or_default();
+        let mut batch = Batch::default();
+        batch.append_statement(INSERT_THREAD_REPLY_QUERY);
+        batch.append_statement(INSERT_THREAD_MESSAGE_QUERY);
+
+        let values = (
+            (
+                reply.topic_id,
+                reply.message_id,
+                reply.from_user_id,
+                &reply.message,
+                reply.created_at,
+                &reply.kind,
+                reply.parent_id,
+                reply.quoted_message_id,
+                reply.quoted_from_user_id,
+                &reply.quoted_message,
+            ),
+            (reply.topic_id, parent_id, reply.message_id, reply.created_at),
+        );
+        match session.batch(&batch, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_thread_reply(&self, reply: &TopicMessage) -> AppResult<()> {
+        let session = &self.db;
+        let values = (reply.topic_id, reply.parent_id.unwrap_or_default(), reply.message_id);
+        match session.execute_unpaged(DELETE_THREAD_MESSAGE_QUERY, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn find_thread_messages_by_partition_key(
+        &self,
+        query: &RequestGetThreadMessages,
+    ) -> AppResult<Page<TopicMessage>> {
+        let session = &self.db;
+        let result = ThreadMessage::find_by_partition_key_value_paged((query.topic_id, query.parent_id))
+            .page_size(page_size_or_default(query.page_size))
+            .paging_state(paging_state(&query.page_token)?)
+            .execute(session)
+            .await;
+
+        let (entries, next_page_token) = match result {
+            Ok((entries, paging_state_response)) => (
+                entries.collect::<Result<Vec<_>, _>>()?,
+                next_page_token(paging_state_response),
+            ),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+        if entries.is_empty() {
+            return Ok(Page { items: vec![], next_page_token });
+        }
+
+        // The index holds the order, the bodies live in `topic_replies`.
+        let message_ids: Vec<Timeuuid> = entries.iter().map(|entry| entry.message_id).collect();
+        let result = TopicMessage::find(FIND_TOPIC_REPLIES_BY_IDS_QUERY, (query.topic_id, message_ids))
+            .execute(session)
+            .await;
+        let mut replies: HashMap<Timeuuid, TopicMessage> = match result {
+            Ok(replies) => replies
+                .try_collect()
+                .await?
+                .into_iter()
+                .map(|reply: TopicMessage| (reply.message_id, reply))
+                .collect(),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+
+        Ok(Page {
+            items: entries
+                .iter()
+                .filter_map(|entry| replies.remove(&entry.message_id))
+                .collect(),
+            next_page_token,
+        })
+    }
+
+    async fn refresh_reply_stats(&self, parent: &TopicMessage) -> AppResult<TopicMessage> {
+        let session = &self.db;
+        // Recounting rather than incrementing lets a later reply correct a
+        // count written by a racing one.
+        let result = session
+            .execute_unpaged(COUNT_THREAD_MESSAGES_QUERY, (parent.topic_id, parent.message_id))
+            .await;
+        let (reply_count, last_reply_at) = match result {
+            Ok(result) => result.single_row_typed::<(i64, Option<Timestamp>)>()?,
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+
+        let reply_count = reply_count as i32;
+        let values = (reply_count, last_reply_at, parent.topic_id, parent.message_id);
+        match session.execute_unpaged(UPDATE_REPLY_STATS_QUERY, values).await {
+            Ok(_) => Ok(TopicMessage {
+                reply_count: Some(reply_count),
+                last_reply_at,
+                ..parent.clone()
+            }),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_topic_threads(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let session = &self.db;
+        let result = session.execute_iter(FIND_TOPIC_REPLY_PARENTS_QUERY, (topic_id,)).await;
+        let mut parents = match result {
+            Ok(rows) => rows.into_typed::<(Option<Timeuuid>,)>(),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+
+        // Every reply repeats its parent; each thread is deleted once.
+        let mut removed: HashSet<Timeuuid> = HashSet::new();
+        while let Some(row) = parents.next().await {
+            let (parent_id,) = row?;
+            let Some(parent_id) = parent_id else { continue };
+            if !removed.insert(parent_id) {
+                continue;
+            }
+            if let Err(err) = session
+                .execute_unpaged(DELETE_THREAD_QUERY, (topic_id, parent_id))
+                .await
+            {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        }
+        Ok(())
+    }
+}
+
+static INSERT_THREAD_REPLY_QUERY: &str = r#"
+    INSERT INTO uptop.topic_replies (
+        topic_id, message_id, from_user_id, message, created_at, kind, parent_id,
+        quoted_message_id, quoted_from_user_id, quoted_message
+    )
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+
+static INSERT_THREAD_MESSAGE_QUERY: &str = r#"
+    INSERT INTO uptop.thread_messages (topic_id, parent_id, message_id, created_at) VALUES (?, ?, ?, ?)
+"#;
+
+static DELETE_THREAD_MESSAGE_QUERY: &str = r#"
+    DELETE FROM uptop.thread_messages WHERE topic_id = ? AND parent_id = ? AND message_id = ?
+"#;
+
+static FIND_TOPIC_REPLIES_BY_IDS_QUERY: &str = r#"
+    SELECT topic_id, message_id, from_user_id, message, created_at, kind, edited_at, deleted_at, deleted_by,
+        parent_id, reply_count, last_reply_at, quoted_message_id, quoted_from_user_id, quoted_message,
+        forwarded_from_topic_id, forwarded_from_message_id, forwarded_from_user_id
+    FROM uptop.topic_replies
+    WHERE topic_id = ? AND message_id IN ?
+"#;
+
+static COUNT_THREAD_MESSAGES_QUERY: &str = r#"
+    SELECT COUNT(*), MAX(created_at) FROM uptop.thread_messages WHERE topic_id = ? AND parent_id = ?
+"#;
+
+static UPDATE_REPLY_STATS_QUERY: &str = r#"
+    UPDATE uptop.topic_messages SET reply_count = ?, last_reply_at = ? WHERE topic_id = ? AND message_id = ?
+"#;
+
+static FIND_TOPIC_REPLY_PARENTS_QUERY: &str = r#"
+    SELECT parent_id FROM uptop.topic_replies WHERE topic_id = ?
+"#;
+
+static DELETE_THREAD_QUERY: &str = r#"
+    DELETE FROM uptop.thread_messages WHERE topic_id = ? AND parent_id = ?
+"#;
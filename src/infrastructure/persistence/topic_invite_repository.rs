@@ -0,0 +1,106 @@
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::application::topic_user::request::{RequestGetInvitesByUsername, RequestGetTopicMember};
+use crate::domain::topic_invite::{entity::TopicInvite, repository::TopicInviteRepository};
+use crate::infrastructure::persistence::{next_page_token, paging_state, storage_error, MessageSession};
+use charybdis::operations::{Find, Insert};
+use charybdis::types::{Text, Timeuuid};
+use tokio_stream::StreamExt;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct TopicInviteRepo {
+    db: MessageSession,
+}
+
+impl TopicInviteRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl TopicInviteRepository for TopicInviteRepo {
+    async fn find_topic_invite(&self, query: &RequestGetTopicMember) -> AppResult<Option<TopicInvite>> {
+        let session = &self.db;
+        let result = TopicInvite {
+            username: query.username.to_owned(),
+            topic_id: query.topic_id,
+            ..Default::default()
+        }
+            .maybe_find_by_primary_key()
+            .execute(session)
+            .await;
+
+        match result {
+            Ok(topic_invite) => Ok(topic_invite),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn find_topic_invites_by_partition_key(
+        &self,
+        query: &RequestGetInvitesByUsername,
+    ) -> AppResult<Page<TopicInvite>> {
+        let session = &self.db;
+        let result = TopicInvite::find_by_partition_key_value_paged((query.username.to_owned(),))
+            .page_size(page_size_or_default(query.page_size))
+            .paging_state(paging_state(&query.page_token)?)
+            .execute(session)
+            .await;
+
+        match result {
+            Ok((topic_invites, paging_state_response)) => Ok(Page {
+                items: topic_invites.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn save_topic_invite(&self, topic_invite: &TopicInvite) -> AppResult<()> {
+        let session = &self.db;
+        match topic_invite.insert().execute(session).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_topic_invites(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let session = &self.db;
+        let result = session.execute_iter(FIND_TOPIC_INVITEES_QUERY, (topic_id,)).await;
+        let mut invitees = match result {
+            Ok(rows) => rows.into_typed::<(Text,)>(),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+        while let Some(row) = invitees.next().await {
+            let (username,) = row?;
+            if let Err(err) = session
+                .execute_unpaged(DELETE_TOPIC_INVITE_QUERY, (&username, topic_id))
+                .await
+            {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        }
+        Ok(())
+    }
+}
+
+static FIND_TOPIC_INVITEES_QUERY: &str = r#"
+    SELECT username FROM uptop.topic_invites WHERE topic_id = ?
+"#;
+
+static DELETE_TOPIC_INVITE_QUERY: &str = r#"
+    DELETE FROM uptop.topic_invites WHERE username = ? AND topic_id = ?
+"#;
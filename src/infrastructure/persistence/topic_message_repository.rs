@@ -1,119 +1,353 @@
-use crate::application::topic_message::request::{RequestGetMessagesByTopicId, RequestUpdateTopicMessage};
+use crate::application::topic_message::request::{RequestGetMessagesByTopicId, RequestGetTopicMessage, RequestUpdateTopicMessage};
 use crate::{
-    application::topic_message::request::{RequestFindLatestMessageError},
     domain::topic_message::{entity::TopicMessage, repository::TopicMessageRepository},
 };
+use crate::application::error::ApplicationError;
+use crate::domain::topic_message_revision::entity::TopicMessageRevision;
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::infrastructure::persistence::{next_page_token, paging_state, storage_error, MessageSession};
 use anyhow::anyhow;
-use charybdis::operations::{Find, Update};
-use uptop_core::common::{
-    db_types::CassandraCacheSession,
-    result::{AppError, AppResult},
-};
+use charybdis::operations::{Find, Insert};
+use charybdis::types::{Timestamp, Timeuuid};
+use scylla::batch::Batch;
+use tokio_stream::StreamExt;
+use uptop_core::common::result::AppResult;
 
 #[derive(Clone, Debug)]
 pub struct TopicMessageRepo {
-    db: CassandraCacheSession,
+    db: MessageSession,
 }
 
 impl TopicMessageRepo {
-    pub fn new(db: CassandraCacheSession) -> Self {
+    pub fn new(db: MessageSession) -> Self {
         Self { db }
     }
-
-    pub async fn migrate_topic_message_table(&self) -> AppResult<()> {
-        let session = self.db.lock().await;
-        session.execute_unpaged(CREATE_TOPIC_TABLE_QUERY, ()).await?;
-        session.execute_unpaged(CREATE_USER_ID_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_EMAIL_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_NAME_INDEX, ()).await?;
-        Ok(())
-    }
 }
 
 impl TopicMessageRepository for TopicMessageRepo {
+    async fn create_topic_message<'c>(&self, topic_message: &'c TopicMessage) -> AppResult<&'c TopicMessage> {
+        let session = &self.db;
+        match topic_message.insert().execute(session).await {
+            Ok(_) => Ok(topic_message),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
     async fn find_topic_message_by_partition_key(
         &self,
         query: &RequestGetMessagesByTopicId,
-    ) -> AppResult<Vec<TopicMessage>> {
-        let session = self.db.lock().await;
+    ) -> AppResult<Page<TopicMessage>> {
+        let session = &self.db;
+        let paging_state = paging_state(&query.page_token)?;
+        let page_size = page_size_or_default(query.page_size);
+        let result = match (query.before, query.after) {
+            (None, None) => {
+                TopicMessage::find_by_partition_key_value_paged((query.topic_id,))
+                    .page_size(page_size)
+                    .paging_state(paging_state)
+                    .execute(session)
+                    .await
+            }
+            (Some(before), None) => {
+                TopicMessage::find_paged(FIND_TOPIC_MESSAGES_BEFORE_QUERY, (query.topic_id, before), paging_state)
+                    .page_size(page_size)
+                    .execute(session)
+                    .await
+            }
+            (None, Some(after)) => {
+                TopicMessage::find_paged(FIND_TOPIC_MESSAGES_AFTER_QUERY, (query.topic_id, after), paging_state)
+                    .page_size(page_size)
+                    .execute(session)
+                    .await
+            }
+            (Some(before), Some(after)) => {
+                TopicMessage::find_paged(
+                    FIND_TOPIC_MESSAGES_BETWEEN_QUERY,
+                    (query.topic_id, after, before),
+                    paging_state,
+                )
+                    .page_size(page_size)
+                    .execute(session)
+                    .await
+            }
+        };
+
+        match result {
+            Ok((topic_messages, paging_state_response)) => Ok(Page {
+                items: topic_messages.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    /// Replies are kept apart from the timeline, so a message missing from
+    /// it is looked up among them.
+    async fn find_topic_message(&self, query: &RequestGetTopicMessage) -> AppResult<Option<TopicMessage>> {
+        let session = &self.db;
         let result = TopicMessage {
-            topic_id: query.topic_id.to_owned(),
+            topic_id: query.topic_id,
+            message_id: query.message_id,
             ..Default::default()
         }
-            .find_by_partition_key()
-            .execute(&session)
+            .maybe_find_by_primary_key()
+            .execute(session)
             .await;
 
         match result {
-            Ok(topic_message) => Ok(topic_message.try_collect().await?),
+            Ok(Some(topic_message)) => return Ok(Some(topic_message)),
+            Ok(None) => (),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindLatestMessageError::LatestMessageNotFound))
+                return Err(storage_error(err));
             }
         }
-    }
 
+        let result = TopicMessage::find(FIND_TOPIC_REPLY_QUERY, (query.topic_id, query.message_id))
+            .execute(session)
+            .await;
+        match result {
+            Ok(replies) => Ok(replies.try_collect().await?.into_iter().next()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
 
-    async fn update_topic_message(&self, topic_message: &RequestUpdateTopicMessage) -> AppResult<TopicMessage> {
-        let session = self.db.lock().await;
-        let result = TopicMessage {
-            from_user_id: topic_message.from_user_id,
+    async fn update_topic_message(
+        &self,
+        topic_message: &RequestUpdateTopicMessage,
+        revision: &TopicMessageRevision,
+    ) -> AppResult<TopicMessage> {
+        let session = &self.db;
+        // An UPDATE on a missing row would create it, so make sure the message exists first.
+        let query = RequestGetTopicMessage {
             topic_id: topic_message.topic_id,
-            message: (*topic_message.message).parse()?,
-            ..Default::default()
+            message_id: topic_message.message_id,
         };
+        let mut updated = match self.find_topic_message(&query).await? {
+            Some(updated) => updated,
+            None => return Err(anyhow!(ApplicationError::NotFound { resource: "topic message" })),
+        };
+
+        // Only the body is editable, the author and creation time stay as written.
+        // Logged so the body never changes without its revision being recorded.
+        let mut batch = Batch::default();
+        batch.append_statement(INSERT_TOPIC_MESSAGE_REVISION_QUERY);
+        batch.append_statement(match updated.is_reply() {
+            true => UPDATE_TOPIC_REPLY_BODY_QUERY,
+            false => UPDATE_TOPIC_MESSAGE_BODY_QUERY,
+        });
+
+        let values = (
+            (
+                revision.topic_id,
+                revision.message_id,
+                revision.revision_id,
+                revision.edited_by,
+                revision.edited_at,
+                &revision.message,
+            ),
+            (
+                &topic_message.message,
+                revision.edited_at,
+                topic_message.topic_id,
+                topic_message.message_id,
+            ),
+        );
+        match session.batch(&batch, values).await {
+            Ok(_) => {
+                updated.message = topic_message.message.to_owned();
+                updated.edited_at = Some(revision.edited_at);
+                Ok(updated)
+            }
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn delete_topic_message(&self, tombstone: &TopicMessage) -> AppResult<()> {
+        let session = &self.db;
+        // Old bodies go with the message, so the revisions are dropped in the same write.
+        let mut batch = Batch::default();
+        batch.append_statement(match tombstone.is_reply() {
+            true => UPDATE_TOPIC_REPLY_TOMBSTONE_QUERY,
+            false => UPDATE_TOPIC_MESSAGE_TOMBSTONE_QUERY,
+        });
+        batch.append_statement(DELETE_TOPIC_MESSAGE_REVISIONS_QUERY);
+
+        let values = (
+            (
+                &tombstone.message,
+                &tombstone.quoted_message,
+                tombstone.deleted_at,
+                tombstone.deleted_by,
+                tombstone.topic_id,
+                tombstone.message_id,
+            ),
+            (tombstone.topic_id, tombstone.message_id),
+        );
+        match session.batch(&batch, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    /// Deletes from both tables rather than reading first which one holds the message.
+    async fn purge_topic_message(&self, query: &RequestGetTopicMessage) -> AppResult<()> {
+        let session = &self.db;
+        let mut batch = Batch::default();
+        batch.append_statement(DELETE_TOPIC_MESSAGE_QUERY);
+        batch.append_statement(DELETE_TOPIC_REPLY_QUERY);
+        batch.append_statement(DELETE_TOPIC_MESSAGE_REVISIONS_QUERY);
+
+        let values = (
+            (query.topic_id, query.message_id),
+            (query.topic_id, query.message_id),
+            (query.topic_id, query.message_id),
+        );
+        match session.batch(&batch, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
 
-        match result.update().execute(&session).await {
-            Ok(_V) => {
-                Ok(result)
+    async fn purge_topic_messages(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let session = &self.db;
+        // Only edited messages have revisions; skipping the others saves a
+        // partition tombstone per message.
+        for query in [FIND_TOPIC_MESSAGE_EDITS_QUERY, FIND_TOPIC_REPLY_EDITS_QUERY] {
+            let result = session.execute_iter(query, (topic_id,)).await;
+            let mut edits = match result {
+                Ok(rows) => rows.into_typed::<(Timeuuid, Option<Timestamp>)>(),
+                Err(err) => {
+                    tracing::error!("{err:?}");
+                    return Err(storage_error(err));
+                }
+            };
+            while let Some(row) = edits.next().await {
+                let (message_id, edited_at) = row?;
+                if edited_at.is_none() {
+                    continue;
+                }
+                if let Err(err) = session
+                    .execute_unpaged(DELETE_TOPIC_MESSAGE_REVISIONS_QUERY, (topic_id, message_id))
+                    .await
+                {
+                    tracing::error!("{err:?}");
+                    return Err(storage_error(err));
+                }
             }
+        }
+
+        let mut batch = Batch::default();
+        batch.append_statement(DELETE_TOPIC_MESSAGES_QUERY);
+        batch.append_statement(DELETE_TOPIC_REPLIES_QUERY);
+        match session.batch(&batch, ((topic_id,), (topic_id,))).await {
+            Ok(_) => Ok(()),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindLatestMessageError::LatestMessageNotFound))
+                Err(storage_error(err))
             }
         }
     }
 }
 
-static CREATE_TOPIC_TABLE_QUERY: &str = r#"
-    CREATE TABLE IF NOT EXISTS uptop.topic_messages (
-        topic_message_id timeuuid,
-        topic_message_name text,
-        display_name text,
-        email text,
-        password text,
-        status list<text>,
-        role text,
-        phone_number text,
-        language text,
-        address text,
-        country text,
-        region text,
-        city text,
-        post_code text,
-        owners list<timeuuid>,
-        admins list<timeuuid>,
-        organizations list<timeuuid>,
-        active_organization timeuuid,
-        other_emails list<text>,
-        email_verify_code text,
-        email_verified_at timestamp,
-        password_recovery_code text,
-        password_recovered_at timestamp,
-        created_at timestamp,
-        updated_at timestamp,
-        PRIMARY KEY ((country, region, city), topic_message_id)
-    ) WITH CLUSTERING ORDER BY (topic_message_id DESC);
-"#;
-
-static CREATE_USER_ID_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_topic_message_id_index ON uptop.topic_messages (topic_message_id);
-"#;
-
-static CREATE_USER_EMAIL_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_email_index ON uptop.topic_messages (email);
-"#;
-
-static CREATE_USER_NAME_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_topic_message_name_index ON uptop.topic_messages (topic_message_name);
+static FIND_TOPIC_MESSAGES_BEFORE_QUERY: &str = r#"
+    SELECT topic_id, message_id, from_user_id, message, created_at, kind, edited_at, deleted_at, deleted_by,
+        parent_id, reply_count, last_reply_at, quoted_message_id, quoted_from_user_id, quoted_message,
+        forwarded_from_topic_id, forwarded_from_message_id, forwarded_from_user_id
+    FROM uptop.topic_messages
+    WHERE topic_id = ? AND message_id < ?
+"#;
+
+static FIND_TOPIC_MESSAGES_AFTER_QUERY: &str = r#"
+    SELECT topic_id, message_id, from_user_id, message, created_at, kind, edited_at, deleted_at, deleted_by,
+        parent_id, reply_count, last_reply_at, quoted_message_id, quoted_from_user_id, quoted_message,
+        forwarded_from_topic_id, forwarded_from_message_id, forwarded_from_user_id
+    FROM uptop.topic_messages
+    WHERE topic_id = ? AND message_id > ?
+    ORDER BY message_id ASC
+"#;
+
+static FIND_TOPIC_MESSAGES_BETWEEN_QUERY: &str = r#"
+    SELECT topic_id, message_id, from_user_id, message, created_at, kind, edited_at, deleted_at, deleted_by,
+        parent_id, reply_count, last_reply_at, quoted_message_id, quoted_from_user_id, quoted_message,
+        forwarded_from_topic_id, forwarded_from_message_id, forwarded_from_user_id
+    FROM uptop.topic_messages
+    WHERE topic_id = ? AND message_id > ? AND message_id < ?
+"#;
+
+static FIND_TOPIC_REPLY_QUERY: &str = r#"
+    SELECT topic_id, message_id, from_user_id, message, created_at, kind, edited_at, deleted_at, deleted_by,
+        parent_id, reply_count, last_reply_at, quoted_message_id, quoted_from_user_id, quoted_message,
+        forwarded_from_topic_id, forwarded_from_message_id, forwarded_from_user_id
+    FROM uptop.topic_replies
+    WHERE topic_id = ? AND message_id = ?
+"#;
+
+static FIND_TOPIC_MESSAGE_EDITS_QUERY: &str = r#"
+    SELECT message_id, edited_at FROM uptop.topic_messages WHERE topic_id = ?
+"#;
+
+static FIND_TOPIC_REPLY_EDITS_QUERY: &str = r#"
+    SELECT message_id, edited_at FROM uptop.topic_replies WHERE topic_id = ?
+"#;
+
+static UPDATE_TOPIC_REPLY_BODY_QUERY: &str = r#"
+    UPDATE uptop.topic_replies SET message = ?, edited_at = ? WHERE topic_id = ? AND message_id = ?
+"#;
+
+static UPDATE_TOPIC_MESSAGE_BODY_QUERY: &str = r#"
+    UPDATE uptop.topic_messages SET message = ?, edited_at = ? WHERE topic_id = ? AND message_id = ?
+"#;
+
+static INSERT_TOPIC_MESSAGE_REVISION_QUERY: &str = r#"
+    INSERT INTO uptop.topic_message_revisions (topic_id, message_id, revision_id, edited_by, edited_at, message)
+    VALUES (?, ?, ?, ?, ?, ?)
+"#;
+
+static UPDATE_TOPIC_MESSAGE_TOMBSTONE_QUERY: &str = r#"
+    UPDATE uptop.topic_messages SET message = ?, quoted_message = ?, deleted_at = ?, deleted_by = ?
+    WHERE topic_id = ? AND message_id = ?
+"#;
+
+static UPDATE_TOPIC_REPLY_TOMBSTONE_QUERY: &str = r#"
+    UPDATE uptop.topic_replies SET message = ?, quoted_message = ?, deleted_at = ?, deleted_by = ?
+    WHERE topic_id = ? AND message_id = ?
+"#;
+
+static DELETE_TOPIC_MESSAGE_QUERY: &str = r#"
+    DELETE FROM uptop.topic_messages WHERE topic_id = ? AND message_id = ?
+"#;
+
+static DELETE_TOPIC_REPLY_QUERY: &str = r#"
+    DELETE FROM uptop.topic_replies WHERE topic_id = ? AND message_id = ?
+"#;
+
+static DELETE_TOPIC_MESSAGES_QUERY: &str = r#"
+    DELETE FROM uptop.topic_messages WHERE topic_id = ?
+"#;
+
+static DELETE_TOPIC_REPLIES_QUERY: &str = r#"
+    DELETE FROM uptop.topic_replies WHERE topic_id = ?
+"#;
+
+static DELETE_TOPIC_MESSAGE_REVISIONS_QUERY: &str = r#"
+    DELETE FROM uptop.topic_message_revisions WHERE topic_id = ? AND message_id = ?
 "#;
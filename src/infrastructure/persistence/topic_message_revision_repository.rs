@@ -0,0 +1,44 @@
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::application::topic_message::request::RequestGetMessageRevisions;
+use crate::domain::topic_message_revision::{
+    entity::TopicMessageRevision, repository::TopicMessageRevisionRepository,
+};
+use crate::infrastructure::persistence::{next_page_token, paging_state, storage_error, MessageSession};
+use charybdis::operations::Find;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct TopicMessageRevisionRepo {
+    db: MessageSession,
+}
+
+impl TopicMessageRevisionRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl TopicMessageRevisionRepository for TopicMessageRevisionRepo {
+    async fn find_revisions_by_partition_key(
+        &self,
+        query: &RequestGetMessageRevisions,
+    ) -> AppResult<Page<TopicMessageRevision>> {
+        let session = &self.db;
+        let result = TopicMessageRevision::find_by_partition_key_value_paged((query.topic_id, query.message_id))
+            .page_size(page_size_or_default(query.page_size))
+            .paging_state(paging_state(&query.page_token)?)
+            .execute(session)
+            .await;
+
+        match result {
+            Ok((revisions, paging_state_response)) => Ok(Page {
+                items: revisions.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::domain::topic_purge::{entity::TopicPurge, repository::TopicPurgeRepository};
+use crate::infrastructure::persistence::{storage_error, MessageSession};
+use charybdis::types::{Timestamp, Timeuuid};
+use tokio_stream::StreamExt;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct TopicPurgeRepo {
+    db: MessageSession,
+}
+
+impl TopicPurgeRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl TopicPurgeRepository for TopicPurgeRepo {
+    async fn find_topic_purges(&self) -> AppResult<Vec<TopicPurge>> {
+        let session = &self.db;
+        let result = session.execute_iter(FIND_TOPIC_PURGES_QUERY, ()).await;
+        let mut rows = match result {
+            Ok(rows) => rows.into_typed::<(Timeuuid, Timestamp)>(),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+
+        let mut topic_purges: Vec<TopicPurge> = vec![];
+        while let Some(row) = rows.next().await {
+            let (topic_id, requested_at) = row?;
+            topic_purges.push(TopicPurge { topic_id, requested_at });
+        }
+        Ok(topic_purges)
+    }
+
+    async fn save_topic_purge(&self, topic_purge: &TopicPurge) -> AppResult<()> {
+        let session = &self.db;
+        let values = (topic_purge.topic_id, topic_purge.requested_at);
+        match session.execute_unpaged(INSERT_TOPIC_PURGE_QUERY, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_topic_purge(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let session = &self.db;
+        match session.execute_unpaged(DELETE_TOPIC_PURGE_QUERY, (topic_id,)).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+static FIND_TOPIC_PURGES_QUERY: &str = r#"
+    SELECT topic_id, requested_at FROM uptop.topic_purges
+"#;
+
+static INSERT_TOPIC_PURGE_QUERY: &str = r#"
+    INSERT INTO uptop.topic_purges (topic_id, requested_at) VALUES (?, ?)
+"#;
+
+static DELETE_TOPIC_PURGE_QUERY: &str = r#"
+    DELETE FROM uptop.topic_purges WHERE topic_id = ?
+"#;
@@ -1,51 +1,37 @@
-use crate::application::topic::request::{
-    RequestGetTopicByIndexKey, RequestGetTopicByPrimaryKey, RequestUpdateTopic,
-};
+use crate::application::topic::request::{RequestGetTopicByIndexKey, RequestGetTopicByPrimaryKey};
 use crate::{
-    application::topic::request::{RequestFindTopicError, RequestGetTopicByPartitionKey},
+    application::topic::request::RequestGetTopicByPartitionKey,
     domain::topic::{entity::Topic, repository::TopicRepository},
 };
-use anyhow::anyhow;
-use charybdis::batch::ModelBatch;
-use charybdis::operations::{Find, Insert, Update};
-use charybdis::types::Text;
-use scylla::batch::Batch;
-use std::rc::Rc;
-use uptop_core::common::{
-    db_types::CassandraCacheSession,
-    result::{AppError, AppResult},
+use crate::application::error::ApplicationError;
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::infrastructure::persistence::{
+    lwt_applied, next_page_token, paging_state, storage_error, MessageSession,
 };
+use anyhow::anyhow;
+use charybdis::operations::{Find, Insert};
+use charybdis::types::Timestamp;
+use uptop_core::common::result::AppResult;
 
 #[derive(Clone, Debug)]
 pub struct TopicRepo {
-    db: CassandraCacheSession,
+    db: MessageSession,
 }
 
 impl TopicRepo {
-    pub fn new(db: CassandraCacheSession) -> Self {
+    pub fn new(db: MessageSession) -> Self {
         Self { db }
     }
-
-    pub async fn migrate_topic_table(&self) -> AppResult<()> {
-        let session = self.db.lock().await;
-        session
-            .execute_unpaged(CREATE_TOPIC_TABLE_QUERY, ())
-            .await?;
-        session.execute_unpaged(CREATE_USER_ID_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_EMAIL_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_NAME_INDEX, ()).await?;
-        Ok(())
-    }
 }
 
 impl TopicRepository for TopicRepo {
     async fn create_topic<'c>(&self, topic: &'c Topic) -> AppResult<&'c Topic> {
-        let session = self.db.lock().await;
-        match topic.insert().execute(&session).await {
+        let session = &self.db;
+        match topic.insert().execute(session).await {
             Ok(_) => Ok(topic),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(AppError::InternalServerError))
+                Err(storage_error(err))
             }
         }
     }
@@ -53,21 +39,22 @@ impl TopicRepository for TopicRepo {
     async fn find_topic_by_partition_key(
         &self,
         query: &RequestGetTopicByPartitionKey,
-    ) -> AppResult<Vec<Topic>> {
-        let session = self.db.lock().await;
-        let result = Topic {
-            topic_id: query.topic_id.to_owned(),
-            ..Default::default()
-        }
-            .find_by_partition_key()
-            .execute(&session)
+    ) -> AppResult<Page<Topic>> {
+        let session = &self.db;
+        let result = Topic::find_by_partition_key_value_paged((query.topic_id,))
+            .page_size(page_size_or_default(query.page_size))
+            .paging_state(paging_state(&query.page_token)?)
+            .execute(session)
             .await;
 
         match result {
-            Ok(topic) => Ok(topic.try_collect().await?),
+            Ok((topics, paging_state_response)) => Ok(Page {
+                items: topics.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindTopicError::TopicNotFound))
+                Err(storage_error(err))
             }
         }
     }
@@ -76,21 +63,22 @@ impl TopicRepository for TopicRepo {
         &self,
         request_topic_by_primary_key: &RequestGetTopicByPrimaryKey,
     ) -> AppResult<Topic> {
-        let session = self.db.lock().await;
+        let session = &self.db;
         let topic = Topic {
             topic_id: request_topic_by_primary_key.topic_id.to_owned(),
             created_at: request_topic_by_primary_key.created_at,
             ..Default::default()
         }
-            .find_by_primary_key()
-            .execute(&session)
+            .maybe_find_by_primary_key()
+            .execute(session)
             .await;
 
         match topic {
-            Ok(topic) => Ok(topic),
+            Ok(Some(topic)) => Ok(topic),
+            Ok(None) => Err(anyhow!(ApplicationError::NotFound { resource: "topic" })),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(AppError::InternalServerError))
+                Err(storage_error(err))
             }
         }
     }
@@ -98,99 +86,64 @@ impl TopicRepository for TopicRepo {
     async fn find_topic_by_index_key(
         &self,
         query: &RequestGetTopicByIndexKey,
-    ) -> AppResult<Vec<Topic>> {
-        let session = self.db.lock().await;
-        let results = Topic::find_by_topic_name((*query.topic_name).to_string())
-            .execute(&session)
+    ) -> AppResult<Page<Topic>> {
+        let session = &self.db;
+        let results = Topic::find_paged(
+            FIND_TOPICS_BY_NAME_QUERY,
+            (query.topic_name.to_owned(),),
+            paging_state(&query.page_token)?,
+        )
+            .page_size(page_size_or_default(query.page_size))
+            .execute(session)
             .await;
 
         match results {
-            Ok(value) => match value.try_collect().await {
-                Ok(val) => Ok(val),
-                Err(err) => {
-                    tracing::error!("{err:?}");
-                    Err(anyhow!(AppError::InternalServerError))
-                }
-            },
+            Ok((topics, paging_state_response)) => Ok(Page {
+                items: topics.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(AppError::InternalServerError))
+                Err(storage_error(err))
             }
         }
     }
 
-    async fn update_topic(&self, topic: &RequestUpdateTopic) -> AppResult<Topic> {
-        let session = self.db.lock().await;
-        let mut batch = Topic::batch();
-        if topic.push_to_admins.is_some() {
-            batch.append_statement(
-                Topic::PUSH_TOPIC_ADMINS_QUERY,
-                Topic {
-                    topic_owners: topic.push_to_admins.as_ref().unwrap().to_owned(),
-                    ..Default::default()
-                },
-            );
-        }
-
-        if topic.pop_to_admins.is_some() {
-            batch.append_statement(
-                Topic::PULL_TOPIC_ADMINS_QUERY,
-                Topic {
-                    topic_owners: topic.push_to_admins.as_ref().unwrap().to_owned(),
-                    ..Default::default()
-                },
-            );
-        }
-
-        match batch.execute(&session).await {
-            Ok(_) => Ok(Topic::default()),
+    async fn update_topic(&self, topic: &Topic, expected_updated_at: Timestamp) -> AppResult<bool> {
+        let session = &self.db;
+        let values = (
+            &topic.topic_name,
+            &topic.topic_description,
+            &topic.topic_owners,
+            &topic.topic_admins,
+            &topic.topic_kind,
+            &topic.topic_state,
+            topic.updated_at,
+            topic.topic_id,
+            topic.created_at,
+            expected_updated_at,
+        );
+        match session.execute_unpaged(UPDATE_TOPIC_QUERY, values).await {
+            Ok(result) => lwt_applied(&result),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(AppError::InternalServerError))
+                Err(storage_error(err))
             }
         }
     }
 }
 
-static CREATE_TOPIC_TABLE_QUERY: &str = r#"
-    CREATE TABLE IF NOT EXISTS uptop.topics (
-        topic_id timeuuid,
-        topic_name text,
-        display_name text,
-        email text,
-        password text,
-        status list<text>,
-        role text,
-        phone_number text,
-        language text,
-        address text,
-        country text,
-        region text,
-        city text,
-        post_code text,
-        owners list<timeuuid>,
-        admins list<timeuuid>,
-        organizations list<timeuuid>,
-        active_organization timeuuid,
-        other_emails list<text>,
-        email_verify_code text,
-        email_verified_at timestamp,
-        password_recovery_code text,
-        password_recovered_at timestamp,
-        created_at timestamp,
-        updated_at timestamp,
-        PRIMARY KEY ((country, region, city), topic_id)
-    ) WITH CLUSTERING ORDER BY (topic_id DESC);
-"#;
-
-static CREATE_USER_ID_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_topic_id_index ON uptop.topics (topic_id);
-"#;
-
-static CREATE_USER_EMAIL_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_email_index ON uptop.topics (email);
+static FIND_TOPICS_BY_NAME_QUERY: &str = r#"
+    SELECT topic_id, topic_name, topic_description, topic_owners, topic_admins, created_at, updated_at, topic_kind,
+        topic_state
+    FROM uptop.topics
+    WHERE topic_name = ?
 "#;
 
-static CREATE_USER_NAME_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_topic_name_index ON uptop.topics (topic_name);
+static UPDATE_TOPIC_QUERY: &str = r#"
+    UPDATE uptop.topics
+    SET topic_name = ?, topic_description = ?, topic_owners = ?, topic_admins = ?, topic_kind = ?,
+        topic_state = ?, updated_at = ?
+    WHERE topic_id = ? AND created_at = ?
+    IF updated_at = ?
 "#;
@@ -0,0 +1,106 @@
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::application::topic_role::request::RequestGetRolesByTopicId;
+use crate::application::topic_user::request::RequestGetTopicMember;
+use crate::domain::topic_role::{entity::TopicRole, repository::TopicRoleRepository};
+use crate::infrastructure::persistence::{next_page_token, paging_state, storage_error, MessageSession};
+use charybdis::operations::{Find, Insert};
+use charybdis::types::Timeuuid;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct TopicRoleRepo {
+    db: MessageSession,
+}
+
+impl TopicRoleRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl TopicRoleRepository for TopicRoleRepo {
+    async fn find_topic_role(&self, query: &RequestGetTopicMember) -> AppResult<Option<TopicRole>> {
+        let session = &self.db;
+        let result = TopicRole {
+            topic_id: query.topic_id,
+            username: query.username.to_owned(),
+            ..Default::default()
+        }
+            .maybe_find_by_primary_key()
+            .execute(session)
+            .await;
+
+        match result {
+            Ok(topic_role) => Ok(topic_role),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn find_topic_roles_by_partition_key(
+        &self,
+        query: &RequestGetRolesByTopicId,
+    ) -> AppResult<Page<TopicRole>> {
+        let session = &self.db;
+        let result = TopicRole::find_by_partition_key_value_paged((query.topic_id,))
+            .page_size(page_size_or_default(query.page_size))
+            .paging_state(paging_state(&query.page_token)?)
+            .execute(session)
+            .await;
+
+        match result {
+            Ok((topic_roles, paging_state_response)) => Ok(Page {
+                items: topic_roles.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn save_topic_role(&self, topic_role: &TopicRole) -> AppResult<()> {
+        let session = &self.db;
+        match topic_role.insert().execute(session).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_topic_role(&self, query: &RequestGetTopicMember) -> AppResult<()> {
+        let session = &self.db;
+        let values = (query.topic_id, &query.username);
+        match session.execute_unpaged(DELETE_TOPIC_ROLE_QUERY, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn remove_topic_roles(&self, topic_id: Timeuuid) -> AppResult<()> {
+        let session = &self.db;
+        match session.execute_unpaged(DELETE_TOPIC_ROLES_QUERY, (topic_id,)).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+static DELETE_TOPIC_ROLE_QUERY: &str = r#"
+    DELETE FROM uptop.topic_roles WHERE topic_id = ? AND username = ?
+"#;
+
+static DELETE_TOPIC_ROLES_QUERY: &str = r#"
+    DELETE FROM uptop.topic_roles WHERE topic_id = ?
+"#;
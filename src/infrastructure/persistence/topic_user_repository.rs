@@ -1,126 +1,132 @@
-use crate::application::topic_user::request::{RequestGetUsersByTopicId, RequestFindLatestMessageError, RequestUpdateTopicUser};
+use crate::application::topic_user::request::RequestGetUsersByTopicId;
 use crate::{
     domain::topic_user::{entity::TopicUser, repository::TopicUserRepository},
 };
-use anyhow::anyhow;
-use charybdis::batch::ModelBatch;
-use charybdis::operations::{Find, Insert, Update};
-use charybdis::types::Text;
+use crate::domain::topic_invite::entity::TopicInvite;
+use crate::domain::user_topic::entity::UserTopic;
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::infrastructure::persistence::{next_page_token, paging_state, storage_error, MessageSession};
+use charybdis::operations::Find;
 use scylla::batch::Batch;
-use std::rc::Rc;
-use charybdis::errors::CharybdisError;
-use scylla::QueryResult;
-use uptop_core::common::{
-    db_types::CassandraCacheSession,
-    result::{AppError, AppResult},
-};
+use uptop_core::common::result::AppResult;
 
 #[derive(Clone, Debug)]
 pub struct TopicUserRepo {
-    db: CassandraCacheSession,
+    db: MessageSession,
 }
 
 impl TopicUserRepo {
-    pub fn new(db: CassandraCacheSession) -> Self {
+    pub fn new(db: MessageSession) -> Self {
         Self { db }
     }
-
-    pub async fn migrate_topic_user_table(&self) -> AppResult<()> {
-        let session = self.db.lock().await;
-        session
-            .execute_unpaged(CREATE_TOPIC_TABLE_QUERY, ())
-            .await?;
-        session.execute_unpaged(CREATE_USER_ID_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_EMAIL_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_NAME_INDEX, ()).await?;
-        Ok(())
-    }
 }
 
 impl TopicUserRepository for TopicUserRepo {
     async fn find_topic_users_by_partition_key(
         &self,
         query: &RequestGetUsersByTopicId,
-    ) -> AppResult<Vec<TopicUser>> {
-        let session = self.db.lock().await;
-        let result = TopicUser {
-            topic_id: query.topic_id,
-            ..Default::default()
-        }
-            .find_by_partition_key()
-            .execute(&session)
+    ) -> AppResult<Page<TopicUser>> {
+        let session = &self.db;
+        let result = TopicUser::find_by_partition_key_value_paged((query.topic_id,))
+            .page_size(page_size_or_default(query.page_size))
+            .paging_state(paging_state(&query.page_token)?)
+            .execute(session)
             .await;
 
         match result {
-            Ok(topic_user) => Ok(topic_user.try_collect().await?),
+            Ok((topic_users, paging_state_response)) => Ok(Page {
+                items: topic_users.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindLatestMessageError::LatestMessageNotFound))
+                Err(storage_error(err))
             }
         }
     }
 
 
-    async fn update_topic_users(&self, topic_user: &RequestUpdateTopicUser) -> AppResult<TopicUser> {
-        let session = self.db.lock().await;
-        let result = TopicUser {
-            user_id: topic_user.user_id,
-            topic_id: topic_user.topic_id,
-            username: (*topic_user.username).parse()?,
-            ..Default::default()
+    async fn add_topic_member(
+        &self,
+        topic_user: &TopicUser,
+        user_topic: &UserTopic,
+        accepted_invite: Option<&TopicInvite>,
+    ) -> AppResult<()> {
+        let session = &self.db;
+        // Logged so both views, and the invite, land together or not at all.
+        let mut batch = Batch::default();
+        batch.append_statement(INSERT_TOPIC_USER_QUERY);
+        batch.append_statement(INSERT_USER_TOPIC_QUERY);
+
+        let topic_user_values = (
+            topic_user.topic_id,
+            topic_user.created_at,
+            &topic_user.username,
+            topic_user.user_id,
+        );
+        let user_topic_values = (
+            &user_topic.username,
+            user_topic.topic_id,
+            user_topic.created_at,
+            &user_topic.topic_kind,
+            &user_topic.topic_state,
+        );
+        let result = match accepted_invite {
+            Some(invite) => {
+                batch.append_statement(UPDATE_TOPIC_INVITE_STATUS_QUERY);
+                let invite_values = (&invite.status, invite.updated_at, &invite.username, invite.topic_id);
+                session
+                    .batch(&batch, (topic_user_values, user_topic_values, invite_values))
+                    .await
+            }
+            None => session.batch(&batch, (topic_user_values, user_topic_values)).await,
         };
 
-        match result.update().execute(&session).await {
-            Ok(_V) => {
-                Ok(result)
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
             }
+        }
+    }
+
+    async fn remove_topic_member(&self, user_topic: &UserTopic) -> AppResult<()> {
+        let session = &self.db;
+        let mut batch = Batch::default();
+        batch.append_statement(DELETE_TOPIC_USER_QUERY);
+        batch.append_statement(DELETE_USER_TOPIC_QUERY);
+
+        let values = (
+            (user_topic.topic_id, user_topic.created_at, &user_topic.username),
+            (&user_topic.username, user_topic.topic_id),
+        );
+        match session.batch(&batch, values).await {
+            Ok(_) => Ok(()),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindLatestMessageError::LatestMessageNotFound))
+                Err(storage_error(err))
             }
         }
     }
 }
 
-static CREATE_TOPIC_TABLE_QUERY: &str = r#"
-    CREATE TABLE IF NOT EXISTS uptop.topic_users (
-        topic_user_id timeuuid,
-        topic_user_name text,
-        display_name text,
-        email text,
-        password text,
-        status list<text>,
-        role text,
-        phone_number text,
-        language text,
-        address text,
-        country text,
-        region text,
-        city text,
-        post_code text,
-        owners list<timeuuid>,
-        admins list<timeuuid>,
-        organizations list<timeuuid>,
-        active_organization timeuuid,
-        other_emails list<text>,
-        email_verify_code text,
-        email_verified_at timestamp,
-        password_recovery_code text,
-        password_recovered_at timestamp,
-        created_at timestamp,
-        updated_at timestamp,
-        PRIMARY KEY ((country, region, city), topic_user_id)
-    ) WITH CLUSTERING ORDER BY (topic_user_id DESC);
+static INSERT_TOPIC_USER_QUERY: &str = r#"
+    INSERT INTO uptop.topic_members (topic_id, created_at, username, user_id) VALUES (?, ?, ?, ?)
+"#;
+
+static INSERT_USER_TOPIC_QUERY: &str = r#"
+    INSERT INTO uptop.user_topic (username, topic_id, created_at, topic_kind, topic_state) VALUES (?, ?, ?, ?, ?)
 "#;
 
-static CREATE_USER_ID_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_topic_user_id_index ON uptop.topic_users (topic_user_id);
+static UPDATE_TOPIC_INVITE_STATUS_QUERY: &str = r#"
+    UPDATE uptop.topic_invites SET status = ?, updated_at = ? WHERE username = ? AND topic_id = ?
 "#;
 
-static CREATE_USER_EMAIL_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_email_index ON uptop.topic_users (email);
+static DELETE_TOPIC_USER_QUERY: &str = r#"
+    DELETE FROM uptop.topic_members WHERE topic_id = ? AND created_at = ? AND username = ?
 "#;
 
-static CREATE_USER_NAME_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_topic_user_name_index ON uptop.topic_users (topic_user_name);
+static DELETE_USER_TOPIC_QUERY: &str = r#"
+    DELETE FROM uptop.user_topic WHERE username = ? AND topic_id = ?
 "#;
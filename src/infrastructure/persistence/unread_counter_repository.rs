@@ -0,0 +1,133 @@
+use crate::application::latest_message::request::{RequestGetUnreadCounters, RequestGetUnreadSummary};
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::domain::unread_counter::{entity::UnreadCounter, repository::UnreadCounterRepository};
+use crate::infrastructure::persistence::{
+    next_page_token, paging_state, storage_error, MessageSession, FAN_OUT_CHUNK_SIZE,
+};
+use charybdis::types::Timeuuid;
+use scylla::batch::{Batch, BatchType};
+use scylla::frame::value::Counter;
+use scylla::query::Query;
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct UnreadCounterRepo {
+    db: MessageSession,
+}
+
+impl UnreadCounterRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl UnreadCounterRepository for UnreadCounterRepo {
+    async fn find_unread_counters_by_user_id(&self, query: &RequestGetUnreadSummary) -> AppResult<Vec<UnreadCounter>> {
+        let session = &self.db;
+        let result = session
+            .execute_unpaged(FIND_UNREAD_COUNTERS_BY_USER_ID_QUERY, (query.user_id,))
+            .await;
+
+        match result {
+            Ok(result) => unread_counters(result),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn find_unread_counters(&self, query: &RequestGetUnreadCounters) -> AppResult<Page<UnreadCounter>> {
+        let session = &self.db;
+        let mut statement = Query::new(FIND_UNREAD_COUNTERS_QUERY);
+        statement.set_page_size(page_size_or_default(query.page_size));
+        let result = session
+            .execute_single_page(statement, (), paging_state(&query.page_token)?)
+            .await;
+
+        match result {
+            Ok((result, paging_state_response)) => Ok(Page {
+                items: unread_counters(result)?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn increment_unread_counters(&self, increments: &[UnreadCounter]) -> AppResult<()> {
+        let session = &self.db;
+        for chunk in increments.chunks(FAN_OUT_CHUNK_SIZE) {
+            let mut batch = Batch::new(BatchType::Counter);
+            for _ in chunk {
+                batch.append_statement(INCREMENT_UNREAD_COUNTER_QUERY);
+            }
+            let values: Vec<(Counter, Timeuuid, Timeuuid)> = chunk
+                .iter()
+                .map(|increment| (Counter(increment.unread), increment.user_id, increment.topic_id))
+                .collect();
+
+            if let Err(err) = session.batch(&batch, values).await {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        }
+        Ok(())
+    }
+
+    async fn reset_unread_counter(&self, counter: &UnreadCounter) -> AppResult<()> {
+        let session = &self.db;
+        let result = session
+            .execute_unpaged(FIND_UNREAD_COUNTER_QUERY, (counter.user_id, counter.topic_id))
+            .await;
+        let stored = match result {
+            Ok(result) => match result.maybe_first_row_typed::<(Counter,)>()? {
+                Some((Counter(unread),)) => unread,
+                None => 0,
+            },
+            Err(err) => {
+                tracing::error!("{err:?}");
+                return Err(storage_error(err));
+            }
+        };
+        if stored == counter.unread {
+            return Ok(());
+        }
+
+        let values = (Counter(counter.unread - stored), counter.user_id, counter.topic_id);
+        match session.execute_unpaged(INCREMENT_UNREAD_COUNTER_QUERY, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+fn unread_counters(result: scylla::QueryResult) -> AppResult<Vec<UnreadCounter>> {
+    let mut counters: Vec<UnreadCounter> = vec![];
+    for row in result.rows_typed::<(Timeuuid, Timeuuid, Counter)>()? {
+        let (user_id, topic_id, Counter(unread)) = row?;
+        counters.push(UnreadCounter { user_id, topic_id, unread });
+    }
+    Ok(counters)
+}
+
+static FIND_UNREAD_COUNTERS_BY_USER_ID_QUERY: &str = r#"
+    SELECT user_id, topic_id, unread FROM uptop.unread_counters WHERE user_id = ?
+"#;
+
+static FIND_UNREAD_COUNTERS_QUERY: &str = r#"
+    SELECT user_id, topic_id, unread FROM uptop.unread_counters
+"#;
+
+static FIND_UNREAD_COUNTER_QUERY: &str = r#"
+    SELECT unread FROM uptop.unread_counters WHERE user_id = ? AND topic_id = ?
+"#;
+
+static INCREMENT_UNREAD_COUNTER_QUERY: &str = r#"
+    UPDATE uptop.unread_counters SET unread = unread + ? WHERE user_id = ? AND topic_id = ?
+"#;
@@ -0,0 +1,62 @@
+use crate::application::user_identity::request::RequestGetUserIdentity;
+use crate::domain::user_identity::{entity::UserIdentity, repository::UserIdentityRepository};
+use crate::infrastructure::persistence::{storage_error, MessageSession};
+use charybdis::types::{Text, Timestamp, Timeuuid};
+use uptop_core::common::result::AppResult;
+
+#[derive(Clone, Debug)]
+pub struct UserIdentityRepo {
+    db: MessageSession,
+}
+
+impl UserIdentityRepo {
+    pub fn new(db: MessageSession) -> Self {
+        Self { db }
+    }
+}
+
+impl UserIdentityRepository for UserIdentityRepo {
+    async fn find_user_identity(&self, query: &RequestGetUserIdentity) -> AppResult<Option<UserIdentity>> {
+        let session = &self.db;
+        let result = session
+            .execute_unpaged(FIND_USER_IDENTITY_QUERY, (&query.username,))
+            .await;
+
+        match result {
+            Ok(result) => Ok(result
+                .maybe_first_row_typed::<(Text, Timeuuid, Timestamp)>()?
+                .map(|(username, user_id, updated_at)| UserIdentity {
+                    username,
+                    user_id,
+                    updated_at,
+                })),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+
+    async fn save_user_identity(&self, identity: &UserIdentity) -> AppResult<()> {
+        let session = &self.db;
+        let values = (&identity.username, identity.user_id, identity.updated_at);
+        match session.execute_unpaged(INSERT_USER_IDENTITY_QUERY, values).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                tracing::error!("{err:?}");
+                Err(storage_error(err))
+            }
+        }
+    }
+}
+
+static FIND_USER_IDENTITY_QUERY: &str = r#"
+    SELECT username, user_id, updated_at
+    FROM uptop.user_identities
+    WHERE username = ?
+"#;
+
+static INSERT_USER_IDENTITY_QUERY: &str = r#"
+    INSERT INTO uptop.user_identities (username, user_id, updated_at)
+    VALUES (?, ?, ?)
+"#;
@@ -1,124 +1,65 @@
-use crate::application::user_topic::request::{RequestGetTopicsByUsername, RequestFindLatestMessageError, RequestUpdateUserTopic};
+use crate::application::topic_user::request::RequestGetTopicMember;
+use crate::application::user_topic::request::RequestGetTopicsByUsername;
 use crate::{
     domain::user_topic::{entity::UserTopic, repository::UserTopicRepository},
 };
-use anyhow::anyhow;
-use charybdis::batch::ModelBatch;
-use charybdis::operations::{Find, Insert, Update};
-use charybdis::types::Text;
-use scylla::batch::Batch;
-use std::rc::Rc;
-use charybdis::errors::CharybdisError;
-use scylla::QueryResult;
-use uptop_core::common::{
-    db_types::CassandraCacheSession,
-    result::{AppError, AppResult},
-};
+use crate::application::pagination::{page_size_or_default, Page};
+use crate::infrastructure::persistence::{next_page_token, paging_state, storage_error, MessageSession};
+use charybdis::operations::Find;
+use uptop_core::common::result::AppResult;
 
 #[derive(Clone, Debug)]
 pub struct UserTopicRepo {
-    db: CassandraCacheSession,
+    db: MessageSession,
 }
 
 impl UserTopicRepo {
-    pub fn new(db: CassandraCacheSession) -> Self {
+    pub fn new(db: MessageSession) -> Self {
         Self { db }
     }
-
-    pub async fn migrate_user_topic_table(&self) -> AppResult<()> {
-        let session = self.db.lock().await;
-        session
-            .execute_unpaged(CREATE_TOPIC_TABLE_QUERY, ())
-            .await?;
-        session.execute_unpaged(CREATE_USER_ID_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_EMAIL_INDEX, ()).await?;
-        session.execute_unpaged(CREATE_USER_NAME_INDEX, ()).await?;
-        Ok(())
-    }
 }
 
 impl UserTopicRepository for UserTopicRepo {
     async fn find_user_topics_by_partition_key(
         &self,
         query: &RequestGetTopicsByUsername,
-    ) -> AppResult<Vec<UserTopic>> {
-        let session = self.db.lock().await;
-        let result = UserTopic {
-            username: (*query.username).parse()?,
-            ..Default::default()
-        }
-            .find_by_partition_key()
-            .execute(&session)
+    ) -> AppResult<Page<UserTopic>> {
+        let session = &self.db;
+        let result = UserTopic::find_by_partition_key_value_paged((query.username.to_owned(),))
+            .page_size(page_size_or_default(query.page_size))
+            .paging_state(paging_state(&query.page_token)?)
+            .execute(session)
             .await;
 
         match result {
-            Ok(user_topic) => Ok(user_topic.try_collect().await?),
+            Ok((user_topics, paging_state_response)) => Ok(Page {
+                items: user_topics.collect::<Result<Vec<_>, _>>()?,
+                next_page_token: next_page_token(paging_state_response),
+            }),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindLatestMessageError::LatestMessageNotFound))
+                Err(storage_error(err))
             }
         }
     }
 
-    async fn update_user_topics(&self, user_topic: &RequestUpdateUserTopic) -> AppResult<UserTopic> {
-        let session = self.db.lock().await;
+    async fn find_user_topic(&self, query: &RequestGetTopicMember) -> AppResult<Option<UserTopic>> {
+        let session = &self.db;
         let result = UserTopic {
-            topic_id: user_topic.topic_id,
-            username: (*user_topic.username).parse()?,
+            username: query.username.to_owned(),
+            topic_id: query.topic_id,
             ..Default::default()
-        };
+        }
+            .maybe_find_by_primary_key()
+            .execute(session)
+            .await;
 
-        match result.update().execute(&session).await {
-            Ok(_V) => {
-                Ok(result)
-            }
+        match result {
+            Ok(user_topic) => Ok(user_topic),
             Err(err) => {
                 tracing::error!("{err:?}");
-                Err(anyhow!(RequestFindLatestMessageError::LatestMessageNotFound))
+                Err(storage_error(err))
             }
         }
     }
 }
-
-static CREATE_TOPIC_TABLE_QUERY: &str = r#"
-    CREATE TABLE IF NOT EXISTS uptop.user_topics (
-        user_topic_id timeuuid,
-        user_topic_name text,
-        display_name text,
-        email text,
-        password text,
-        status list<text>,
-        role text,
-        phone_number text,
-        language text,
-        address text,
-        country text,
-        region text,
-        city text,
-        post_code text,
-        owners list<timeuuid>,
-        admins list<timeuuid>,
-        organizations list<timeuuid>,
-        active_organization timeuuid,
-        other_emails list<text>,
-        email_verify_code text,
-        email_verified_at timestamp,
-        password_recovery_code text,
-        password_recovered_at timestamp,
-        created_at timestamp,
-        updated_at timestamp,
-        PRIMARY KEY ((country, region, city), user_topic_id)
-    ) WITH CLUSTERING ORDER BY (user_topic_id DESC);
-"#;
-
-static CREATE_USER_ID_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_user_topic_id_index ON uptop.user_topics (user_topic_id);
-"#;
-
-static CREATE_USER_EMAIL_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_email_index ON uptop.user_topics (email);
-"#;
-
-static CREATE_USER_NAME_INDEX: &str = r#"
-    CREATE INDEX IF NOT EXISTS uptop_user_topic_name_index ON uptop.user_topics (user_topic_name);
-"#;
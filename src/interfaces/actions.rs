@@ -3,15 +3,85 @@ pub enum MessageModuleServices {
     GetTopic,
     GetTopics,
     UpdateTopic,
+    SetTopicRole,
+    GetTopicRoles,
+    OpenDirectConversation,
+    OpenGroupConversation,
+    PromoteConversation,
+    SetTopicState,
+    GetTopicMessages,
+    GetThreadMessages,
+    PostTopicMessage,
+    ForwardTopicMessage,
+    UpdateTopicMessage,
+    DeleteTopicMessage,
+    PurgeTopicMessage,
+    AddReaction,
+    RemoveReaction,
+    PinMessage,
+    UnpinMessage,
+    GetPinnedMessages,
+    MarkRead,
+    GetMessageReaders,
+    GetMessageRevisions,
+    GetTopicUsers,
+    JoinTopic,
+    LeaveTopic,
+    InviteTopicMember,
+    RespondTopicInvite,
+    RemoveTopicMember,
+    GetTopicInvites,
+    SetPresence,
+    GetPresence,
+    GetUserTopics,
+    GetNotifications,
+    GetLatestMessages,
+    GetUnreadSummary,
+    UpdateLatestMessage,
 }
 
 impl MessageModuleServices {
     pub fn action(input: &str) -> Option<MessageModuleServices> {
         match input {
-            "CREATE_USER" => Some(MessageModuleServices::CreateTopic),
-            "GET_USER" => Some(MessageModuleServices::GetTopic),
-            "GET_USERS" => Some(MessageModuleServices::GetTopics),
-            "UPDATE_USER" => Some(MessageModuleServices::UpdateTopic),
+            "CREATE_TOPIC" | "CREATE_USER" => Some(MessageModuleServices::CreateTopic),
+            "GET_TOPIC" | "GET_USER" => Some(MessageModuleServices::GetTopic),
+            "GET_TOPICS" | "GET_USERS" => Some(MessageModuleServices::GetTopics),
+            "UPDATE_TOPIC" | "UPDATE_USER" => Some(MessageModuleServices::UpdateTopic),
+            "SET_TOPIC_ROLE" => Some(MessageModuleServices::SetTopicRole),
+            "GET_TOPIC_ROLES" => Some(MessageModuleServices::GetTopicRoles),
+            "OPEN_DIRECT_CONVERSATION" => Some(MessageModuleServices::OpenDirectConversation),
+            "OPEN_GROUP_CONVERSATION" => Some(MessageModuleServices::OpenGroupConversation),
+            "PROMOTE_CONVERSATION" => Some(MessageModuleServices::PromoteConversation),
+            "SET_TOPIC_STATE" => Some(MessageModuleServices::SetTopicState),
+            "GET_TOPIC_MESSAGES" => Some(MessageModuleServices::GetTopicMessages),
+            "GET_THREAD_MESSAGES" => Some(MessageModuleServices::GetThreadMessages),
+            "POST_TOPIC_MESSAGE" => Some(MessageModuleServices::PostTopicMessage),
+            "FORWARD_TOPIC_MESSAGE" => Some(MessageModuleServices::ForwardTopicMessage),
+            "UPDATE_TOPIC_MESSAGE" => Some(MessageModuleServices::UpdateTopicMessage),
+            "DELETE_TOPIC_MESSAGE" => Some(MessageModuleServices::DeleteTopicMessage),
+            "PURGE_TOPIC_MESSAGE" => Some(MessageModuleServices::PurgeTopicMessage),
+            "ADD_REACTION" => Some(MessageModuleServices::AddReaction),
+            "REMOVE_REACTION" => Some(MessageModuleServices::RemoveReaction),
+            "PIN_MESSAGE" => Some(MessageModuleServices::PinMessage),
+            "UNPIN_MESSAGE" => Some(MessageModuleServices::UnpinMessage),
+            "GET_PINNED_MESSAGES" => Some(MessageModuleServices::GetPinnedMessages),
+            "MARK_READ" => Some(MessageModuleServices::MarkRead),
+            "GET_MESSAGE_READERS" => Some(MessageModuleServices::GetMessageReaders),
+            "GET_MESSAGE_REVISIONS" => Some(MessageModuleServices::GetMessageRevisions),
+            "GET_TOPIC_USERS" => Some(MessageModuleServices::GetTopicUsers),
+            "JOIN_TOPIC" => Some(MessageModuleServices::JoinTopic),
+            "LEAVE_TOPIC" => Some(MessageModuleServices::LeaveTopic),
+            "INVITE_TOPIC_MEMBER" => Some(MessageModuleServices::InviteTopicMember),
+            "RESPOND_TOPIC_INVITE" => Some(MessageModuleServices::RespondTopicInvite),
+            "REMOVE_TOPIC_MEMBER" => Some(MessageModuleServices::RemoveTopicMember),
+            "GET_TOPIC_INVITES" => Some(MessageModuleServices::GetTopicInvites),
+            "SET_PRESENCE" => Some(MessageModuleServices::SetPresence),
+            "GET_PRESENCE" => Some(MessageModuleServices::GetPresence),
+            "GET_USER_TOPICS" => Some(MessageModuleServices::GetUserTopics),
+            "GET_NOTIFICATIONS" => Some(MessageModuleServices::GetNotifications),
+            "GET_LATEST_MESSAGES" => Some(MessageModuleServices::GetLatestMessages),
+            "GET_UNREAD_SUMMARY" => Some(MessageModuleServices::GetUnreadSummary),
+            "UPDATE_LATEST_MESSAGE" => Some(MessageModuleServices::UpdateLatestMessage),
             _ => None,
         }
     }
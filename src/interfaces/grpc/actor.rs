@@ -0,0 +1,13 @@
+use super::auth::unauthenticated;
+use crate::application::actor::Actor;
+use tonic::Request;
+use tonic::Status;
+
+/// The caller [`AuthInterceptor`](super::auth::AuthInterceptor) verified for this request.
+#[allow(clippy::result_large_err)] // tonic's own error type, returned as is to the client.
+pub(crate) fn actor_from_request<T>(request: &Request<T>) -> Result<Actor, Status> {
+    match request.extensions().get::<Actor>() {
+        Some(actor) => Ok(actor.clone()),
+        None => Err(unauthenticated("request was not authenticated")),
+    }
+}
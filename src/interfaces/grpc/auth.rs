@@ -0,0 +1,149 @@
+use super::status::application_status;
+use crate::application::actor::Actor;
+use crate::application::error::ApplicationError;
+use crate::application::user_identity::app::IdentityRecorder;
+use anyhow::{anyhow, bail};
+use charybdis::types::Timeuuid;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use std::{env, fs, sync::Arc};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+use uptop_core::common::result::AppResult;
+
+/// Claims this service reads from a token. `sub` is the user id.
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,
+    username: String,
+}
+
+/// Verifies bearer tokens against the configured keys. Several keys per
+/// algorithm may be configured so they can be rotated without downtime.
+#[derive(Clone)]
+pub struct JwtVerifier {
+    keys: Vec<(Algorithm, DecodingKey)>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+impl JwtVerifier {
+    /// Reads the keys from the environment:
+    /// `JWT_HS256_SECRETS` holds comma separated shared secrets,
+    /// `JWT_RS256_PUBLIC_KEYS` comma separated paths to PEM public keys.
+    /// `JWT_ISSUER` and `JWT_AUDIENCE` are checked when set.
+    pub fn from_env() -> AppResult<Self> {
+        let mut keys = vec![];
+        for secret in list_var("JWT_HS256_SECRETS") {
+            keys.push((Algorithm::HS256, DecodingKey::from_secret(secret.as_bytes())));
+        }
+        for path in list_var("JWT_RS256_PUBLIC_KEYS") {
+            let pem = fs::read(&path).map_err(|err| anyhow!("cannot read {path}: {err}"))?;
+            keys.push((Algorithm::RS256, DecodingKey::from_rsa_pem(&pem)?));
+        }
+        if keys.is_empty() {
+            bail!("no JWT verification key, set JWT_HS256_SECRETS or JWT_RS256_PUBLIC_KEYS");
+        }
+
+        Ok(Self {
+            keys,
+            issuer: env::var("JWT_ISSUER").ok(),
+            audience: env::var("JWT_AUDIENCE").ok(),
+        })
+    }
+
+    pub fn verify(&self, token: &str) -> AppResult<Actor> {
+        let header = decode_header(token)?;
+        let mut validation = Validation::new(header.alg);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        // The algorithm is pinned per key, so a token cannot pick how it is checked.
+        let mut last_err = anyhow!("no key for {:?}", header.alg);
+        for (_, key) in self.keys.iter().filter(|(algorithm, _)| *algorithm == header.alg) {
+            match decode::<Claims>(token, key, &validation) {
+                Ok(data) => {
+                    return Ok(Actor {
+                        user_id: data.claims.sub.parse::<Timeuuid>()?,
+                        username: data.claims.username,
+                    })
+                }
+                Err(err) => last_err = err.into(),
+            }
+        }
+        Err(last_err)
+    }
+}
+
+fn list_var(key: &str) -> Vec<String> {
+    env::var(key)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Authenticates every call: checks the `authorization: Bearer <jwt>` metadata
+/// and stores the caller as an [`Actor`] in the request extensions.
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    verifier: Arc<JwtVerifier>,
+    recorder: Option<IdentityRecorder>,
+}
+
+impl AuthInterceptor {
+    pub fn new(verifier: Arc<JwtVerifier>) -> Self {
+        Self {
+            verifier,
+            recorder: None,
+        }
+    }
+
+    /// Hands every authenticated caller to `recorder`, so other users can
+    /// name them in conversations.
+    pub fn with_identity_recorder(mut self, recorder: IdentityRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let token = match request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+        {
+            Some(token) => token.trim(),
+            None => return Err(unauthenticated("missing bearer token")),
+        };
+
+        let actor = match self.verifier.verify(token) {
+            Ok(actor) => actor,
+            Err(err) => {
+                tracing::debug!("rejected bearer token: {err:?}");
+                return Err(unauthenticated("invalid bearer token"));
+            }
+        };
+        if let Some(recorder) = &self.recorder {
+            recorder.record(&actor);
+        }
+        request.extensions_mut().insert(actor);
+        Ok(request)
+    }
+}
+
+pub(crate) fn unauthenticated(msg: &str) -> Status {
+    application_status(&ApplicationError::Unauthenticated { msg: msg.to_string() })
+}
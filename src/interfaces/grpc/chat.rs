@@ -0,0 +1,173 @@
+use super::status::{into_status, invalid_field};
+use super::proto::v1;
+use super::proto::v1::chat_client_frame::Frame as ClientFrame;
+use super::proto::v1::chat_server_frame::Frame as ServerFrame;
+use crate::application::actor::Actor;
+use crate::application::latest_message::app::LatestMessageAppInterface;
+use crate::application::notification::app::NotificationAppInterface;
+use crate::application::topic::app::TopicAppInterface;
+use crate::application::topic_message::app::TopicMessageAppInterface;
+use crate::application::topic_user::app::TopicUserAppInterface;
+use crate::application::user_topic::app::UserTopicAppInterface;
+use crate::interfaces::message_handler::MessageHandler;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tonic::{Status, Streaming};
+
+pub(crate) type ChatOutbound = mpsc::Sender<Result<v1::ChatServerFrame, Status>>;
+
+/// One `Chat` stream: answers client frames one at a time, in the order they
+/// were sent, and forwards the events of the followed topics meanwhile.
+pub(crate) struct ChatSession<
+    TAI: TopicAppInterface,
+    LTI: LatestMessageAppInterface,
+    NI: NotificationAppInterface,
+    UTI: UserTopicAppInterface,
+    TUI: TopicUserAppInterface,
+    TMI: TopicMessageAppInterface,
+> {
+    handler: Arc<MessageHandler<TAI, LTI, NI, UTI, TUI, TMI>>,
+    /// The caller who opened the stream; every frame runs on their behalf.
+    actor: Actor,
+    outbound: ChatOutbound,
+    subscription: Option<JoinHandle<()>>,
+}
+
+impl<
+    TAI: TopicAppInterface,
+    LTI: LatestMessageAppInterface,
+    NI: NotificationAppInterface,
+    UTI: UserTopicAppInterface,
+    TUI: TopicUserAppInterface,
+    TMI: TopicMessageAppInterface,
+> ChatSession<TAI, LTI, NI, UTI, TUI, TMI>
+{
+    pub(crate) fn new(
+        handler: Arc<MessageHandler<TAI, LTI, NI, UTI, TUI, TMI>>,
+        actor: Actor,
+        outbound: ChatOutbound,
+    ) -> Self {
+        Self {
+            handler,
+            actor,
+            outbound,
+            subscription: None,
+        }
+    }
+
+    pub(crate) async fn run(mut self, mut inbound: Streaming<v1::ChatClientFrame>) {
+        loop {
+            match inbound.message().await {
+                Ok(Some(frame)) => self.handle(frame).await,
+                Ok(None) => break,
+                Err(status) => {
+                    tracing::debug!("chat stream closed: {status:?}");
+                    break;
+                }
+            }
+        }
+
+        if let Some(subscription) = self.subscription.take() {
+            subscription.abort();
+        }
+    }
+
+    async fn handle(&mut self, frame: v1::ChatClientFrame) {
+        let correlation_id = frame.correlation_id;
+
+        match frame.frame {
+            Some(ClientFrame::Command(command)) => {
+                // Awaited rather than spawned: a client may send an edit right
+                // behind its post, and nothing but the stream bounds the work.
+                let reply = match self
+                    .handler
+                    .dispatch(&self.actor, &command.command, command.payload)
+                    .await
+                {
+                    Ok(payload) => ServerFrame::Result(v1::ChatCommandResult { payload }),
+                    Err(err) => error_frame(into_status(err)),
+                };
+                send(&self.outbound, correlation_id, reply).await;
+            }
+            Some(ClientFrame::Subscribe(req)) => {
+                let reply = match self.subscribe(correlation_id.clone(), req).await {
+                    Ok(()) => ServerFrame::Result(v1::ChatCommandResult::default()),
+                    Err(status) => error_frame(status),
+                };
+                send(&self.outbound, correlation_id, reply).await;
+            }
+            Some(ClientFrame::Typing(typing)) => {
+                let result = match typing.try_into() {
+                    Ok(query) => self
+                        .handler
+                        .notify_typing(&self.actor, query)
+                        .await
+                        .map_err(into_status),
+                    Err(status) => Err(status),
+                };
+                if let Err(status) = result {
+                    send(&self.outbound, correlation_id, error_frame(status)).await;
+                }
+            }
+            None => {
+                let status = invalid_field("frame", "is empty");
+                send(&self.outbound, correlation_id, error_frame(status)).await;
+            }
+        }
+    }
+
+    async fn subscribe(
+        &mut self,
+        correlation_id: String,
+        req: v1::SubscribeTopicRequest,
+    ) -> Result<(), Status> {
+        let mut subscription = self
+            .handler
+            .subscribe_topics(&self.actor, req.try_into()?)
+            .await
+            .map_err(into_status)?;
+
+        if let Some(previous) = self.subscription.take() {
+            previous.abort();
+        }
+
+        let outbound = self.outbound.clone();
+        self.subscription = Some(tokio::spawn(async move {
+            loop {
+                let frame = match subscription.next().await {
+                    Ok(Some(event)) => ServerFrame::Event(event.into()),
+                    Ok(None) => break,
+                    Err(err) => {
+                        send(&outbound, correlation_id, error_frame(into_status(err))).await;
+                        break;
+                    }
+                };
+                if !send(&outbound, correlation_id.clone(), frame).await {
+                    break;
+                }
+            }
+        }));
+
+        Ok(())
+    }
+}
+
+fn error_frame(status: Status) -> ServerFrame {
+    ServerFrame::Error(v1::ChatError {
+        code: status.code() as i32,
+        message: status.message().to_owned(),
+        details: status.details().to_vec(),
+    })
+}
+
+/// Returns `false` once the client has gone away.
+async fn send(outbound: &ChatOutbound, correlation_id: String, frame: ServerFrame) -> bool {
+    outbound
+        .send(Ok(v1::ChatServerFrame {
+            correlation_id,
+            frame: Some(frame),
+        }))
+        .await
+        .is_ok()
+}
@@ -0,0 +1,857 @@
+// Conversions fail with the `Status` tonic hands back to the client, boxing
+// it would only be undone at every call site.
+#![allow(clippy::result_large_err)]
+
+use super::proto::v1;
+use super::status::invalid_field;
+use crate::application::live::event::{PinChange, PresenceUpdate, ReactionChange, TopicEvent, TypingIndicator};
+use crate::application::latest_message::request::{
+    RequestGetLatestMessagesByUserId, RequestGetUnreadSummary, RequestUpdateLatestMessage,
+};
+use crate::application::latest_message::response::{PublicLatestMessage, PublicTopicUnread, PublicUnreadSummary};
+use crate::application::notification::request::RequestGetNotificationByUsername;
+use crate::application::notification::response::PublicNotification;
+use crate::application::topic::request::{
+    RequestConversationMember, RequestCreateTopic, RequestGetTopicByPartitionKey, RequestOpenDirectConversation,
+    RequestOpenGroupConversation, RequestPromoteConversation, RequestSetTopicState, RequestUpdateTopic,
+};
+use crate::application::topic::response::{PublicDirectConversation, PublicGroupConversation, PublicTopic};
+use crate::application::topic_message::request::{
+    RequestDeleteTopicMessage, RequestForwardTopicMessage, RequestGetMessageRevisions, RequestGetThreadMessages, RequestGetTopicMessage, RequestGetMessagesByTopicId, RequestMarkRead, RequestNotifyTyping, RequestGetPinnedMessages, RequestPinTopicMessage, RequestPostTopicMessage,
+    RequestReactTopicMessage, RequestSubscribeTopics, RequestTopicCursor, RequestUpdateTopicMessage,
+};
+use crate::application::topic_message::response::{
+    PublicForwardedFrom, PublicMessageReader, PublicPinnedMessage, PublicQuotedMessage, PublicReaction, PublicReadCursor, PublicTopicMessage,
+    PublicTopicMessageRevision,
+};
+use crate::application::topic_role::request::{RequestGetRolesByTopicId, RequestSetTopicRole};
+use crate::application::topic_role::response::PublicTopicRole;
+use crate::application::topic_user::request::{
+    RequestGetInvitesByUsername, RequestGetPresence, RequestGetUsersByTopicId, RequestInviteTopicMember,
+    RequestJoinTopic, RequestLeaveTopic, RequestRemoveTopicMember, RequestRespondTopicInvite, RequestSetPresence,
+};
+use crate::application::topic_user::response::{PublicPresence, PublicTopicInvite, PublicTopicUser};
+use crate::application::user_topic::request::RequestGetTopicsByUsername;
+use crate::application::user_topic::response::PublicUserTopic;
+use charybdis::types::{Timestamp, Timeuuid};
+use chrono::DateTime;
+use tonic::Status;
+
+pub(crate) fn parse_timeuuid(field: &str, value: &str) -> Result<Timeuuid, Status> {
+    value
+        .parse::<Timeuuid>()
+        .map_err(|_| invalid_field(field, "is not a valid timeuuid"))
+}
+
+pub(crate) fn parse_timestamp(field: &str, millis: i64) -> Result<Timestamp, Status> {
+    DateTime::from_timestamp_millis(millis)
+        .ok_or_else(|| invalid_field(field, "is out of range"))
+}
+
+fn non_empty(values: Vec<String>) -> Option<Vec<String>> {
+    (!values.is_empty()).then_some(values)
+}
+
+// Topic
+
+impl From<v1::CreateTopicRequest> for RequestCreateTopic {
+    fn from(req: v1::CreateTopicRequest) -> Self {
+        Self {
+            topic_name: req.topic_name,
+            topic_description: req.topic_description,
+            topic_owners: req.topic_owners,
+            topic_admins: req.topic_admins,
+        }
+    }
+}
+
+impl TryFrom<v1::GetTopicRequest> for RequestGetTopicByPartitionKey {
+    type Error = Status;
+
+    fn try_from(req: v1::GetTopicRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            page_size: req.page_size,
+            page_token: req.page_token,
+        })
+    }
+}
+
+impl TryFrom<v1::UpdateTopicRequest> for RequestUpdateTopic {
+    type Error = Status;
+
+    fn try_from(req: v1::UpdateTopicRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            topic_name: req.topic_name,
+            topic_description: req.topic_description,
+            push_to_owners: non_empty(req.push_to_owners),
+            pop_to_owners: non_empty(req.pop_to_owners),
+            push_to_admins: non_empty(req.push_to_admins),
+            pop_to_admins: non_empty(req.pop_to_admins),
+            expected_updated_at: req
+                .expected_updated_at
+                .map(|millis| parse_timestamp("expected_updated_at", millis))
+                .transpose()?,
+        })
+    }
+}
+
+impl From<PublicTopic> for v1::Topic {
+    fn from(topic: PublicTopic) -> Self {
+        Self {
+            topic_name: topic.topic_name,
+            topic_description: topic.topic_description,
+            topic_owners: topic.topic_owners,
+            topic_admins: topic.topic_admins,
+            created_at: topic.created_at.timestamp_millis(),
+            topic_kind: topic.topic_kind,
+            topic_state: topic.topic_state,
+            topic_id: topic.topic_id.to_string(),
+            updated_at: topic.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+impl TryFrom<v1::OpenDirectConversationRequest> for RequestOpenDirectConversation {
+    type Error = Status;
+
+    fn try_from(req: v1::OpenDirectConversationRequest) -> Result<Self, Status> {
+        Ok(Self {
+            username: String::new(),
+            user_id: Default::default(),
+            peer_username: req.peer_username,
+        })
+    }
+}
+
+impl From<PublicDirectConversation> for v1::DirectConversation {
+    fn from(conversation: PublicDirectConversation) -> Self {
+        Self {
+            topic_id: conversation.topic_id.to_string(),
+            peer_username: conversation.peer_username,
+            created_at: conversation.created_at.timestamp_millis(),
+        }
+    }
+}
+
+impl TryFrom<v1::OpenGroupConversationRequest> for RequestOpenGroupConversation {
+    type Error = Status;
+
+    fn try_from(req: v1::OpenGroupConversationRequest) -> Result<Self, Status> {
+        Ok(Self {
+            username: String::new(),
+            user_id: Default::default(),
+            members: req
+                .members
+                .into_iter()
+                .map(|member| RequestConversationMember {
+                    username: member.username,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl From<PublicGroupConversation> for v1::GroupConversation {
+    fn from(conversation: PublicGroupConversation) -> Self {
+        Self {
+            topic_id: conversation.topic_id.to_string(),
+            members: conversation.members,
+            created_at: conversation.created_at.timestamp_millis(),
+        }
+    }
+}
+
+impl TryFrom<v1::PromoteConversationRequest> for RequestPromoteConversation {
+    type Error = Status;
+
+    fn try_from(req: v1::PromoteConversationRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            username: String::new(),
+            topic_name: req.topic_name,
+            topic_description: req.topic_description,
+            topic_owners: req.topic_owners,
+        })
+    }
+}
+
+impl TryFrom<v1::SetTopicStateRequest> for RequestSetTopicState {
+    type Error = Status;
+
+    fn try_from(req: v1::SetTopicStateRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            username: String::new(),
+            topic_state: req.topic_state,
+        })
+    }
+}
+
+// Topic role
+
+impl TryFrom<v1::SetTopicRoleRequest> for RequestSetTopicRole {
+    type Error = Status;
+
+    fn try_from(req: v1::SetTopicRoleRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            username: req.username,
+            role: req.role,
+        })
+    }
+}
+
+impl TryFrom<v1::ListTopicRolesRequest> for RequestGetRolesByTopicId {
+    type Error = Status;
+
+    fn try_from(req: v1::ListTopicRolesRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            page_size: req.page_size,
+            page_token: req.page_token,
+        })
+    }
+}
+
+impl From<PublicTopicRole> for v1::TopicRole {
+    fn from(topic_role: PublicTopicRole) -> Self {
+        Self {
+            topic_id: topic_role.topic_id.to_string(),
+            username: topic_role.username,
+            role: topic_role.role,
+            updated_by: topic_role.updated_by,
+            updated_at: topic_role.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+// Topic message
+
+impl TryFrom<v1::ListTopicMessagesRequest> for RequestGetMessagesByTopicId {
+    type Error = Status;
+
+    fn try_from(req: v1::ListTopicMessagesRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            page_size: req.page_size,
+            page_token: req.page_token,
+            before: req
+                .before
+                .map(|before| parse_timeuuid("before", &before))
+                .transpose()?,
+            after: req
+                .after
+                .map(|after| parse_timeuuid("after", &after))
+                .transpose()?,
+        })
+    }
+}
+
+impl TryFrom<v1::PostMessageRequest> for RequestPostTopicMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::PostMessageRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            from_user_id: Default::default(),
+            message: req.message,
+            parent_id: req
+                .parent_id
+                .map(|parent_id| parse_timeuuid("parent_id", &parent_id))
+                .transpose()?,
+            quoted_message_id: req
+                .quoted_message_id
+                .map(|quoted_message_id| parse_timeuuid("quoted_message_id", &quoted_message_id))
+                .transpose()?,
+        })
+    }
+}
+
+impl TryFrom<v1::ForwardMessageRequest> for RequestForwardTopicMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::ForwardMessageRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+            to_topic_id: parse_timeuuid("to_topic_id", &req.to_topic_id)?,
+            from_user_id: Default::default(),
+        })
+    }
+}
+
+impl TryFrom<v1::ListThreadMessagesRequest> for RequestGetThreadMessages {
+    type Error = Status;
+
+    fn try_from(req: v1::ListThreadMessagesRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            parent_id: parse_timeuuid("parent_id", &req.parent_id)?,
+            page_size: req.page_size,
+            page_token: req.page_token,
+        })
+    }
+}
+
+impl TryFrom<v1::UpdateTopicMessageRequest> for RequestUpdateTopicMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::UpdateTopicMessageRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+            from_user_id: Default::default(),
+            message: req.message,
+        })
+    }
+}
+
+impl From<PublicTopicMessage> for v1::TopicMessage {
+    fn from(topic_message: PublicTopicMessage) -> Self {
+        Self {
+            topic_id: topic_message.topic_id.to_string(),
+            from_user_id: topic_message.from_user_id.to_string(),
+            message: topic_message.message,
+            created_at: topic_message.created_at.timestamp_millis(),
+            message_id: topic_message.message_id.to_string(),
+            kind: topic_message.kind,
+            edited: topic_message.edited,
+            edited_at: topic_message
+                .edited_at
+                .map(|edited_at| edited_at.timestamp_millis()),
+            deleted: topic_message.deleted,
+            deleted_at: topic_message
+                .deleted_at
+                .map(|deleted_at| deleted_at.timestamp_millis()),
+            parent_id: topic_message.parent_id.map(|parent_id| parent_id.to_string()),
+            reply_count: topic_message.reply_count,
+            last_reply_at: topic_message
+                .last_reply_at
+                .map(|last_reply_at| last_reply_at.timestamp_millis()),
+            quote: topic_message.quote.map(Into::into),
+            forwarded_from: topic_message.forwarded_from.map(Into::into),
+            reactions: topic_message.reactions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<PublicReaction> for v1::Reaction {
+    fn from(reaction: PublicReaction) -> Self {
+        Self {
+            emoji: reaction.emoji,
+            count: reaction.count,
+            reacted_by_me: reaction.reacted_by_me,
+        }
+    }
+}
+
+impl TryFrom<v1::MarkReadRequest> for RequestMarkRead {
+    type Error = Status;
+
+    fn try_from(req: v1::MarkReadRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+            user_id: Default::default(),
+        })
+    }
+}
+
+impl From<PublicReadCursor> for v1::ReadCursor {
+    fn from(read_cursor: PublicReadCursor) -> Self {
+        Self {
+            topic_id: read_cursor.topic_id.to_string(),
+            user_id: read_cursor.user_id.to_string(),
+            last_read_message_id: read_cursor.last_read_message_id.to_string(),
+            read_at: read_cursor.read_at.timestamp_millis(),
+        }
+    }
+}
+
+impl TryFrom<v1::ListMessageReadersRequest> for RequestGetTopicMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::ListMessageReadersRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+        })
+    }
+}
+
+impl From<PublicMessageReader> for v1::MessageReader {
+    fn from(reader: PublicMessageReader) -> Self {
+        Self {
+            user_id: reader.user_id.to_string(),
+            username: reader.username,
+            read_at: reader.read_at.timestamp_millis(),
+        }
+    }
+}
+
+impl TryFrom<v1::ReactionRequest> for RequestReactTopicMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::ReactionRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+            user_id: Default::default(),
+            emoji: req.emoji,
+        })
+    }
+}
+
+impl From<PublicQuotedMessage> for v1::QuotedMessage {
+    fn from(quote: PublicQuotedMessage) -> Self {
+        Self {
+            message_id: quote.message_id.to_string(),
+            from_user_id: quote.from_user_id.to_string(),
+            message: quote.message,
+        }
+    }
+}
+
+impl From<PublicForwardedFrom> for v1::ForwardedFrom {
+    fn from(forwarded_from: PublicForwardedFrom) -> Self {
+        Self {
+            topic_id: forwarded_from.topic_id.to_string(),
+            message_id: forwarded_from.message_id.to_string(),
+            from_user_id: forwarded_from.from_user_id.to_string(),
+        }
+    }
+}
+
+impl TryFrom<v1::DeleteTopicMessageRequest> for RequestDeleteTopicMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::DeleteTopicMessageRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+            for_everyone: req.for_everyone,
+        })
+    }
+}
+
+impl TryFrom<v1::PurgeTopicMessageRequest> for RequestGetTopicMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::PurgeTopicMessageRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+        })
+    }
+}
+
+impl TryFrom<v1::ListMessageRevisionsRequest> for RequestGetMessageRevisions {
+    type Error = Status;
+
+    fn try_from(req: v1::ListMessageRevisionsRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+            page_size: req.page_size,
+            page_token: req.page_token,
+        })
+    }
+}
+
+impl From<PublicTopicMessageRevision> for v1::MessageRevision {
+    fn from(revision: PublicTopicMessageRevision) -> Self {
+        Self {
+            topic_id: revision.topic_id.to_string(),
+            message_id: revision.message_id.to_string(),
+            revision_id: revision.revision_id.to_string(),
+            edited_by: revision.edited_by.to_string(),
+            edited_at: revision.edited_at.timestamp_millis(),
+            message: revision.message,
+        }
+    }
+}
+
+impl TryFrom<v1::SubscribeTopicRequest> for RequestSubscribeTopics {
+    type Error = Status;
+
+    fn try_from(req: v1::SubscribeTopicRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_ids: req
+                .topic_ids
+                .iter()
+                .map(|topic_id| parse_timeuuid("topic_ids", topic_id))
+                .collect::<Result<_, _>>()?,
+            resume_from: req
+                .resume_from
+                .iter()
+                .map(|cursor| {
+                    Ok(RequestTopicCursor {
+                        topic_id: parse_timeuuid("resume_from.topic_id", &cursor.topic_id)?,
+                        last_message_id: parse_timeuuid("resume_from.last_message_id", &cursor.last_message_id)?,
+                    })
+                })
+                .collect::<Result<_, Status>>()?,
+        })
+    }
+}
+
+impl From<TopicEvent> for v1::TopicEvent {
+    fn from(event: TopicEvent) -> Self {
+        use v1::topic_event::Event;
+
+        let event = match event {
+            TopicEvent::MessageCreated(message) => Event::MessageCreated(message.into()),
+            TopicEvent::MessageUpdated(message) => Event::MessageUpdated(message.into()),
+            TopicEvent::MessageDeleted(message) => Event::MessageDeleted(message.into()),
+            TopicEvent::Typing(typing) => Event::Typing(typing.into()),
+            TopicEvent::ReactionChanged(change) => Event::ReactionChanged(change.into()),
+            TopicEvent::Presence(presence) => Event::Presence(presence.into()),
+            TopicEvent::PinChanged(change) => Event::PinChanged(change.into()),
+            TopicEvent::Notification(notification) => Event::Notification(notification.into()),
+        };
+        Self { event: Some(event) }
+    }
+}
+
+impl TryFrom<v1::TypingIndicator> for RequestNotifyTyping {
+    type Error = Status;
+
+    fn try_from(req: v1::TypingIndicator) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            username: String::new(),
+            stopped: req.stopped,
+        })
+    }
+}
+
+impl From<TypingIndicator> for v1::TypingIndicator {
+    fn from(typing: TypingIndicator) -> Self {
+        Self {
+            topic_id: typing.topic_id.to_string(),
+            username: typing.username,
+            stopped: typing.stopped,
+            expires_at: typing.expires_at.map(|expires_at| expires_at.timestamp_millis()),
+        }
+    }
+}
+
+impl From<PresenceUpdate> for v1::PresenceChange {
+    fn from(presence: PresenceUpdate) -> Self {
+        Self {
+            topic_id: presence.topic_id.to_string(),
+            username: presence.username,
+            status: presence.status,
+            last_seen_at: presence.last_seen_at.timestamp_millis(),
+        }
+    }
+}
+
+impl From<ReactionChange> for v1::ReactionChange {
+    fn from(change: ReactionChange) -> Self {
+        Self {
+            topic_id: change.topic_id.to_string(),
+            message_id: change.message_id.to_string(),
+            user_id: change.user_id.to_string(),
+            emoji: change.emoji,
+            added: change.added,
+            count: change.count,
+        }
+    }
+}
+
+impl TryFrom<v1::PinMessageRequest> for RequestPinTopicMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::PinMessageRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            message_id: parse_timeuuid("message_id", &req.message_id)?,
+            user_id: Default::default(),
+        })
+    }
+}
+
+impl TryFrom<v1::ListPinnedMessagesRequest> for RequestGetPinnedMessages {
+    type Error = Status;
+
+    fn try_from(req: v1::ListPinnedMessagesRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+        })
+    }
+}
+
+impl From<PublicPinnedMessage> for v1::PinnedMessage {
+    fn from(pinned_message: PublicPinnedMessage) -> Self {
+        Self {
+            message: Some(pinned_message.message.into()),
+            pinned_by: pinned_message.pinned_by.to_string(),
+            pinned_at: pinned_message.pinned_at.timestamp_millis(),
+        }
+    }
+}
+
+impl From<PinChange> for v1::PinChange {
+    fn from(change: PinChange) -> Self {
+        Self {
+            topic_id: change.topic_id.to_string(),
+            message_id: change.message_id.to_string(),
+            user_id: change.user_id.to_string(),
+            pinned: change.pinned,
+        }
+    }
+}
+
+// Topic user
+
+impl TryFrom<v1::ListTopicUsersRequest> for RequestGetUsersByTopicId {
+    type Error = Status;
+
+    fn try_from(req: v1::ListTopicUsersRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            page_size: req.page_size,
+            page_token: req.page_token,
+        })
+    }
+}
+
+impl From<PublicTopicUser> for v1::TopicUser {
+    fn from(topic_user: PublicTopicUser) -> Self {
+        Self {
+            topic_id: topic_user.topic_id.to_string(),
+            user_id: topic_user.user_id.to_string(),
+            username: topic_user.username,
+        }
+    }
+}
+
+impl TryFrom<v1::JoinTopicRequest> for RequestJoinTopic {
+    type Error = Status;
+
+    fn try_from(req: v1::JoinTopicRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            username: String::new(),
+            user_id: Default::default(),
+        })
+    }
+}
+
+impl TryFrom<v1::LeaveTopicRequest> for RequestLeaveTopic {
+    type Error = Status;
+
+    fn try_from(req: v1::LeaveTopicRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            username: String::new(),
+        })
+    }
+}
+
+impl TryFrom<v1::InviteTopicMemberRequest> for RequestInviteTopicMember {
+    type Error = Status;
+
+    fn try_from(req: v1::InviteTopicMemberRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            invited_by: String::new(),
+            username: req.username,
+        })
+    }
+}
+
+impl TryFrom<v1::RespondTopicInviteRequest> for RequestRespondTopicInvite {
+    type Error = Status;
+
+    fn try_from(req: v1::RespondTopicInviteRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            username: String::new(),
+            user_id: Default::default(),
+            accept: req.accept,
+        })
+    }
+}
+
+impl TryFrom<v1::RemoveTopicMemberRequest> for RequestRemoveTopicMember {
+    type Error = Status;
+
+    fn try_from(req: v1::RemoveTopicMemberRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            removed_by: String::new(),
+            username: req.username,
+        })
+    }
+}
+
+impl From<v1::ListTopicInvitesRequest> for RequestGetInvitesByUsername {
+    fn from(req: v1::ListTopicInvitesRequest) -> Self {
+        Self {
+            username: String::new(),
+            page_size: req.page_size,
+            page_token: req.page_token,
+        }
+    }
+}
+
+impl From<PublicTopicInvite> for v1::TopicInvite {
+    fn from(topic_invite: PublicTopicInvite) -> Self {
+        Self {
+            topic_id: topic_invite.topic_id.to_string(),
+            username: topic_invite.username,
+            invited_by: topic_invite.invited_by,
+            status: topic_invite.status,
+            created_at: topic_invite.created_at.timestamp_millis(),
+            updated_at: topic_invite.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+impl From<v1::SetPresenceRequest> for RequestSetPresence {
+    fn from(req: v1::SetPresenceRequest) -> Self {
+        Self {
+            username: String::new(),
+            status: req.status,
+        }
+    }
+}
+
+impl TryFrom<v1::GetPresenceRequest> for RequestGetPresence {
+    type Error = Status;
+
+    fn try_from(req: v1::GetPresenceRequest) -> Result<Self, Status> {
+        Ok(Self {
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            username: String::new(),
+            usernames: req.usernames,
+        })
+    }
+}
+
+impl From<PublicPresence> for v1::Presence {
+    fn from(presence: PublicPresence) -> Self {
+        Self {
+            username: presence.username,
+            status: presence.status,
+            last_seen_at: presence.last_seen_at.map(|last_seen_at| last_seen_at.timestamp_millis()),
+        }
+    }
+}
+
+// User topic
+
+impl From<v1::ListUserTopicsRequest> for RequestGetTopicsByUsername {
+    fn from(req: v1::ListUserTopicsRequest) -> Self {
+        Self {
+            username: String::new(),
+            topic_kind: req.topic_kind,
+            include_archived: req.include_archived,
+            page_size: req.page_size,
+            page_token: req.page_token,
+        }
+    }
+}
+
+impl From<PublicUserTopic> for v1::UserTopic {
+    fn from(user_topic: PublicUserTopic) -> Self {
+        Self {
+            topic_id: user_topic.topic_id.to_string(),
+            username: user_topic.username,
+            created_at: user_topic.created_at.timestamp_millis(),
+            topic_kind: user_topic.topic_kind,
+            topic_state: user_topic.topic_state,
+        }
+    }
+}
+
+// Notification
+
+impl From<v1::ListNotificationsRequest> for RequestGetNotificationByUsername {
+    fn from(req: v1::ListNotificationsRequest) -> Self {
+        Self {
+            username: String::new(),
+            page_size: req.page_size,
+            page_token: req.page_token,
+        }
+    }
+}
+
+impl From<PublicNotification> for v1::Notification {
+    fn from(notification: PublicNotification) -> Self {
+        Self {
+            username: notification.username,
+            from_user: notification.from_user,
+            message: notification.message,
+            created_at: notification.created_at.timestamp_millis(),
+            topic_id: notification.topic_id.to_string(),
+        }
+    }
+}
+
+// Latest message
+
+impl From<v1::ListLatestMessagesRequest> for RequestGetLatestMessagesByUserId {
+    fn from(req: v1::ListLatestMessagesRequest) -> Self {
+        Self {
+            user_id: Default::default(),
+            page_size: req.page_size,
+            page_token: req.page_token,
+        }
+    }
+}
+
+impl TryFrom<v1::UpdateLatestMessageRequest> for RequestUpdateLatestMessage {
+    type Error = Status;
+
+    fn try_from(req: v1::UpdateLatestMessageRequest) -> Result<Self, Status> {
+        Ok(Self {
+            latest_message_id: parse_timeuuid("latest_message_id", &req.latest_message_id)?,
+            latest_message_content: req.latest_message_content,
+            topic_id: parse_timeuuid("topic_id", &req.topic_id)?,
+            user_id: Default::default(),
+        })
+    }
+}
+
+impl From<PublicLatestMessage> for v1::LatestMessage {
+    fn from(latest_message: PublicLatestMessage) -> Self {
+        Self {
+            latest_message_id: latest_message.latest_message_id.to_string(),
+            latest_message_content: latest_message.latest_message_content,
+            topic_id: latest_message.topic_id.to_string(),
+            user_id: latest_message.user_id.to_string(),
+            unread_count: latest_message.unread_count,
+        }
+    }
+}
+
+impl From<v1::GetUnreadSummaryRequest> for RequestGetUnreadSummary {
+    fn from(_: v1::GetUnreadSummaryRequest) -> Self {
+        Self {
+            user_id: Default::default(),
+        }
+    }
+}
+
+impl From<PublicUnreadSummary> for v1::UnreadSummary {
+    fn from(summary: PublicUnreadSummary) -> Self {
+        Self {
+            topics: summary.topics.into_iter().map(Into::into).collect(),
+            total_unread: summary.total_unread,
+        }
+    }
+}
+
+impl From<PublicTopicUnread> for v1::TopicUnread {
+    fn from(topic: PublicTopicUnread) -> Self {
+        Self {
+            topic_id: topic.topic_id.to_string(),
+            unread_count: topic.unread_count,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod actor;
+pub mod auth;
+pub mod chat;
+pub mod convert;
+pub mod proto;
+pub mod service;
+pub mod status;
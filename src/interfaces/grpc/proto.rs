@@ -0,0 +1,14 @@
+/// Legacy `SendMessage(id, message)` envelope, kept as a compatibility shim.
+pub mod legacy {
+    tonic::include_proto!("message");
+}
+
+// Generated code: `Chat` frames carry whole requests, boxing them would only
+// make building one noisier.
+#[allow(clippy::large_enum_variant)]
+pub mod v1 {
+    tonic::include_proto!("message.v1");
+}
+
+pub const FILE_MESSAGE_DESCRIPTOR_SET: &[u8] =
+    tonic::include_file_descriptor_set!("message_descriptor");
@@ -0,0 +1,616 @@
+use super::actor::actor_from_request;
+use super::chat::ChatSession;
+use super::status::into_status;
+use super::proto::legacy::message_server::Message;
+use super::proto::legacy::{MessageRequest, MessageResponse};
+use super::proto::v1;
+use super::proto::v1::message_service_server::MessageService;
+use crate::application::latest_message::app::LatestMessageAppInterface;
+use crate::application::notification::app::NotificationAppInterface;
+use crate::application::topic::app::TopicAppInterface;
+use crate::application::topic_message::app::TopicMessageAppInterface;
+use crate::application::topic_user::app::TopicUserAppInterface;
+use crate::application::user_topic::app::UserTopicAppInterface;
+use crate::interfaces::message_handler::MessageHandler;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status, Streaming};
+
+const SUBSCRIPTION_BUFFER: usize = 64;
+const CHAT_BUFFER: usize = 64;
+
+pub type TopicEventStream = Pin<Box<dyn Stream<Item = Result<v1::TopicEvent, Status>> + Send>>;
+pub type ChatFrameStream = Pin<Box<dyn Stream<Item = Result<v1::ChatServerFrame, Status>> + Send>>;
+
+#[derive(Clone, Debug)]
+pub struct MessageGrpcService<
+    TAI: TopicAppInterface,
+    LTI: LatestMessageAppInterface,
+    NI: NotificationAppInterface,
+    UTI: UserTopicAppInterface,
+    TUI: TopicUserAppInterface,
+    TMI: TopicMessageAppInterface,
+> {
+    handler: Arc<MessageHandler<TAI, LTI, NI, UTI, TUI, TMI>>,
+}
+
+impl<
+    TAI: TopicAppInterface,
+    LTI: LatestMessageAppInterface,
+    NI: NotificationAppInterface,
+    UTI: UserTopicAppInterface,
+    TUI: TopicUserAppInterface,
+    TMI: TopicMessageAppInterface,
+> MessageGrpcService<TAI, LTI, NI, UTI, TUI, TMI>
+{
+    pub fn new(handler: Arc<MessageHandler<TAI, LTI, NI, UTI, TUI, TMI>>) -> Self {
+        Self { handler }
+    }
+}
+
+#[tonic::async_trait]
+impl<
+    TAI: TopicAppInterface,
+    LTI: LatestMessageAppInterface,
+    NI: NotificationAppInterface,
+    UTI: UserTopicAppInterface,
+    TUI: TopicUserAppInterface,
+    TMI: TopicMessageAppInterface,
+> Message for MessageGrpcService<TAI, LTI, NI, UTI, TUI, TMI>
+{
+    async fn send_message(
+        &self,
+        request: Request<MessageRequest>,
+    ) -> Result<Response<MessageResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let payload = request.into_inner();
+
+        let message = self
+            .handler
+            .dispatch(&actor, &payload.id, payload.message)
+            .await
+            .map_err(into_status)?;
+
+        Ok(Response::new(MessageResponse {
+            id: "OK".to_string(),
+            message,
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl<
+    TAI: TopicAppInterface,
+    LTI: LatestMessageAppInterface,
+    NI: NotificationAppInterface,
+    UTI: UserTopicAppInterface,
+    TUI: TopicUserAppInterface,
+    TMI: TopicMessageAppInterface,
+> MessageService for MessageGrpcService<TAI, LTI, NI, UTI, TUI, TMI>
+{
+    type SubscribeTopicStream = TopicEventStream;
+    type ChatStream = ChatFrameStream;
+
+    async fn create_topic(
+        &self,
+        request: Request<v1::CreateTopicRequest>,
+    ) -> Result<Response<v1::Topic>, Status> {
+        let actor = actor_from_request(&request)?;
+        let req = request.into_inner().into();
+        let topic = self.handler.create_topic(&actor, req).await.map_err(into_status)?;
+        Ok(Response::new(topic.into()))
+    }
+
+    async fn get_topic(
+        &self,
+        request: Request<v1::GetTopicRequest>,
+    ) -> Result<Response<v1::GetTopicResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let topics = self.handler.find_topic(&actor, query).await.map_err(into_status)?;
+        Ok(Response::new(v1::GetTopicResponse {
+            topics: topics.items.into_iter().map(Into::into).collect(),
+            next_page_token: topics.next_page_token,
+        }))
+    }
+
+    async fn update_topic(
+        &self,
+        request: Request<v1::UpdateTopicRequest>,
+    ) -> Result<Response<v1::Topic>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let topic = self.handler.update_topic(&actor, query).await.map_err(into_status)?;
+        Ok(Response::new(topic.into()))
+    }
+
+    async fn open_direct_conversation(
+        &self,
+        request: Request<v1::OpenDirectConversationRequest>,
+    ) -> Result<Response<v1::DirectConversation>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let conversation = self
+            .handler
+            .open_direct_conversation(&actor, body)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(conversation.into()))
+    }
+
+    async fn open_group_conversation(
+        &self,
+        request: Request<v1::OpenGroupConversationRequest>,
+    ) -> Result<Response<v1::GroupConversation>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let conversation = self
+            .handler
+            .open_group_conversation(&actor, body)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(conversation.into()))
+    }
+
+    async fn promote_conversation(
+        &self,
+        request: Request<v1::PromoteConversationRequest>,
+    ) -> Result<Response<v1::Topic>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let topic = self.handler.promote_conversation(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(topic.into()))
+    }
+
+    async fn set_topic_state(
+        &self,
+        request: Request<v1::SetTopicStateRequest>,
+    ) -> Result<Response<v1::Topic>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let topic = self.handler.set_topic_state(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(topic.into()))
+    }
+
+    async fn list_topic_messages(
+        &self,
+        request: Request<v1::ListTopicMessagesRequest>,
+    ) -> Result<Response<v1::ListTopicMessagesResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let messages = self
+            .handler
+            .find_topic_messages(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(v1::ListTopicMessagesResponse {
+            messages: messages.items.into_iter().map(Into::into).collect(),
+            next_page_token: messages.next_page_token,
+        }))
+    }
+
+    async fn list_thread_messages(
+        &self,
+        request: Request<v1::ListThreadMessagesRequest>,
+    ) -> Result<Response<v1::ListTopicMessagesResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let messages = self
+            .handler
+            .find_thread_messages(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(v1::ListTopicMessagesResponse {
+            messages: messages.items.into_iter().map(Into::into).collect(),
+            next_page_token: messages.next_page_token,
+        }))
+    }
+
+    async fn post_message(
+        &self,
+        request: Request<v1::PostMessageRequest>,
+    ) -> Result<Response<v1::TopicMessage>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let message = self
+            .handler
+            .post_topic_message(&actor, body)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(message.into()))
+    }
+
+    async fn forward_message(
+        &self,
+        request: Request<v1::ForwardMessageRequest>,
+    ) -> Result<Response<v1::TopicMessage>, Status> {
+        let actor = actor_from_request(&request)?;
+        let req = request.into_inner().try_into()?;
+        let message = self
+            .handler
+            .forward_topic_message(&actor, req)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(message.into()))
+    }
+
+    async fn update_topic_message(
+        &self,
+        request: Request<v1::UpdateTopicMessageRequest>,
+    ) -> Result<Response<v1::TopicMessage>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let message = self
+            .handler
+            .update_topic_message(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(message.into()))
+    }
+
+    async fn delete_topic_message(
+        &self,
+        request: Request<v1::DeleteTopicMessageRequest>,
+    ) -> Result<Response<v1::DeleteTopicMessageResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let req = request.into_inner().try_into()?;
+        self.handler.delete_topic_message(&actor, req).await.map_err(into_status)?;
+        Ok(Response::new(v1::DeleteTopicMessageResponse {}))
+    }
+
+    async fn purge_topic_message(
+        &self,
+        request: Request<v1::PurgeTopicMessageRequest>,
+    ) -> Result<Response<v1::PurgeTopicMessageResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let req = request.into_inner().try_into()?;
+        self.handler.purge_topic_message(&actor, req).await.map_err(into_status)?;
+        Ok(Response::new(v1::PurgeTopicMessageResponse {}))
+    }
+
+    async fn mark_read(
+        &self,
+        request: Request<v1::MarkReadRequest>,
+    ) -> Result<Response<v1::ReadCursor>, Status> {
+        let actor = actor_from_request(&request)?;
+        let req = request.into_inner().try_into()?;
+        let read_cursor = self.handler.mark_read(&actor, req).await.map_err(into_status)?;
+        Ok(Response::new(read_cursor.into()))
+    }
+
+    async fn list_message_readers(
+        &self,
+        request: Request<v1::ListMessageReadersRequest>,
+    ) -> Result<Response<v1::ListMessageReadersResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let readers = self
+            .handler
+            .find_message_readers(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(v1::ListMessageReadersResponse {
+            readers: readers.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn add_reaction(
+        &self,
+        request: Request<v1::ReactionRequest>,
+    ) -> Result<Response<v1::ReactionResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let reactions = self.handler.add_reaction(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(v1::ReactionResponse {
+            reactions: reactions.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn remove_reaction(
+        &self,
+        request: Request<v1::ReactionRequest>,
+    ) -> Result<Response<v1::ReactionResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let reactions = self.handler.remove_reaction(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(v1::ReactionResponse {
+            reactions: reactions.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn pin_message(
+        &self,
+        request: Request<v1::PinMessageRequest>,
+    ) -> Result<Response<v1::PinnedMessage>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let pinned_message = self.handler.pin_message(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(pinned_message.into()))
+    }
+
+    async fn unpin_message(
+        &self,
+        request: Request<v1::PinMessageRequest>,
+    ) -> Result<Response<v1::UnpinMessageResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        self.handler.unpin_message(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(v1::UnpinMessageResponse {}))
+    }
+
+    async fn list_pinned_messages(
+        &self,
+        request: Request<v1::ListPinnedMessagesRequest>,
+    ) -> Result<Response<v1::ListPinnedMessagesResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let pinned_messages = self
+            .handler
+            .find_pinned_messages(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(v1::ListPinnedMessagesResponse {
+            pinned_messages: pinned_messages.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn list_message_revisions(
+        &self,
+        request: Request<v1::ListMessageRevisionsRequest>,
+    ) -> Result<Response<v1::ListMessageRevisionsResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let revisions = self
+            .handler
+            .find_message_revisions(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(v1::ListMessageRevisionsResponse {
+            revisions: revisions.items.into_iter().map(Into::into).collect(),
+            next_page_token: revisions.next_page_token,
+        }))
+    }
+
+    async fn subscribe_topic(
+        &self,
+        request: Request<v1::SubscribeTopicRequest>,
+    ) -> Result<Response<Self::SubscribeTopicStream>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let mut subscription = self
+            .handler
+            .subscribe_topics(&actor, query)
+            .await
+            .map_err(into_status)?;
+
+        let (tx, rx) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        tokio::spawn(async move {
+            loop {
+                let item = match subscription.next().await {
+                    Ok(Some(event)) => Ok(event.into()),
+                    Ok(None) => break,
+                    Err(err) => Err(into_status(err)),
+                };
+                let failed = item.is_err();
+                // Stop following the hub once the client is gone or the stream has failed.
+                if tx.send(item).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn chat(
+        &self,
+        request: Request<Streaming<v1::ChatClientFrame>>,
+    ) -> Result<Response<Self::ChatStream>, Status> {
+        let actor = actor_from_request(&request)?;
+        let inbound = request.into_inner();
+        let (tx, rx) = mpsc::channel(CHAT_BUFFER);
+        let session = ChatSession::new(Arc::clone(&self.handler), actor, tx);
+        tokio::spawn(session.run(inbound));
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn set_topic_role(
+        &self,
+        request: Request<v1::SetTopicRoleRequest>,
+    ) -> Result<Response<v1::TopicRole>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let role = self.handler.set_topic_role(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(role.into()))
+    }
+
+    async fn list_topic_roles(
+        &self,
+        request: Request<v1::ListTopicRolesRequest>,
+    ) -> Result<Response<v1::ListTopicRolesResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let roles = self.handler.find_topic_roles(&actor, query).await.map_err(into_status)?;
+        Ok(Response::new(v1::ListTopicRolesResponse {
+            roles: roles.items.into_iter().map(Into::into).collect(),
+            next_page_token: roles.next_page_token,
+        }))
+    }
+
+    async fn list_topic_users(
+        &self,
+        request: Request<v1::ListTopicUsersRequest>,
+    ) -> Result<Response<v1::ListTopicUsersResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let users = self.handler.find_topic_users(&actor, query).await.map_err(into_status)?;
+        Ok(Response::new(v1::ListTopicUsersResponse {
+            users: users.items.into_iter().map(Into::into).collect(),
+            next_page_token: users.next_page_token,
+        }))
+    }
+
+    async fn join_topic(
+        &self,
+        request: Request<v1::JoinTopicRequest>,
+    ) -> Result<Response<v1::TopicUser>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let user = self.handler.join_topic(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(user.into()))
+    }
+
+    async fn leave_topic(
+        &self,
+        request: Request<v1::LeaveTopicRequest>,
+    ) -> Result<Response<v1::LeaveTopicResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        self.handler.leave_topic(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(v1::LeaveTopicResponse {}))
+    }
+
+    async fn invite_topic_member(
+        &self,
+        request: Request<v1::InviteTopicMemberRequest>,
+    ) -> Result<Response<v1::TopicInvite>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let invite = self.handler.invite_topic_member(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(invite.into()))
+    }
+
+    async fn respond_topic_invite(
+        &self,
+        request: Request<v1::RespondTopicInviteRequest>,
+    ) -> Result<Response<v1::TopicInvite>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        let invite = self.handler.respond_topic_invite(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(invite.into()))
+    }
+
+    async fn remove_topic_member(
+        &self,
+        request: Request<v1::RemoveTopicMemberRequest>,
+    ) -> Result<Response<v1::RemoveTopicMemberResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().try_into()?;
+        self.handler.remove_topic_member(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(v1::RemoveTopicMemberResponse {}))
+    }
+
+    async fn list_topic_invites(
+        &self,
+        request: Request<v1::ListTopicInvitesRequest>,
+    ) -> Result<Response<v1::ListTopicInvitesResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().into();
+        let invites = self.handler.find_topic_invites(&actor, query).await.map_err(into_status)?;
+        Ok(Response::new(v1::ListTopicInvitesResponse {
+            invites: invites.items.into_iter().map(Into::into).collect(),
+            next_page_token: invites.next_page_token,
+        }))
+    }
+
+    async fn set_presence(
+        &self,
+        request: Request<v1::SetPresenceRequest>,
+    ) -> Result<Response<v1::Presence>, Status> {
+        let actor = actor_from_request(&request)?;
+        let body = request.into_inner().into();
+        let presence = self.handler.set_presence(&actor, body).await.map_err(into_status)?;
+        Ok(Response::new(presence.into()))
+    }
+
+    async fn get_presence(
+        &self,
+        request: Request<v1::GetPresenceRequest>,
+    ) -> Result<Response<v1::GetPresenceResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let presence = self.handler.find_presence(&actor, query).await.map_err(into_status)?;
+        Ok(Response::new(v1::GetPresenceResponse {
+            presence: presence.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn list_user_topics(
+        &self,
+        request: Request<v1::ListUserTopicsRequest>,
+    ) -> Result<Response<v1::ListUserTopicsResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().into();
+        let topics = self.handler.find_user_topics(&actor, query).await.map_err(into_status)?;
+        Ok(Response::new(v1::ListUserTopicsResponse {
+            topics: topics.items.into_iter().map(Into::into).collect(),
+            next_page_token: topics.next_page_token,
+        }))
+    }
+
+    async fn list_notifications(
+        &self,
+        request: Request<v1::ListNotificationsRequest>,
+    ) -> Result<Response<v1::ListNotificationsResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().into();
+        let notifications = self
+            .handler
+            .find_notifications(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(v1::ListNotificationsResponse {
+            notifications: notifications.items.into_iter().map(Into::into).collect(),
+            next_page_token: notifications.next_page_token,
+        }))
+    }
+
+    async fn list_latest_messages(
+        &self,
+        request: Request<v1::ListLatestMessagesRequest>,
+    ) -> Result<Response<v1::ListLatestMessagesResponse>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().into();
+        let latest_messages = self
+            .handler
+            .find_latest_messages(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(v1::ListLatestMessagesResponse {
+            latest_messages: latest_messages.items.into_iter().map(Into::into).collect(),
+            next_page_token: latest_messages.next_page_token,
+        }))
+    }
+
+    async fn get_unread_summary(
+        &self,
+        request: Request<v1::GetUnreadSummaryRequest>,
+    ) -> Result<Response<v1::UnreadSummary>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().into();
+        let summary = self
+            .handler
+            .find_unread_summary(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(summary.into()))
+    }
+
+    async fn update_latest_message(
+        &self,
+        request: Request<v1::UpdateLatestMessageRequest>,
+    ) -> Result<Response<v1::LatestMessage>, Status> {
+        let actor = actor_from_request(&request)?;
+        let query = request.into_inner().try_into()?;
+        let latest_message = self
+            .handler
+            .update_latest_message(&actor, query)
+            .await
+            .map_err(into_status)?;
+        Ok(Response::new(latest_message.into()))
+    }
+}
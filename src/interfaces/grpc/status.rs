@@ -0,0 +1,72 @@
+use crate::application::error::ApplicationError;
+use crate::application::live::hub::TopicSubscriptionError;
+use std::collections::HashMap;
+use tonic::{Code, Status};
+use tonic_types::{ErrorDetails, StatusExt};
+use uptop_core::common::result::AppError;
+
+/// `ErrorInfo.domain` of every error this service returns.
+const ERROR_DOMAIN: &str = "message.uptop";
+
+/// Maps a use case failure onto a status carrying `google.rpc` error details:
+/// an `ErrorInfo` with a stable reason, plus `BadRequest` field violations or
+/// `ResourceInfo` where they apply. Unclassified errors are logged and hidden.
+pub(crate) fn into_status(err: anyhow::Error) -> Status {
+    if let Some(err) = err.downcast_ref::<ApplicationError>() {
+        return application_status(err);
+    }
+
+    if let Some(TopicSubscriptionError::Lagged { skipped }) = err.downcast_ref() {
+        let mut details = ErrorDetails::new();
+        details.set_error_info(
+            "SUBSCRIPTION_LAGGED",
+            ERROR_DOMAIN,
+            HashMap::from([("skipped".to_string(), skipped.to_string())]),
+        );
+        return Status::with_error_details(Code::Aborted, err.to_string(), details);
+    }
+
+    if let Some(AppError::BadRequest { msg }) = err.downcast_ref() {
+        let mut details = ErrorDetails::new();
+        details.set_error_info("INVALID_ARGUMENT", ERROR_DOMAIN, HashMap::new());
+        return Status::with_error_details(Code::InvalidArgument, msg.to_owned(), details);
+    }
+
+    tracing::error!("{err:?}");
+    let mut details = ErrorDetails::new();
+    details.set_error_info("INTERNAL", ERROR_DOMAIN, HashMap::new());
+    Status::with_error_details(Code::Internal, "internal server error", details)
+}
+
+pub(crate) fn application_status(err: &ApplicationError) -> Status {
+    let mut details = ErrorDetails::new();
+    details.set_error_info(err.reason(), ERROR_DOMAIN, HashMap::new());
+
+    let code = match err {
+        ApplicationError::InvalidArgument { violations, .. } => {
+            for violation in violations {
+                details.add_bad_request_violation(&violation.field, &violation.description);
+            }
+            Code::InvalidArgument
+        }
+        ApplicationError::NotFound { resource } => {
+            details.set_resource_info(*resource, "", "", err.to_string());
+            Code::NotFound
+        }
+        ApplicationError::Unauthenticated { .. } => Code::Unauthenticated,
+        ApplicationError::PermissionDenied { .. } => Code::PermissionDenied,
+        ApplicationError::AlreadyExists { resource } => {
+            details.set_resource_info(*resource, "", "", err.to_string());
+            Code::AlreadyExists
+        }
+        ApplicationError::FailedPrecondition { .. } => Code::FailedPrecondition,
+        ApplicationError::Unavailable => Code::Unavailable,
+    };
+
+    Status::with_error_details(code, err.to_string(), details)
+}
+
+/// Rejects one field of an incoming message before it reaches a use case.
+pub(crate) fn invalid_field(field: &str, description: impl Into<String>) -> Status {
+    application_status(&ApplicationError::invalid_field(field, description))
+}
@@ -3,24 +3,32 @@ use crate::application::topic::{
     request::{RequestCreateTopic},
 };
 use std::sync::Arc;
+use anyhow::anyhow;
 use uptop_core::common::result::AppResult;
+use crate::application::actor::Actor;
+use crate::application::error::ApplicationError;
 use crate::application::latest_message::app::LatestMessageAppInterface;
-use crate::application::latest_message::request::{RequestGetLatestMessagesByUserId, RequestUpdateLatestMessage};
-use crate::application::latest_message::response::PublicLatestMessage;
+use crate::application::latest_message::request::{RequestGetLatestMessagesByUserId, RequestGetUnreadSummary, RequestUpdateLatestMessage};
+use crate::application::latest_message::response::{PublicLatestMessage, PublicUnreadSummary};
 use crate::application::notification::app::NotificationAppInterface;
-use crate::application::notification::request::{RequestGetNotificationByUsername, RequestUpdateNotification};
+use crate::application::notification::request::RequestGetNotificationByUsername;
 use crate::application::notification::response::PublicNotification;
-use crate::application::topic::request::{RequestGetTopicByPartitionKey, RequestUpdateTopic};
-use crate::application::topic::response::PublicTopic;
+use crate::application::topic::request::{RequestGetTopicByIndexKey, RequestGetTopicByPartitionKey, RequestOpenDirectConversation, RequestOpenGroupConversation, RequestPromoteConversation, RequestSetTopicState, RequestUpdateTopic};
+use crate::application::topic::response::{PublicDirectConversation, PublicGroupConversation, PublicTopic};
 use crate::application::topic_message::app::TopicMessageAppInterface;
-use crate::application::topic_message::request::{RequestGetMessagesByTopicId, RequestUpdateTopicMessage};
-use crate::application::topic_message::response::PublicTopicMessage;
+use crate::application::live::hub::TopicSubscription;
+use crate::application::pagination::Page;
+use crate::application::topic_message::request::{RequestDeleteTopicMessage, RequestForwardTopicMessage, RequestGetMessageRevisions, RequestGetThreadMessages, RequestGetTopicMessage, RequestGetMessagesByTopicId, RequestMarkRead, RequestNotifyTyping, RequestGetPinnedMessages, RequestPinTopicMessage, RequestPostTopicMessage, RequestReactTopicMessage, RequestSubscribeTopics, RequestUpdateTopicMessage};
+use crate::application::topic_message::response::{PublicMessageReader, PublicPinnedMessage, PublicReaction, PublicReadCursor, PublicTopicMessage, PublicTopicMessageRevision};
+use crate::application::topic_role::request::{RequestGetRolesByTopicId, RequestSetTopicRole};
+use crate::application::topic_role::response::PublicTopicRole;
 use crate::application::topic_user::app::TopicUserAppInterface;
-use crate::application::topic_user::request::{RequestGetUsersByTopicId, RequestUpdateTopicUser};
-use crate::application::topic_user::response::PublicTopicUser;
+use crate::application::topic_user::request::{RequestGetInvitesByUsername, RequestGetPresence, RequestGetUsersByTopicId, RequestInviteTopicMember, RequestJoinTopic, RequestLeaveTopic, RequestRemoveTopicMember, RequestRespondTopicInvite, RequestSetPresence};
+use crate::application::topic_user::response::{PublicPresence, PublicTopicInvite, PublicTopicUser};
 use crate::application::user_topic::app::UserTopicAppInterface;
-use crate::application::user_topic::request::{RequestGetTopicsByUsername, RequestUpdateUserTopic};
+use crate::application::user_topic::request::RequestGetTopicsByUsername;
 use crate::application::user_topic::response::PublicUserTopic;
+use crate::interfaces::actions::MessageModuleServices;
 
 #[derive(Clone, Debug)]
 pub struct MessageHandler<
@@ -39,6 +47,10 @@ pub struct MessageHandler<
     pub topic_message_app: Arc<TMI>,
 
 }
+
+/// Typed operations, shared by the gRPC service and the JSON entry points below.
+/// Request fields naming the caller are overwritten with `actor`, so nobody can
+/// act on behalf of someone else.
 impl<
     TAI: TopicAppInterface,
     LTI: LatestMessageAppInterface,
@@ -48,109 +60,833 @@ impl<
     TMI: TopicMessageAppInterface
 > MessageHandler<TAI, LTI, NI, UTI, TUI, TMI>
 {
-    pub async fn on_create_new_topic(&self, payload: String,
-    ) -> AppResult<String> {
-        let body: RequestCreateTopic = serde_json::from_str(&payload)?;
+    pub async fn create_topic(&self, actor: &Actor, body: RequestCreateTopic) -> AppResult<PublicTopic> {
+        let req = body.try_into_domain()?;
+        self.topic_app.create_topic(actor, req).await
+    }
+
+    pub async fn find_topic(
+        &self,
+        actor: &Actor,
+        query: RequestGetTopicByPartitionKey,
+    ) -> AppResult<Page<PublicTopic>> {
+        let query = query.try_into_domain()?;
+        self.topic_app.find_topic_by_partition_key(actor, &query).await
+    }
+
+    pub async fn find_topics_by_name(
+        &self,
+        actor: &Actor,
+        query: RequestGetTopicByIndexKey,
+    ) -> AppResult<Page<PublicTopic>> {
+        let query = query.try_into_domain()?;
+        self.topic_app.find_topic_by_index_key(actor, &query).await
+    }
+
+    pub async fn update_topic(&self, actor: &Actor, query: RequestUpdateTopic) -> AppResult<PublicTopic> {
+        let query = query.try_into_domain()?;
+        self.topic_app.update_topic(actor, &query).await
+    }
+
+    pub async fn set_topic_role(
+        &self,
+        actor: &Actor,
+        body: RequestSetTopicRole,
+    ) -> AppResult<PublicTopicRole> {
         let req = body.try_into_domain()?;
+        self.topic_app.set_topic_role(actor, &req).await
+    }
+
+    pub async fn find_topic_roles(
+        &self,
+        actor: &Actor,
+        query: RequestGetRolesByTopicId,
+    ) -> AppResult<Page<PublicTopicRole>> {
+        let query = query.try_into_domain()?;
+        self.topic_app.find_list_roles_by_topic_id(actor, &query).await
+    }
+
+    pub async fn open_direct_conversation(
+        &self,
+        actor: &Actor,
+        body: RequestOpenDirectConversation,
+    ) -> AppResult<PublicDirectConversation> {
+        let req = RequestOpenDirectConversation {
+            username: actor.username.to_owned(),
+            user_id: actor.user_id,
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_app.open_direct_conversation(&req).await
+    }
+
+    pub async fn open_group_conversation(
+        &self,
+        actor: &Actor,
+        body: RequestOpenGroupConversation,
+    ) -> AppResult<PublicGroupConversation> {
+        let req = RequestOpenGroupConversation {
+            username: actor.username.to_owned(),
+            user_id: actor.user_id,
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_app.open_group_conversation(&req).await
+    }
+
+    pub async fn promote_conversation(
+        &self,
+        actor: &Actor,
+        body: RequestPromoteConversation,
+    ) -> AppResult<PublicTopic> {
+        let req = RequestPromoteConversation {
+            username: actor.username.to_owned(),
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_app.promote_conversation(&req).await
+    }
+
+    pub async fn set_topic_state(&self, actor: &Actor, body: RequestSetTopicState) -> AppResult<PublicTopic> {
+        let req = RequestSetTopicState {
+            username: actor.username.to_owned(),
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_app.set_topic_state(actor, &req).await
+    }
+
+    pub async fn find_notifications(
+        &self,
+        actor: &Actor,
+        query: RequestGetNotificationByUsername,
+    ) -> AppResult<Page<PublicNotification>> {
+        let query = RequestGetNotificationByUsername {
+            username: actor.username.to_owned(),
+            ..query
+        }
+        .try_into_domain()?;
+        self.notification_app.find_list_notification_by_username(&query).await
+    }
+
+    pub async fn find_user_topics(
+        &self,
+        actor: &Actor,
+        query: RequestGetTopicsByUsername,
+    ) -> AppResult<Page<PublicUserTopic>> {
+        let query = RequestGetTopicsByUsername {
+            username: actor.username.to_owned(),
+            ..query
+        }
+        .try_into_domain()?;
+        self.user_topic_app.find_list_topics_by_username(&query).await
+    }
+
+    pub async fn find_topic_users(
+        &self,
+        actor: &Actor,
+        query: RequestGetUsersByTopicId,
+    ) -> AppResult<Page<PublicTopicUser>> {
+        let query = query.try_into_domain()?;
+        self.topic_user_app.find_list_users_by_topic_id(actor, &query).await
+    }
+
+    pub async fn join_topic(&self, actor: &Actor, body: RequestJoinTopic) -> AppResult<PublicTopicUser> {
+        let req = RequestJoinTopic {
+            username: actor.username.to_owned(),
+            user_id: actor.user_id,
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_user_app.join_topic(&req).await
+    }
+
+    pub async fn leave_topic(&self, actor: &Actor, body: RequestLeaveTopic) -> AppResult<()> {
+        let req = RequestLeaveTopic {
+            username: actor.username.to_owned(),
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_user_app.leave_topic(&req).await
+    }
+
+    pub async fn invite_topic_member(
+        &self,
+        actor: &Actor,
+        body: RequestInviteTopicMember,
+    ) -> AppResult<PublicTopicInvite> {
+        let req = RequestInviteTopicMember {
+            invited_by: actor.username.to_owned(),
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_user_app.invite_topic_member(&req).await
+    }
+
+    pub async fn respond_topic_invite(
+        &self,
+        actor: &Actor,
+        body: RequestRespondTopicInvite,
+    ) -> AppResult<PublicTopicInvite> {
+        let req = RequestRespondTopicInvite {
+            username: actor.username.to_owned(),
+            user_id: actor.user_id,
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_user_app.respond_topic_invite(&req).await
+    }
+
+    pub async fn remove_topic_member(&self, actor: &Actor, body: RequestRemoveTopicMember) -> AppResult<()> {
+        let req = RequestRemoveTopicMember {
+            removed_by: actor.username.to_owned(),
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_user_app.remove_topic_member(&req).await
+    }
+
+    pub async fn find_topic_invites(
+        &self,
+        actor: &Actor,
+        query: RequestGetInvitesByUsername,
+    ) -> AppResult<Page<PublicTopicInvite>> {
+        let query = RequestGetInvitesByUsername {
+            username: actor.username.to_owned(),
+            ..query
+        }
+        .try_into_domain()?;
+        self.topic_user_app.find_list_invites_by_username(&query).await
+    }
 
-        let result = self.topic_app.create_topic(req).await?;
+    pub async fn set_presence(&self, actor: &Actor, body: RequestSetPresence) -> AppResult<PublicPresence> {
+        let req = RequestSetPresence {
+            username: actor.username.to_owned(),
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_user_app.set_presence(&req).await
+    }
+
+    pub async fn find_presence(&self, actor: &Actor, query: RequestGetPresence) -> AppResult<Vec<PublicPresence>> {
+        let query = RequestGetPresence {
+            username: actor.username.to_owned(),
+            ..query
+        }
+        .try_into_domain()?;
+        self.topic_user_app.find_list_presence(&query).await
+    }
+
+    pub async fn find_topic_messages(
+        &self,
+        actor: &Actor,
+        query: RequestGetMessagesByTopicId,
+    ) -> AppResult<Page<PublicTopicMessage>> {
+        let query = query.try_into_domain()?;
+        self.topic_message_app.find_list_messages_by_topic_id(actor, &query).await
+    }
+
+    pub async fn find_thread_messages(
+        &self,
+        actor: &Actor,
+        query: RequestGetThreadMessages,
+    ) -> AppResult<Page<PublicTopicMessage>> {
+        let query = query.try_into_domain()?;
+        self.topic_message_app.find_list_messages_by_thread(actor, &query).await
+    }
+
+    pub async fn post_topic_message(
+        &self,
+        actor: &Actor,
+        body: RequestPostTopicMessage,
+    ) -> AppResult<PublicTopicMessage> {
+        let req = RequestPostTopicMessage {
+            from_user_id: actor.user_id,
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_message_app.post_message(actor, req).await
+    }
+
+    pub async fn forward_topic_message(
+        &self,
+        actor: &Actor,
+        req: RequestForwardTopicMessage,
+    ) -> AppResult<PublicTopicMessage> {
+        let req = RequestForwardTopicMessage {
+            from_user_id: actor.user_id,
+            ..req
+        };
+        self.topic_message_app.forward_message(actor, &req).await
+    }
+
+    pub async fn update_topic_message(
+        &self,
+        actor: &Actor,
+        query: RequestUpdateTopicMessage,
+    ) -> AppResult<PublicTopicMessage> {
+        let query = RequestUpdateTopicMessage {
+            from_user_id: actor.user_id,
+            ..query
+        }
+        .try_into_domain()?;
+        self.topic_message_app.update_topic_message(actor, &query).await
+    }
+
+    pub async fn delete_topic_message(&self, actor: &Actor, req: RequestDeleteTopicMessage) -> AppResult<()> {
+        self.topic_message_app.delete_topic_message(actor, &req).await
+    }
+
+    pub async fn purge_topic_message(&self, actor: &Actor, req: RequestGetTopicMessage) -> AppResult<()> {
+        self.topic_message_app.purge_topic_message(actor, &req).await
+    }
+
+    pub async fn mark_read(&self, actor: &Actor, req: RequestMarkRead) -> AppResult<PublicReadCursor> {
+        let req = RequestMarkRead {
+            user_id: actor.user_id,
+            ..req
+        };
+        self.topic_message_app.mark_read(actor, &req).await
+    }
+
+    pub async fn find_message_readers(
+        &self,
+        actor: &Actor,
+        query: RequestGetTopicMessage,
+    ) -> AppResult<Vec<PublicMessageReader>> {
+        self.topic_message_app.find_list_message_readers(actor, &query).await
+    }
+
+    pub async fn add_reaction(
+        &self,
+        actor: &Actor,
+        body: RequestReactTopicMessage,
+    ) -> AppResult<Vec<PublicReaction>> {
+        let req = RequestReactTopicMessage {
+            user_id: actor.user_id,
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_message_app.add_reaction(actor, &req).await
+    }
+
+    pub async fn remove_reaction(
+        &self,
+        actor: &Actor,
+        body: RequestReactTopicMessage,
+    ) -> AppResult<Vec<PublicReaction>> {
+        let req = RequestReactTopicMessage {
+            user_id: actor.user_id,
+            ..body
+        }
+        .try_into_domain()?;
+        self.topic_message_app.remove_reaction(actor, &req).await
+    }
+
+    pub async fn pin_message(&self, actor: &Actor, body: RequestPinTopicMessage) -> AppResult<PublicPinnedMessage> {
+        let req = RequestPinTopicMessage {
+            user_id: actor.user_id,
+            ..body
+        };
+        self.topic_message_app.pin_message(actor, &req).await
+    }
+
+    pub async fn unpin_message(&self, actor: &Actor, body: RequestPinTopicMessage) -> AppResult<()> {
+        let req = RequestPinTopicMessage {
+            user_id: actor.user_id,
+            ..body
+        };
+        self.topic_message_app.unpin_message(actor, &req).await
+    }
+
+    pub async fn find_pinned_messages(
+        &self,
+        actor: &Actor,
+        query: RequestGetPinnedMessages,
+    ) -> AppResult<Vec<PublicPinnedMessage>> {
+        self.topic_message_app.find_list_pinned_messages(actor, &query).await
+    }
+
+    pub async fn find_message_revisions(
+        &self,
+        actor: &Actor,
+        query: RequestGetMessageRevisions,
+    ) -> AppResult<Page<PublicTopicMessageRevision>> {
+        let query = query.try_into_domain()?;
+        self.topic_message_app.find_list_revisions_by_message_id(actor, &query).await
+    }
+
+    pub async fn subscribe_topics(
+        &self,
+        actor: &Actor,
+        query: RequestSubscribeTopics,
+    ) -> AppResult<TopicSubscription> {
+        let query = query.try_into_domain()?;
+        self.topic_message_app.subscribe_topics(actor, &query).await
+    }
+
+    pub async fn notify_typing(&self, actor: &Actor, query: RequestNotifyTyping) -> AppResult<()> {
+        let query = RequestNotifyTyping {
+            username: actor.username.to_owned(),
+            ..query
+        }
+        .try_into_domain()?;
+        self.topic_message_app.notify_typing(actor, &query).await
+    }
+
+    pub async fn find_latest_messages(
+        &self,
+        actor: &Actor,
+        query: RequestGetLatestMessagesByUserId,
+    ) -> AppResult<Page<PublicLatestMessage>> {
+        let query = RequestGetLatestMessagesByUserId {
+            user_id: actor.user_id,
+            ..query
+        }
+        .try_into_domain()?;
+        self.latest_message_app.find_list_latest_messages_by_user_id(&query).await
+    }
+
+    pub async fn find_unread_summary(
+        &self,
+        actor: &Actor,
+        _query: RequestGetUnreadSummary,
+    ) -> AppResult<PublicUnreadSummary> {
+        // The only field is the user, and that is always the caller.
+        let query = RequestGetUnreadSummary {
+            user_id: actor.user_id,
+        };
+        self.latest_message_app.find_unread_summary(&query).await
+    }
+
+    pub async fn update_latest_message(
+        &self,
+        actor: &Actor,
+        query: RequestUpdateLatestMessage,
+    ) -> AppResult<PublicLatestMessage> {
+        let query = RequestUpdateLatestMessage {
+            user_id: actor.user_id,
+            ..query
+        }
+        .try_into_domain()?;
+        self.latest_message_app.update_latest_message(&query).await
+    }
+}
+
+/// JSON entry points backing the legacy `SendMessage(id, message)` envelope.
+impl<
+    TAI: TopicAppInterface,
+    LTI: LatestMessageAppInterface,
+    NI: NotificationAppInterface,
+    UTI: UserTopicAppInterface,
+    TUI: TopicUserAppInterface,
+    TMI: TopicMessageAppInterface
+> MessageHandler<TAI, LTI, NI, UTI, TUI, TMI>
+{
+    /// Routes a legacy command to its handler and returns the JSON encoded result.
+    pub async fn dispatch(&self, actor: &Actor, command: &str, payload: String) -> AppResult<String> {
+        let action = match MessageModuleServices::action(command) {
+            Some(action) => action,
+            None => {
+                return Err(anyhow!(ApplicationError::invalid_field(
+                    "command",
+                    format!("unknown command {command}")
+                )))
+            }
+        };
+
+        match action {
+            MessageModuleServices::CreateTopic => self.on_create_new_topic(actor, payload).await,
+            MessageModuleServices::GetTopic => to_json(self.on_find_topic(actor, payload).await?),
+            MessageModuleServices::GetTopics => to_json(self.on_find_topics(actor, payload).await?),
+            MessageModuleServices::UpdateTopic => {
+                to_json(self.on_update_topic(actor, payload).await?)
+            }
+            MessageModuleServices::SetTopicRole => {
+                to_json(self.on_set_topic_role(actor, payload).await?)
+            }
+            MessageModuleServices::GetTopicRoles => {
+                to_json(self.on_find_topic_roles(actor, payload).await?)
+            }
+            MessageModuleServices::OpenDirectConversation => {
+                to_json(self.on_open_direct_conversation(actor, payload).await?)
+            }
+            MessageModuleServices::OpenGroupConversation => {
+                to_json(self.on_open_group_conversation(actor, payload).await?)
+            }
+            MessageModuleServices::PromoteConversation => {
+                to_json(self.on_promote_conversation(actor, payload).await?)
+            }
+            MessageModuleServices::SetTopicState => {
+                to_json(self.on_set_topic_state(actor, payload).await?)
+            }
+            MessageModuleServices::GetTopicMessages => {
+                to_json(self.on_find_topic_message(actor, payload).await?)
+            }
+            MessageModuleServices::GetThreadMessages => {
+                to_json(self.on_find_thread_messages(actor, payload).await?)
+            }
+            MessageModuleServices::PostTopicMessage => {
+                to_json(self.on_post_topic_message(actor, payload).await?)
+            }
+            MessageModuleServices::ForwardTopicMessage => {
+                to_json(self.on_forward_topic_message(actor, payload).await?)
+            }
+            MessageModuleServices::UpdateTopicMessage => {
+                to_json(self.on_update_topic_message(actor, payload).await?)
+            }
+            MessageModuleServices::DeleteTopicMessage => {
+                to_json(self.on_delete_topic_message(actor, payload).await?)
+            }
+            MessageModuleServices::PurgeTopicMessage => {
+                to_json(self.on_purge_topic_message(actor, payload).await?)
+            }
+            MessageModuleServices::MarkRead => to_json(self.on_mark_read(actor, payload).await?),
+            MessageModuleServices::GetMessageReaders => {
+                to_json(self.on_find_message_readers(actor, payload).await?)
+            }
+            MessageModuleServices::AddReaction => {
+                to_json(self.on_add_reaction(actor, payload).await?)
+            }
+            MessageModuleServices::RemoveReaction => {
+                to_json(self.on_remove_reaction(actor, payload).await?)
+            }
+            MessageModuleServices::PinMessage => {
+                to_json(self.on_pin_message(actor, payload).await?)
+            }
+            MessageModuleServices::UnpinMessage => {
+                to_json(self.on_unpin_message(actor, payload).await?)
+            }
+            MessageModuleServices::GetPinnedMessages => {
+                to_json(self.on_find_pinned_messages(actor, payload).await?)
+            }
+            MessageModuleServices::GetMessageRevisions => {
+                to_json(self.on_find_message_revisions(actor, payload).await?)
+            }
+            MessageModuleServices::GetTopicUsers => to_json(self.on_find_topic_user(actor, payload).await?),
+            MessageModuleServices::JoinTopic => to_json(self.on_join_topic(actor, payload).await?),
+            MessageModuleServices::LeaveTopic => to_json(self.on_leave_topic(actor, payload).await?),
+            MessageModuleServices::InviteTopicMember => {
+                to_json(self.on_invite_topic_member(actor, payload).await?)
+            }
+            MessageModuleServices::RespondTopicInvite => {
+                to_json(self.on_respond_topic_invite(actor, payload).await?)
+            }
+            MessageModuleServices::RemoveTopicMember => {
+                to_json(self.on_remove_topic_member(actor, payload).await?)
+            }
+            MessageModuleServices::GetTopicInvites => {
+                to_json(self.on_find_topic_invites(actor, payload).await?)
+            }
+            MessageModuleServices::SetPresence => to_json(self.on_set_presence(actor, payload).await?),
+            MessageModuleServices::GetPresence => to_json(self.on_find_presence(actor, payload).await?),
+            MessageModuleServices::GetUserTopics => to_json(self.on_find_user_topic(actor, payload).await?),
+            MessageModuleServices::GetNotifications => {
+                to_json(self.on_find_notification(actor, payload).await?)
+            }
+            MessageModuleServices::GetLatestMessages => {
+                to_json(self.on_find_latest_message(actor, payload).await?)
+            }
+            MessageModuleServices::GetUnreadSummary => {
+                to_json(self.on_find_unread_summary(actor, payload).await?)
+            }
+            MessageModuleServices::UpdateLatestMessage => {
+                to_json(self.on_update_latest_message(actor, payload).await?)
+            }
+        }
+    }
+
+    pub async fn on_create_new_topic(&self, actor: &Actor, payload: String,
+    ) -> AppResult<String> {
+        let body: RequestCreateTopic = from_json(&payload)?;
+        let result = self.create_topic(actor, body).await?;
         Ok(serde_json::to_string(&result)?)
     }
 
-    pub async fn on_find_topic<UA: TopicAppInterface>(
+    pub async fn on_find_topic(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<Page<PublicTopic>> {
+        let query: RequestGetTopicByPartitionKey = from_json(&payload)?;
+        self.find_topic(actor, query).await
+    }
+
+    pub async fn on_find_topics(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<Vec<PublicTopic>> {
-        let query: RequestGetTopicByPartitionKey = serde_json::from_str(&payload)?;
-        Ok(self.topic_app.find_topic_by_partition_key(&query).await?)
+    ) -> AppResult<Page<PublicTopic>> {
+        let query: RequestGetTopicByIndexKey = from_json(&payload)?;
+        self.find_topics_by_name(actor, query).await
     }
 
-    pub async fn update_topic<TA: TopicAppInterface>(
+    pub async fn on_update_topic(
         &self,
+        actor: &Actor,
         payload: String,
     ) -> AppResult<PublicTopic> {
-        let query: RequestUpdateTopic = serde_json::from_str(&payload)?;
-        Ok(self.topic_app.update_topic(&query).await?)
+        let query: RequestUpdateTopic = from_json(&payload)?;
+        self.update_topic(actor, query).await
     }
 
-    pub async fn on_find_notification(
+    pub async fn on_set_topic_role(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<Vec<PublicNotification>> {
-        let query: RequestGetNotificationByUsername = serde_json::from_str(&payload)?;
-        Ok(self.notification_app.find_list_notification_by_username(&query).await?)
+    ) -> AppResult<PublicTopicRole> {
+        let body: RequestSetTopicRole = from_json(&payload)?;
+        self.set_topic_role(actor, body).await
     }
 
-    pub async fn update_notification(
+    pub async fn on_find_topic_roles(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<PublicNotification> {
-        let query: RequestUpdateNotification = serde_json::from_str(&payload)?;
-        Ok(self.notification_app.update_notification(&query).await?)
+    ) -> AppResult<Page<PublicTopicRole>> {
+        let query: RequestGetRolesByTopicId = from_json(&payload)?;
+        self.find_topic_roles(actor, query).await
     }
 
-    pub async fn on_find_user_topic(
+    pub async fn on_open_direct_conversation(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<PublicDirectConversation> {
+        let body: RequestOpenDirectConversation = from_json(&payload)?;
+        self.open_direct_conversation(actor, body).await
+    }
+
+    pub async fn on_open_group_conversation(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<Vec<PublicUserTopic>> {
-        let query: RequestGetTopicsByUsername = serde_json::from_str(&payload)?;
-        Ok(self.user_topic_app.find_list_topics_by_username(&query).await?)
+    ) -> AppResult<PublicGroupConversation> {
+        let body: RequestOpenGroupConversation = from_json(&payload)?;
+        self.open_group_conversation(actor, body).await
     }
 
-    pub async fn update_user_topic(
+    pub async fn on_promote_conversation(&self, actor: &Actor, payload: String) -> AppResult<PublicTopic> {
+        let body: RequestPromoteConversation = from_json(&payload)?;
+        self.promote_conversation(actor, body).await
+    }
+
+    pub async fn on_set_topic_state(&self, actor: &Actor, payload: String) -> AppResult<PublicTopic> {
+        let body: RequestSetTopicState = from_json(&payload)?;
+        self.set_topic_state(actor, body).await
+    }
+
+    pub async fn on_find_notification(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<PublicUserTopic> {
-        let query: RequestUpdateUserTopic = serde_json::from_str(&payload)?;
-        Ok(self.user_topic_app.update_user_topic(&query).await?)
+    ) -> AppResult<Page<PublicNotification>> {
+        let query: RequestGetNotificationByUsername = from_json(&payload)?;
+        self.find_notifications(actor, query).await
+    }
+
+    pub async fn on_find_user_topic(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<Page<PublicUserTopic>> {
+        let query: RequestGetTopicsByUsername = from_json(&payload)?;
+        self.find_user_topics(actor, query).await
     }
 
     pub async fn on_find_topic_user(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<Vec<PublicTopicUser>> {
-        let query: RequestGetUsersByTopicId = serde_json::from_str(&payload)?;
-        Ok(self.topic_user_app.find_list_users_by_topic_id(&query).await?)
+    ) -> AppResult<Page<PublicTopicUser>> {
+        let query: RequestGetUsersByTopicId = from_json(&payload)?;
+        self.find_topic_users(actor, query).await
+    }
+
+    pub async fn on_join_topic(&self, actor: &Actor, payload: String) -> AppResult<PublicTopicUser> {
+        let body: RequestJoinTopic = from_json(&payload)?;
+        self.join_topic(actor, body).await
+    }
+
+    pub async fn on_leave_topic(&self, actor: &Actor, payload: String) -> AppResult<()> {
+        let body: RequestLeaveTopic = from_json(&payload)?;
+        self.leave_topic(actor, body).await
+    }
+
+    pub async fn on_invite_topic_member(&self, actor: &Actor, payload: String) -> AppResult<PublicTopicInvite> {
+        let body: RequestInviteTopicMember = from_json(&payload)?;
+        self.invite_topic_member(actor, body).await
+    }
+
+    pub async fn on_respond_topic_invite(&self, actor: &Actor, payload: String) -> AppResult<PublicTopicInvite> {
+        let body: RequestRespondTopicInvite = from_json(&payload)?;
+        self.respond_topic_invite(actor, body).await
+    }
+
+    pub async fn on_remove_topic_member(&self, actor: &Actor, payload: String) -> AppResult<()> {
+        let body: RequestRemoveTopicMember = from_json(&payload)?;
+        self.remove_topic_member(actor, body).await
     }
 
-    pub async fn update_topic_user(
+    pub async fn on_find_topic_invites(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<PublicTopicUser> {
-        let query: RequestUpdateTopicUser = serde_json::from_str(&payload)?;
-        Ok(self.topic_user_app.update_topic_user(&query).await?)
+    ) -> AppResult<Page<PublicTopicInvite>> {
+        let query: RequestGetInvitesByUsername = from_json(&payload)?;
+        self.find_topic_invites(actor, query).await
+    }
+
+    pub async fn on_set_presence(&self, actor: &Actor, payload: String) -> AppResult<PublicPresence> {
+        let body: RequestSetPresence = from_json(&payload)?;
+        self.set_presence(actor, body).await
+    }
+
+    pub async fn on_find_presence(&self, actor: &Actor, payload: String) -> AppResult<Vec<PublicPresence>> {
+        let query: RequestGetPresence = from_json(&payload)?;
+        self.find_presence(actor, query).await
     }
 
     pub async fn on_find_topic_message(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<Vec<PublicTopicMessage>> {
-        let query: RequestGetMessagesByTopicId = serde_json::from_str(&payload)?;
-        Ok(self.topic_message_app.find_list_messages_by_topic_id(&query).await?)
+    ) -> AppResult<Page<PublicTopicMessage>> {
+        let query: RequestGetMessagesByTopicId = from_json(&payload)?;
+        self.find_topic_messages(actor, query).await
     }
 
-    pub async fn update_topic_message(
+    pub async fn on_find_thread_messages(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<Page<PublicTopicMessage>> {
+        let query: RequestGetThreadMessages = from_json(&payload)?;
+        self.find_thread_messages(actor, query).await
+    }
+
+    pub async fn on_post_topic_message(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<PublicTopicMessage> {
+        let body: RequestPostTopicMessage = from_json(&payload)?;
+        self.post_topic_message(actor, body).await
+    }
+
+    pub async fn on_forward_topic_message(
         &self,
+        actor: &Actor,
         payload: String,
     ) -> AppResult<PublicTopicMessage> {
-        let query: RequestUpdateTopicMessage = serde_json::from_str(&payload)?;
-        Ok(self.topic_message_app.update_topic_message(&query).await?)
+        let req: RequestForwardTopicMessage = from_json(&payload)?;
+        self.forward_topic_message(actor, req).await
+    }
+
+    pub async fn on_update_topic_message(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<PublicTopicMessage> {
+        let query: RequestUpdateTopicMessage = from_json(&payload)?;
+        self.update_topic_message(actor, query).await
+    }
+
+    pub async fn on_delete_topic_message(&self, actor: &Actor, payload: String) -> AppResult<()> {
+        let req: RequestDeleteTopicMessage = from_json(&payload)?;
+        self.delete_topic_message(actor, req).await
+    }
+
+    pub async fn on_purge_topic_message(&self, actor: &Actor, payload: String) -> AppResult<()> {
+        let req: RequestGetTopicMessage = from_json(&payload)?;
+        self.purge_topic_message(actor, req).await
+    }
+
+    pub async fn on_mark_read(&self, actor: &Actor, payload: String) -> AppResult<PublicReadCursor> {
+        let req: RequestMarkRead = from_json(&payload)?;
+        self.mark_read(actor, req).await
+    }
+
+    pub async fn on_find_message_readers(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<Vec<PublicMessageReader>> {
+        let query: RequestGetTopicMessage = from_json(&payload)?;
+        self.find_message_readers(actor, query).await
+    }
+
+    pub async fn on_add_reaction(&self, actor: &Actor, payload: String) -> AppResult<Vec<PublicReaction>> {
+        let body: RequestReactTopicMessage = from_json(&payload)?;
+        self.add_reaction(actor, body).await
+    }
+
+    pub async fn on_remove_reaction(&self, actor: &Actor, payload: String) -> AppResult<Vec<PublicReaction>> {
+        let body: RequestReactTopicMessage = from_json(&payload)?;
+        self.remove_reaction(actor, body).await
+    }
+
+    pub async fn on_pin_message(&self, actor: &Actor, payload: String) -> AppResult<PublicPinnedMessage> {
+        let body: RequestPinTopicMessage = from_json(&payload)?;
+        self.pin_message(actor, body).await
+    }
+
+    pub async fn on_unpin_message(&self, actor: &Actor, payload: String) -> AppResult<()> {
+        let body: RequestPinTopicMessage = from_json(&payload)?;
+        self.unpin_message(actor, body).await
+    }
+
+    pub async fn on_find_pinned_messages(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<Vec<PublicPinnedMessage>> {
+        let query: RequestGetPinnedMessages = from_json(&payload)?;
+        self.find_pinned_messages(actor, query).await
+    }
+
+    pub async fn on_find_message_revisions(
+        &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<Page<PublicTopicMessageRevision>> {
+        let query: RequestGetMessageRevisions = from_json(&payload)?;
+        self.find_message_revisions(actor, query).await
     }
 
     pub async fn on_find_latest_message(
         &self,
+        actor: &Actor,
         payload: String,
-    ) -> AppResult<Vec<PublicLatestMessage>> {
-        let query: RequestGetLatestMessagesByUserId = serde_json::from_str(&payload)?;
-        Ok(self.latest_message_app.find_list_latest_messages_by_user_id(&query).await?)
+    ) -> AppResult<Page<PublicLatestMessage>> {
+        let query: RequestGetLatestMessagesByUserId = from_json(&payload)?;
+        self.find_latest_messages(actor, query).await
     }
 
-    pub async fn update_latest_message(
+    pub async fn on_find_unread_summary(
         &self,
+        actor: &Actor,
+        payload: String,
+    ) -> AppResult<PublicUnreadSummary> {
+        let query: RequestGetUnreadSummary = from_json(&payload)?;
+        self.find_unread_summary(actor, query).await
+    }
+
+    pub async fn on_update_latest_message(
+        &self,
+        actor: &Actor,
         payload: String,
     ) -> AppResult<PublicLatestMessage> {
-        let query: RequestUpdateLatestMessage = serde_json::from_str(&payload)?;
-        Ok(self.latest_message_app.update_latest_message(&query).await?)
+        let query: RequestUpdateLatestMessage = from_json(&payload)?;
+        self.update_latest_message(actor, query).await
     }
 }
 
+fn to_json<T: serde::Serialize>(value: T) -> AppResult<String> {
+    Ok(serde_json::to_string(&value)?)
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(payload: &str) -> AppResult<T> {
+    serde_json::from_str(payload)
+        .map_err(|err| anyhow!(ApplicationError::invalid_field("payload", err.to_string())))
+}
@@ -1,2 +1,3 @@
 pub mod actions;
+pub mod grpc;
 pub mod message_handler;